@@ -124,7 +124,7 @@ This section records functional behaviour, interface expectations, quality attri
 The CLI entry point coordinates reading the source file, executing the pipeline, and reporting results.
 - FR-01: The application shall require at least one positional CLI argument representing the path to a source file; missing arguments must trigger a usage message and exit code 1.
 - FR-02: The application shall read the specified file as UTF-8 text and terminate with an explanatory diagnostic when the file cannot be accessed.
-- FR-03: When the analysis pipeline completes without diagnostics, the system shall print the raw input, the lexed token stream, and the formatted AST to stdout.
+- FR-03: The system shall print only diagnostics by default; the token stream, the AST (as debug text or JSON), and the symbol table shall be printed or written to a file only when requested with `--emit`.
 - FR-04: The application shall terminate with a non-zero exit code whenever lexer, parser, or semantic diagnostics are produced.
 - FR-FUT-01: The CLI shall accept additional arguments to control linting scope (file or directory), configuration file path, and output format once lint rules are introduced (per README remaining tasks).
 
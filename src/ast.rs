@@ -1,4 +1,5 @@
 use crate::errors::Span;
+use crate::json::JsonValue;
 
 /// Represents a type descriptor in the Ballerina language.
 #[derive(Debug, Clone, PartialEq)]
@@ -13,6 +14,157 @@ pub enum TypeDescriptor {
     },
     Optional(Box<TypeDescriptor>),
     Union(Vec<TypeDescriptor>),
+    /// A record type: `record { ... }`, or `record {| ... |}` when closed.
+    Record {
+        fields: Vec<RecordField>,
+        /// Written with `{| |}`, so values have no fields beyond the listed
+        /// ones and those the rest descriptor admits.
+        closed: bool,
+        /// The `T...;` rest descriptor, typing any further fields.
+        rest: Option<Box<TypeDescriptor>>,
+    },
+    /// An object type: `[isolated|client|distinct] object { ... }`.
+    Object {
+        qualifiers: Vec<Qualifier>,
+        members: Vec<ObjectMember>,
+    },
+}
+
+/// A qualifier on a class, object type, field, or function, other than
+/// `public`, which the nodes record as `is_public`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Qualifier {
+    Private,
+    Final,
+    Isolated,
+    Readonly,
+    Distinct,
+    Client,
+    Remote,
+}
+
+impl Qualifier {
+    /// The keyword as written in source.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Qualifier::Private => "private",
+            Qualifier::Final => "final",
+            Qualifier::Isolated => "isolated",
+            Qualifier::Readonly => "readonly",
+            Qualifier::Distinct => "distinct",
+            Qualifier::Client => "client",
+            Qualifier::Remote => "remote",
+        }
+    }
+}
+
+/// A field of a class or object type: `[public|private] [final] T name;`,
+/// optionally with `= default`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ObjectField {
+    pub is_public: bool,
+    /// `private` and `final`.
+    pub qualifiers: Vec<Qualifier>,
+    pub name: String,
+    pub name_span: Span,
+    pub type_desc: TypeDescriptor,
+    pub default: Option<Expr>,
+    /// Covers the field from its first qualifier or its type to its `;`.
+    pub span: Span,
+}
+
+/// A method declared by an object type, without a body:
+/// `function name(T param) returns R;`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MethodDecl {
+    pub is_public: bool,
+    pub qualifiers: Vec<Qualifier>,
+    pub name: String,
+    pub name_span: Span,
+    pub params: Vec<(String, TypeDescriptor)>,
+    pub return_type: Option<TypeDescriptor>,
+    /// Covers the declaration from the `function` keyword to its `;`.
+    pub span: Span,
+}
+
+/// A member of an object type descriptor, in source order.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ObjectMember {
+    Field(ObjectField),
+    Method(MethodDecl),
+    /// `*T;`, including the fields and methods of object type `T`.
+    Inclusion {
+        type_name: String,
+        span: Span,
+    },
+}
+
+/// A member of a class definition or service declaration, in source order.
+#[derive(Debug)]
+pub enum ClassMember {
+    Field(ObjectField),
+    /// A method: a [`Stmt::Function`], or a [`Stmt::ResourceFunction`].
+    Method(Stmt),
+    /// `*T;`, including the fields and methods of object type `T`.
+    Inclusion {
+        type_name: String,
+        span: Span,
+    },
+}
+
+/// One segment of a resource function's path, after its accessor.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PathSegment {
+    /// A fixed segment: `users`.
+    Name { name: String, span: Span },
+    /// A path parameter matching one segment: `[string id]`.
+    Param {
+        type_desc: TypeDescriptor,
+        name: String,
+        name_span: Span,
+        span: Span,
+    },
+    /// A rest parameter matching the remaining segments: `[string... ids]`.
+    Rest {
+        type_desc: TypeDescriptor,
+        name: String,
+        name_span: Span,
+        span: Span,
+    },
+}
+
+impl PathSegment {
+    pub fn span(&self) -> &Span {
+        match self {
+            PathSegment::Name { span, .. }
+            | PathSegment::Param { span, .. }
+            | PathSegment::Rest { span, .. } => span,
+        }
+    }
+}
+
+/// Where a service is attached on its listeners.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AttachPoint {
+    /// An absolute resource path: `/api/v1`, or `/` with no segments.
+    Path { segments: Vec<String>, span: Span },
+    /// A service name given as a string literal: `"greeter"`.
+    Name { name: String, span: Span },
+}
+
+/// A field of a record type descriptor: `T name;`, `T name?;`, or
+/// `T name = default;`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordField {
+    pub name: String,
+    pub name_span: Span,
+    pub type_desc: TypeDescriptor,
+    /// Written `name?`: values may leave the field out.
+    pub optional: bool,
+    /// Value used when a record value leaves the field out.
+    pub default: Option<Expr>,
+    /// Covers the field from its type to its `;`.
+    pub span: Span,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -22,8 +174,17 @@ pub enum ArrayDimension {
     Open,     // for []
 }
 
+/// One `key: value` entry of a map literal.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MapEntry {
+    pub key: String,
+    /// The key as written, including the quotes of a string key.
+    pub key_span: Span,
+    pub value: Expr,
+}
+
 /// Represents an expression in the abstract syntax tree with precise source span information.
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 #[allow(dead_code)]
 pub enum Expr {
     /// A binary expression with a left operand, an operator, and a right operand.
@@ -57,26 +218,49 @@ pub enum Expr {
         value: Box<Expr>,
         span: Span,
     },
-    /// Member access expression (e.g., array[0] or obj.field).
+    /// Assignment to a field (e.g., self.count = 0 or self.count += 1).
+    FieldAssign {
+        object: Box<Expr>,
+        field: String,
+        field_span: Span,
+        /// `PlusAssign` or `MinusAssign` for `+=` and `-=`; `None` for `=`.
+        op: Option<BinaryOp>,
+        value: Box<Expr>,
+        span: Span,
+    },
+    /// Member access expression (e.g., array[0] or map["key"]).
     MemberAccess {
         object: Box<Expr>,
         member: Box<Expr>,
         span: Span,
     },
+    /// Field access expression (e.g., person.name).
+    FieldAccess {
+        object: Box<Expr>,
+        field: String,
+        field_span: Span,
+        span: Span,
+    },
     /// Method call expression (e.g., obj.method()).
     MethodCall {
         object: Box<Expr>,
         method: String,
+        method_span: Span,
+        arguments: Vec<Expr>,
+        span: Span,
+    },
+    /// Object construction (e.g., new Counter(0), or new (0) when the class
+    /// is given by the expected type).
+    New {
+        /// The class, possibly module-qualified (`http:Client`).
+        class_name: Option<String>,
         arguments: Vec<Expr>,
         span: Span,
     },
     /// Array literal expression.
     ArrayLiteral { elements: Vec<Expr>, span: Span },
     /// Map literal expression.
-    MapLiteral {
-        entries: Vec<(String, Expr)>,
-        span: Span,
-    },
+    MapLiteral { entries: Vec<MapEntry>, span: Span },
     /// Ternary conditional expression (condition ? true_expr : false_expr).
     Ternary {
         condition: Box<Expr>,
@@ -115,8 +299,11 @@ impl Expr {
             | Expr::Grouping { span, .. }
             | Expr::Call { span, .. }
             | Expr::Assign { span, .. }
+            | Expr::FieldAssign { span, .. }
             | Expr::MemberAccess { span, .. }
+            | Expr::FieldAccess { span, .. }
             | Expr::MethodCall { span, .. }
+            | Expr::New { span, .. }
             | Expr::ArrayLiteral { span, .. }
             | Expr::MapLiteral { span, .. }
             | Expr::Ternary { span, .. }
@@ -128,7 +315,7 @@ impl Expr {
 }
 
 /// Represents a literal value.
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 #[allow(dead_code)]
 pub enum Literal {
     /// A floating-point number.
@@ -142,7 +329,7 @@ pub enum Literal {
 }
 
 /// Represents a binary operator.
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 #[allow(dead_code)]
 pub enum BinaryOp {
     // Arithmetic
@@ -183,13 +370,17 @@ pub enum BinaryOp {
 }
 
 /// Represents a unary operator.
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 #[allow(dead_code)]
 pub enum UnaryOp {
     Bang,
     Minus,
     Plus,
     BitwiseNot,
+    /// `check`: returns an error value from the enclosing function.
+    Check,
+    /// `checkpanic`: panics on an error value.
+    CheckPanic,
 }
 
 /// Represents a statement in the abstract syntax tree.
@@ -251,6 +442,8 @@ pub enum Stmt {
     /// A function declaration statement.
     Function {
         is_public: bool,
+        /// `isolated`, and for methods `private` and `remote`.
+        qualifiers: Vec<Qualifier>,
         name: String,
         name_span: Span,
         params: Vec<(String, TypeDescriptor)>,
@@ -258,6 +451,62 @@ pub enum Stmt {
         body: Vec<Stmt>,
         span: Span,
     },
+    /// A module-level type definition (`type Person record { ... };`).
+    TypeDefinition {
+        is_public: bool,
+        name: String,
+        name_span: Span,
+        type_desc: TypeDescriptor,
+        span: Span,
+    },
+    /// A module-level class definition (`class Counter { ... }`).
+    Class {
+        is_public: bool,
+        /// `isolated`, `readonly`, `distinct`, and `client`.
+        qualifiers: Vec<Qualifier>,
+        name: String,
+        name_span: Span,
+        members: Vec<ClassMember>,
+        span: Span,
+    },
+    /// A module-level listener declaration
+    /// (`listener http:Listener ep = new (9090);`).
+    Listener {
+        is_public: bool,
+        name: String,
+        name_span: Span,
+        type_annotation: Option<TypeDescriptor>,
+        initializer: Expr,
+        span: Span,
+    },
+    /// A module-level service declaration (`service /api on ep { ... }`).
+    Service {
+        /// `isolated`.
+        qualifiers: Vec<Qualifier>,
+        /// The service type, as in `service http:Service /api on ep`.
+        type_annotation: Option<TypeDescriptor>,
+        attach_point: Option<AttachPoint>,
+        /// The listeners after `on`, usually a listener name or a `new`
+        /// expression.
+        listeners: Vec<Expr>,
+        members: Vec<ClassMember>,
+        span: Span,
+    },
+    /// A resource method of a service or client class
+    /// (`resource function get users/[string id]() returns User { ... }`).
+    ResourceFunction {
+        /// `isolated`.
+        qualifiers: Vec<Qualifier>,
+        /// The accessor, such as `get` or `post`; `default` for any.
+        accessor: String,
+        accessor_span: Span,
+        /// The segments of the resource path; empty for the root path `.`.
+        path: Vec<PathSegment>,
+        params: Vec<(String, TypeDescriptor)>,
+        return_type: Option<TypeDescriptor>,
+        body: Vec<Stmt>,
+        span: Span,
+    },
 }
 
 impl Stmt {
@@ -276,7 +525,1182 @@ impl Stmt {
             | Stmt::Foreach { span, .. }
             | Stmt::Break { span, .. }
             | Stmt::Continue { span, .. }
-            | Stmt::Function { span, .. } => span,
+            | Stmt::Function { span, .. }
+            | Stmt::TypeDefinition { span, .. }
+            | Stmt::Class { span, .. }
+            | Stmt::Listener { span, .. }
+            | Stmt::Service { span, .. }
+            | Stmt::ResourceFunction { span, .. } => span,
+        }
+    }
+}
+
+/// Builds a JSON object for a node: `{"node": kind, "span": [start, end], ...}`.
+fn json_node<'a>(
+    kind: &str,
+    span: &Span,
+    fields: impl IntoIterator<Item = (&'a str, JsonValue)>,
+) -> JsonValue {
+    let mut entries = vec![
+        ("node", kind.into()),
+        ("span", vec![span.start, span.end].into()),
+    ];
+    entries.extend(fields);
+    JsonValue::object(entries)
+}
+
+fn json_block(stmts: &[Stmt]) -> JsonValue {
+    JsonValue::Array(stmts.iter().map(Stmt::to_json).collect())
+}
+
+fn json_exprs(exprs: &[Expr]) -> JsonValue {
+    JsonValue::Array(exprs.iter().map(Expr::to_json).collect())
+}
+
+fn json_qualifiers(qualifiers: &[Qualifier]) -> JsonValue {
+    JsonValue::Array(qualifiers.iter().map(|q| q.as_str().into()).collect())
+}
+
+fn json_params(params: &[(String, TypeDescriptor)]) -> JsonValue {
+    let params = params
+        .iter()
+        .map(|(name, ty)| {
+            JsonValue::object([("name", name.as_str().into()), ("type", ty.to_json())])
+        })
+        .collect();
+    JsonValue::Array(params)
+}
+
+impl ObjectField {
+    fn to_json(&self) -> JsonValue {
+        JsonValue::object([
+            ("member", "field".into()),
+            ("public", self.is_public.into()),
+            ("qualifiers", json_qualifiers(&self.qualifiers)),
+            ("name", self.name.as_str().into()),
+            (
+                "name_span",
+                vec![self.name_span.start, self.name_span.end].into(),
+            ),
+            ("type", self.type_desc.to_json()),
+            ("default", self.default.as_ref().map(Expr::to_json).into()),
+        ])
+    }
+}
+
+fn json_inclusion(type_name: &str) -> JsonValue {
+    JsonValue::object([("member", "inclusion".into()), ("type", type_name.into())])
+}
+
+fn json_class_members(members: &[ClassMember]) -> JsonValue {
+    let members = members
+        .iter()
+        .map(|member| match member {
+            ClassMember::Field(field) => field.to_json(),
+            ClassMember::Method(method) => {
+                JsonValue::object([("member", "method".into()), ("function", method.to_json())])
+            }
+            ClassMember::Inclusion { type_name, .. } => json_inclusion(type_name),
+        })
+        .collect();
+    JsonValue::Array(members)
+}
+
+impl TypeDescriptor {
+    /// Describes the type as JSON, used by `--emit ast-json`.
+    pub fn to_json(&self) -> JsonValue {
+        match self {
+            TypeDescriptor::Basic(name) => {
+                JsonValue::object([("kind", "basic".into()), ("name", name.as_str().into())])
+            }
+            TypeDescriptor::Array {
+                element_type,
+                dimension,
+            } => {
+                let dimension = match dimension {
+                    Some(ArrayDimension::Fixed(size)) => (*size).into(),
+                    Some(ArrayDimension::Inferred) => "*".into(),
+                    Some(ArrayDimension::Open) | None => JsonValue::Null,
+                };
+                JsonValue::object([
+                    ("kind", "array".into()),
+                    ("element", element_type.to_json()),
+                    ("dimension", dimension),
+                ])
+            }
+            TypeDescriptor::Map { value_type } => {
+                JsonValue::object([("kind", "map".into()), ("value", value_type.to_json())])
+            }
+            TypeDescriptor::Optional(inner) => {
+                JsonValue::object([("kind", "optional".into()), ("inner", inner.to_json())])
+            }
+            TypeDescriptor::Union(members) => JsonValue::object([
+                ("kind", "union".into()),
+                (
+                    "members",
+                    JsonValue::Array(members.iter().map(TypeDescriptor::to_json).collect()),
+                ),
+            ]),
+            TypeDescriptor::Record {
+                fields,
+                closed,
+                rest,
+            } => {
+                let fields = fields
+                    .iter()
+                    .map(|field| {
+                        JsonValue::object([
+                            ("name", field.name.as_str().into()),
+                            (
+                                "name_span",
+                                vec![field.name_span.start, field.name_span.end].into(),
+                            ),
+                            ("type", field.type_desc.to_json()),
+                            ("optional", field.optional.into()),
+                            ("default", field.default.as_ref().map(Expr::to_json).into()),
+                        ])
+                    })
+                    .collect();
+                JsonValue::object([
+                    ("kind", "record".into()),
+                    ("closed", (*closed).into()),
+                    ("fields", JsonValue::Array(fields)),
+                    ("rest", rest.as_ref().map(|rest| rest.to_json()).into()),
+                ])
+            }
+            TypeDescriptor::Object {
+                qualifiers,
+                members,
+            } => {
+                let members = members
+                    .iter()
+                    .map(|member| match member {
+                        ObjectMember::Field(field) => field.to_json(),
+                        ObjectMember::Method(method) => JsonValue::object([
+                            ("member", "method".into()),
+                            ("public", method.is_public.into()),
+                            ("qualifiers", json_qualifiers(&method.qualifiers)),
+                            ("name", method.name.as_str().into()),
+                            (
+                                "name_span",
+                                vec![method.name_span.start, method.name_span.end].into(),
+                            ),
+                            ("params", json_params(&method.params)),
+                            (
+                                "return_type",
+                                method.return_type.as_ref().map(Self::to_json).into(),
+                            ),
+                        ]),
+                        ObjectMember::Inclusion { type_name, .. } => json_inclusion(type_name),
+                    })
+                    .collect();
+                JsonValue::object([
+                    ("kind", "object".into()),
+                    ("qualifiers", json_qualifiers(qualifiers)),
+                    ("members", JsonValue::Array(members)),
+                ])
+            }
+        }
+    }
+}
+
+impl Expr {
+    /// Describes the expression as JSON, used by `--emit ast-json`.
+    ///
+    /// Operators are named after their `BinaryOp`/`UnaryOp` variants.
+    pub fn to_json(&self) -> JsonValue {
+        match self {
+            Expr::Binary {
+                left,
+                op,
+                right,
+                span,
+            } => json_node(
+                "Binary",
+                span,
+                [
+                    ("op", format!("{op:?}").into()),
+                    ("left", left.to_json()),
+                    ("right", right.to_json()),
+                ],
+            ),
+            Expr::Unary { op, operand, span } => json_node(
+                "Unary",
+                span,
+                [
+                    ("op", format!("{op:?}").into()),
+                    ("operand", operand.to_json()),
+                ],
+            ),
+            Expr::Literal { value, span } => {
+                let value = match value {
+                    Literal::Number(number) => JsonValue::Number(*number),
+                    Literal::String(text) => text.as_str().into(),
+                    Literal::Boolean(flag) => (*flag).into(),
+                    Literal::Nil => JsonValue::Null,
+                };
+                json_node("Literal", span, [("value", value)])
+            }
+            Expr::Variable { name, span } => {
+                json_node("Variable", span, [("name", name.as_str().into())])
+            }
+            Expr::Grouping { expression, span } => {
+                json_node("Grouping", span, [("expression", expression.to_json())])
+            }
+            Expr::Call {
+                callee,
+                arguments,
+                span,
+            } => json_node(
+                "Call",
+                span,
+                [
+                    ("callee", callee.to_json()),
+                    ("arguments", json_exprs(arguments)),
+                ],
+            ),
+            Expr::Assign { name, value, span } => json_node(
+                "Assign",
+                span,
+                [("name", name.as_str().into()), ("value", value.to_json())],
+            ),
+            Expr::FieldAssign {
+                object,
+                field,
+                field_span,
+                op,
+                value,
+                span,
+            } => json_node(
+                "FieldAssign",
+                span,
+                [
+                    ("object", object.to_json()),
+                    ("field", field.as_str().into()),
+                    ("field_span", vec![field_span.start, field_span.end].into()),
+                    ("op", op.as_ref().map(|op| format!("{op:?}")).into()),
+                    ("value", value.to_json()),
+                ],
+            ),
+            Expr::MemberAccess {
+                object,
+                member,
+                span,
+            } => json_node(
+                "MemberAccess",
+                span,
+                [("object", object.to_json()), ("member", member.to_json())],
+            ),
+            Expr::FieldAccess {
+                object,
+                field,
+                field_span,
+                span,
+            } => json_node(
+                "FieldAccess",
+                span,
+                [
+                    ("object", object.to_json()),
+                    ("field", field.as_str().into()),
+                    ("field_span", vec![field_span.start, field_span.end].into()),
+                ],
+            ),
+            Expr::MethodCall {
+                object,
+                method,
+                method_span,
+                arguments,
+                span,
+            } => json_node(
+                "MethodCall",
+                span,
+                [
+                    ("object", object.to_json()),
+                    ("method", method.as_str().into()),
+                    (
+                        "method_span",
+                        vec![method_span.start, method_span.end].into(),
+                    ),
+                    ("arguments", json_exprs(arguments)),
+                ],
+            ),
+            Expr::New {
+                class_name,
+                arguments,
+                span,
+            } => json_node(
+                "New",
+                span,
+                [
+                    ("class", class_name.as_deref().map(JsonValue::from).into()),
+                    ("arguments", json_exprs(arguments)),
+                ],
+            ),
+            Expr::ArrayLiteral { elements, span } => {
+                json_node("ArrayLiteral", span, [("elements", json_exprs(elements))])
+            }
+            Expr::MapLiteral { entries, span } => {
+                let entries = entries
+                    .iter()
+                    .map(|entry| {
+                        JsonValue::object([
+                            ("key", entry.key.as_str().into()),
+                            (
+                                "key_span",
+                                vec![entry.key_span.start, entry.key_span.end].into(),
+                            ),
+                            ("value", entry.value.to_json()),
+                        ])
+                    })
+                    .collect();
+                json_node("MapLiteral", span, [("entries", JsonValue::Array(entries))])
+            }
+            Expr::Ternary {
+                condition,
+                true_expr,
+                false_expr,
+                span,
+            } => json_node(
+                "Ternary",
+                span,
+                [
+                    ("condition", condition.to_json()),
+                    ("then", true_expr.to_json()),
+                    ("else", false_expr.to_json()),
+                ],
+            ),
+            Expr::Elvis {
+                expr,
+                default,
+                span,
+            } => json_node(
+                "Elvis",
+                span,
+                [("expr", expr.to_json()), ("default", default.to_json())],
+            ),
+            Expr::Range { start, end, span } => json_node(
+                "Range",
+                span,
+                [("start", start.to_json()), ("end", end.to_json())],
+            ),
+            Expr::Cast {
+                type_desc,
+                expr,
+                span,
+            } => json_node(
+                "Cast",
+                span,
+                [("type", type_desc.to_json()), ("expr", expr.to_json())],
+            ),
+        }
+    }
+}
+
+impl Stmt {
+    /// Describes the statement as JSON, used by `--emit ast-json`.
+    ///
+    /// Every node carries its variant name under `"node"` and its byte span
+    /// as `[start, end]`.
+    pub fn to_json(&self) -> JsonValue {
+        let type_json = |ty: &Option<TypeDescriptor>| ty.as_ref().map(TypeDescriptor::to_json);
+        match self {
+            Stmt::Import { package_path, span } => {
+                json_node("Import", span, [("package", package_path.clone().into())])
+            }
+            Stmt::VarDecl {
+                is_final,
+                name,
+                name_span,
+                type_annotation,
+                initializer,
+                span,
+            } => json_node(
+                "VarDecl",
+                span,
+                [
+                    ("final", (*is_final).into()),
+                    ("name", name.as_str().into()),
+                    ("name_span", vec![name_span.start, name_span.end].into()),
+                    ("type", type_json(type_annotation).into()),
+                    (
+                        "initializer",
+                        initializer.as_ref().map(Expr::to_json).into(),
+                    ),
+                ],
+            ),
+            Stmt::ConstDecl {
+                name,
+                name_span,
+                type_annotation,
+                initializer,
+                span,
+            } => json_node(
+                "ConstDecl",
+                span,
+                [
+                    ("name", name.as_str().into()),
+                    ("name_span", vec![name_span.start, name_span.end].into()),
+                    ("type", type_json(type_annotation).into()),
+                    ("initializer", initializer.to_json()),
+                ],
+            ),
+            Stmt::Expression { expression, span } => {
+                json_node("Expression", span, [("expression", expression.to_json())])
+            }
+            Stmt::Return { value, span } => json_node(
+                "Return",
+                span,
+                [("value", value.as_ref().map(Expr::to_json).into())],
+            ),
+            Stmt::Panic { value, span } => json_node("Panic", span, [("value", value.to_json())]),
+            Stmt::If {
+                condition,
+                then_branch,
+                else_branch,
+                span,
+            } => json_node(
+                "If",
+                span,
+                [
+                    ("condition", condition.to_json()),
+                    ("then", json_block(then_branch)),
+                    ("else", else_branch.as_deref().map(json_block).into()),
+                ],
+            ),
+            Stmt::While {
+                condition,
+                body,
+                span,
+            } => json_node(
+                "While",
+                span,
+                [
+                    ("condition", condition.to_json()),
+                    ("body", json_block(body)),
+                ],
+            ),
+            Stmt::Foreach {
+                type_annotation,
+                variable,
+                iterable,
+                body,
+                span,
+            } => json_node(
+                "Foreach",
+                span,
+                [
+                    ("type", type_json(type_annotation).into()),
+                    ("variable", variable.as_str().into()),
+                    ("iterable", iterable.to_json()),
+                    ("body", json_block(body)),
+                ],
+            ),
+            Stmt::Break { span } => json_node("Break", span, []),
+            Stmt::Continue { span } => json_node("Continue", span, []),
+            Stmt::Function {
+                is_public,
+                qualifiers,
+                name,
+                name_span,
+                params,
+                return_type,
+                body,
+                span,
+            } => json_node(
+                "Function",
+                span,
+                [
+                    ("public", (*is_public).into()),
+                    ("qualifiers", json_qualifiers(qualifiers)),
+                    ("name", name.as_str().into()),
+                    ("name_span", vec![name_span.start, name_span.end].into()),
+                    ("params", json_params(params)),
+                    ("return_type", type_json(return_type).into()),
+                    ("body", json_block(body)),
+                ],
+            ),
+            Stmt::TypeDefinition {
+                is_public,
+                name,
+                name_span,
+                type_desc,
+                span,
+            } => json_node(
+                "TypeDefinition",
+                span,
+                [
+                    ("public", (*is_public).into()),
+                    ("name", name.as_str().into()),
+                    ("name_span", vec![name_span.start, name_span.end].into()),
+                    ("type", type_desc.to_json()),
+                ],
+            ),
+            Stmt::Class {
+                is_public,
+                qualifiers,
+                name,
+                name_span,
+                members,
+                span,
+            } => json_node(
+                "Class",
+                span,
+                [
+                    ("public", (*is_public).into()),
+                    ("qualifiers", json_qualifiers(qualifiers)),
+                    ("name", name.as_str().into()),
+                    ("name_span", vec![name_span.start, name_span.end].into()),
+                    ("members", json_class_members(members)),
+                ],
+            ),
+            Stmt::Listener {
+                is_public,
+                name,
+                name_span,
+                type_annotation,
+                initializer,
+                span,
+            } => json_node(
+                "Listener",
+                span,
+                [
+                    ("public", (*is_public).into()),
+                    ("name", name.as_str().into()),
+                    ("name_span", vec![name_span.start, name_span.end].into()),
+                    ("type", type_json(type_annotation).into()),
+                    ("initializer", initializer.to_json()),
+                ],
+            ),
+            Stmt::Service {
+                qualifiers,
+                type_annotation,
+                attach_point,
+                listeners,
+                members,
+                span,
+            } => {
+                let attach_point = attach_point
+                    .as_ref()
+                    .map(|attach_point| match attach_point {
+                        AttachPoint::Path { segments, span } => JsonValue::object([
+                            ("kind", "path".into()),
+                            ("segments", segments.clone().into()),
+                            ("span", vec![span.start, span.end].into()),
+                        ]),
+                        AttachPoint::Name { name, span } => JsonValue::object([
+                            ("kind", "name".into()),
+                            ("name", name.as_str().into()),
+                            ("span", vec![span.start, span.end].into()),
+                        ]),
+                    });
+                json_node(
+                    "Service",
+                    span,
+                    [
+                        ("qualifiers", json_qualifiers(qualifiers)),
+                        ("type", type_json(type_annotation).into()),
+                        ("attach_point", attach_point.into()),
+                        ("listeners", json_exprs(listeners)),
+                        ("members", json_class_members(members)),
+                    ],
+                )
+            }
+            Stmt::ResourceFunction {
+                qualifiers,
+                accessor,
+                accessor_span,
+                path,
+                params,
+                return_type,
+                body,
+                span,
+            } => {
+                let path = path
+                    .iter()
+                    .map(|segment| match segment {
+                        PathSegment::Name { name, span } => JsonValue::object([
+                            ("kind", "name".into()),
+                            ("name", name.as_str().into()),
+                            ("span", vec![span.start, span.end].into()),
+                        ]),
+                        PathSegment::Param {
+                            type_desc,
+                            name,
+                            span,
+                            ..
+                        }
+                        | PathSegment::Rest {
+                            type_desc,
+                            name,
+                            span,
+                            ..
+                        } => JsonValue::object([
+                            (
+                                "kind",
+                                if matches!(segment, PathSegment::Rest { .. }) {
+                                    "rest".into()
+                                } else {
+                                    "param".into()
+                                },
+                            ),
+                            ("name", name.as_str().into()),
+                            ("type", type_desc.to_json()),
+                            ("span", vec![span.start, span.end].into()),
+                        ]),
+                    })
+                    .collect();
+                json_node(
+                    "ResourceFunction",
+                    span,
+                    [
+                        ("qualifiers", json_qualifiers(qualifiers)),
+                        ("accessor", accessor.as_str().into()),
+                        (
+                            "accessor_span",
+                            vec![accessor_span.start, accessor_span.end].into(),
+                        ),
+                        ("path", JsonValue::Array(path)),
+                        ("params", json_params(params)),
+                        ("return_type", type_json(return_type).into()),
+                        ("body", json_block(body)),
+                    ],
+                )
+            }
+        }
+    }
+}
+
+/// The kind of a [`Stmt`] or [`Expr`] node, without its contents.
+///
+/// Lint rules list the kinds they want to inspect; see
+/// [`crate::linter::Rule::node_kinds`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NodeKind {
+    // Statements
+    Import,
+    VarDecl,
+    ConstDecl,
+    /// An expression used as a statement ([`Stmt::Expression`]).
+    ExpressionStmt,
+    Return,
+    Panic,
+    If,
+    While,
+    Foreach,
+    Break,
+    Continue,
+    Function,
+    TypeDefinition,
+    Class,
+    Listener,
+    Service,
+    ResourceFunction,
+
+    // Expressions
+    Binary,
+    Unary,
+    Literal,
+    Variable,
+    Grouping,
+    Call,
+    Assign,
+    FieldAssign,
+    MemberAccess,
+    FieldAccess,
+    MethodCall,
+    New,
+    ArrayLiteral,
+    MapLiteral,
+    Ternary,
+    Elvis,
+    Range,
+    Cast,
+}
+
+impl NodeKind {
+    /// Every statement kind.
+    pub const STATEMENTS: &'static [NodeKind] = &[
+        NodeKind::Import,
+        NodeKind::VarDecl,
+        NodeKind::ConstDecl,
+        NodeKind::ExpressionStmt,
+        NodeKind::Return,
+        NodeKind::Panic,
+        NodeKind::If,
+        NodeKind::While,
+        NodeKind::Foreach,
+        NodeKind::Break,
+        NodeKind::Continue,
+        NodeKind::Function,
+        NodeKind::TypeDefinition,
+        NodeKind::Class,
+        NodeKind::Listener,
+        NodeKind::Service,
+        NodeKind::ResourceFunction,
+    ];
+
+    /// Every expression kind.
+    pub const EXPRESSIONS: &'static [NodeKind] = &[
+        NodeKind::Binary,
+        NodeKind::Unary,
+        NodeKind::Literal,
+        NodeKind::Variable,
+        NodeKind::Grouping,
+        NodeKind::Call,
+        NodeKind::Assign,
+        NodeKind::FieldAssign,
+        NodeKind::MemberAccess,
+        NodeKind::FieldAccess,
+        NodeKind::MethodCall,
+        NodeKind::New,
+        NodeKind::ArrayLiteral,
+        NodeKind::MapLiteral,
+        NodeKind::Ternary,
+        NodeKind::Elvis,
+        NodeKind::Range,
+        NodeKind::Cast,
+    ];
+}
+
+impl Stmt {
+    /// Returns the statement's [`NodeKind`].
+    pub fn kind(&self) -> NodeKind {
+        match self {
+            Stmt::Import { .. } => NodeKind::Import,
+            Stmt::VarDecl { .. } => NodeKind::VarDecl,
+            Stmt::ConstDecl { .. } => NodeKind::ConstDecl,
+            Stmt::Expression { .. } => NodeKind::ExpressionStmt,
+            Stmt::Return { .. } => NodeKind::Return,
+            Stmt::Panic { .. } => NodeKind::Panic,
+            Stmt::If { .. } => NodeKind::If,
+            Stmt::While { .. } => NodeKind::While,
+            Stmt::Foreach { .. } => NodeKind::Foreach,
+            Stmt::Break { .. } => NodeKind::Break,
+            Stmt::Continue { .. } => NodeKind::Continue,
+            Stmt::Function { .. } => NodeKind::Function,
+            Stmt::TypeDefinition { .. } => NodeKind::TypeDefinition,
+            Stmt::Class { .. } => NodeKind::Class,
+            Stmt::Listener { .. } => NodeKind::Listener,
+            Stmt::Service { .. } => NodeKind::Service,
+            Stmt::ResourceFunction { .. } => NodeKind::ResourceFunction,
+        }
+    }
+}
+
+impl Expr {
+    /// Returns the expression's [`NodeKind`].
+    pub fn kind(&self) -> NodeKind {
+        match self {
+            Expr::Binary { .. } => NodeKind::Binary,
+            Expr::Unary { .. } => NodeKind::Unary,
+            Expr::Literal { .. } => NodeKind::Literal,
+            Expr::Variable { .. } => NodeKind::Variable,
+            Expr::Grouping { .. } => NodeKind::Grouping,
+            Expr::Call { .. } => NodeKind::Call,
+            Expr::Assign { .. } => NodeKind::Assign,
+            Expr::FieldAssign { .. } => NodeKind::FieldAssign,
+            Expr::MemberAccess { .. } => NodeKind::MemberAccess,
+            Expr::FieldAccess { .. } => NodeKind::FieldAccess,
+            Expr::MethodCall { .. } => NodeKind::MethodCall,
+            Expr::New { .. } => NodeKind::New,
+            Expr::ArrayLiteral { .. } => NodeKind::ArrayLiteral,
+            Expr::MapLiteral { .. } => NodeKind::MapLiteral,
+            Expr::Ternary { .. } => NodeKind::Ternary,
+            Expr::Elvis { .. } => NodeKind::Elvis,
+            Expr::Range { .. } => NodeKind::Range,
+            Expr::Cast { .. } => NodeKind::Cast,
+        }
+    }
+}
+
+/// A borrowed statement or expression.
+#[derive(Debug, Clone, Copy)]
+pub enum Node<'a> {
+    Stmt(&'a Stmt),
+    Expr(&'a Expr),
+}
+
+impl<'a> Node<'a> {
+    pub fn kind(&self) -> NodeKind {
+        match self {
+            Node::Stmt(stmt) => stmt.kind(),
+            Node::Expr(expr) => expr.kind(),
+        }
+    }
+
+    pub fn span(&self) -> &'a Span {
+        match self {
+            Node::Stmt(stmt) => stmt.span(),
+            Node::Expr(expr) => expr.span(),
+        }
+    }
+}
+
+/// Where [`walk`] currently is in the tree.
+///
+/// Passed to every [`Visitor`] hook; the node being visited is not one of its
+/// own ancestors.
+#[derive(Debug, Default)]
+pub struct VisitContext<'a> {
+    /// Nodes from the outermost statement down to the current node's parent.
+    ancestors: Vec<Node<'a>>,
+    /// Statements owning the enclosing blocks, outermost first.
+    scopes: Vec<&'a Stmt>,
+}
+
+impl<'a> VisitContext<'a> {
+    /// The node directly containing the current one; `None` at the top level.
+    pub fn parent(&self) -> Option<Node<'a>> {
+        self.ancestors.last().copied()
+    }
+
+    /// The enclosing nodes, innermost first.
+    pub fn ancestors(&self) -> impl Iterator<Item = Node<'a>> + '_ {
+        self.ancestors.iter().rev().copied()
+    }
+
+    /// Returns true for statements directly at module level.
+    pub fn is_top_level(&self) -> bool {
+        self.ancestors.is_empty()
+    }
+
+    /// The statement owning the innermost enclosing block (a function, `if`
+    /// branch, or loop body); `None` at module level.
+    pub fn scope(&self) -> Option<&'a Stmt> {
+        self.scopes.last().copied()
+    }
+
+    /// Number of blocks enclosing the current node; 0 at module level.
+    pub fn scope_depth(&self) -> usize {
+        self.scopes.len()
+    }
+
+    /// The innermost function or resource function the current node is part
+    /// of.
+    pub fn enclosing_function(&self) -> Option<&'a Stmt> {
+        self.ancestors().find_map(|node| match node {
+            Node::Stmt(stmt @ (Stmt::Function { .. } | Stmt::ResourceFunction { .. })) => {
+                Some(stmt)
+            }
+            _ => None,
+        })
+    }
+}
+
+/// Callbacks for [`walk`].
+///
+/// `enter_*` runs before a node's children are visited and `exit_*` after;
+/// every hook has a default that does nothing, so visitors implement only
+/// what they need and match on the variants they care about.
+pub trait Visitor<'a> {
+    fn enter_stmt(&mut self, _stmt: &'a Stmt, _cx: &VisitContext<'a>) {}
+
+    fn exit_stmt(&mut self, _stmt: &'a Stmt, _cx: &VisitContext<'a>) {}
+
+    fn enter_expr(&mut self, _expr: &'a Expr, _cx: &VisitContext<'a>) {}
+
+    fn exit_expr(&mut self, _expr: &'a Expr, _cx: &VisitContext<'a>) {}
+}
+
+/// Visits every statement and expression in `program`, depth-first and in
+/// source order.
+///
+/// Children are visited in the order they appear: an `if` visits its
+/// condition, then the `then` branch, then the `else` branch.
+pub fn walk<'a>(program: &'a [Stmt], visitor: &mut impl Visitor<'a>) {
+    let mut cx = VisitContext::default();
+    for stmt in program {
+        walk_stmt(stmt, visitor, &mut cx);
+    }
+}
+
+fn walk_stmt<'a>(stmt: &'a Stmt, visitor: &mut impl Visitor<'a>, cx: &mut VisitContext<'a>) {
+    visitor.enter_stmt(stmt, cx);
+    cx.ancestors.push(Node::Stmt(stmt));
+    match stmt {
+        Stmt::VarDecl {
+            type_annotation,
+            initializer,
+            ..
+        } => {
+            if let Some(type_annotation) = type_annotation {
+                walk_type(type_annotation, visitor, cx);
+            }
+            if let Some(initializer) = initializer {
+                walk_expr(initializer, visitor, cx);
+            }
+        }
+        Stmt::ConstDecl { initializer, .. } => walk_expr(initializer, visitor, cx),
+        Stmt::Expression { expression, .. } => walk_expr(expression, visitor, cx),
+        Stmt::Return { value, .. } => {
+            if let Some(value) = value {
+                walk_expr(value, visitor, cx);
+            }
+        }
+        Stmt::Panic { value, .. } => walk_expr(value, visitor, cx),
+        Stmt::If {
+            condition,
+            then_branch,
+            else_branch,
+            ..
+        } => {
+            walk_expr(condition, visitor, cx);
+            walk_block(stmt, then_branch, visitor, cx);
+            if let Some(else_branch) = else_branch {
+                walk_block(stmt, else_branch, visitor, cx);
+            }
+        }
+        Stmt::While {
+            condition, body, ..
+        } => {
+            walk_expr(condition, visitor, cx);
+            walk_block(stmt, body, visitor, cx);
+        }
+        Stmt::Foreach {
+            type_annotation,
+            iterable,
+            body,
+            ..
+        } => {
+            if let Some(type_annotation) = type_annotation {
+                walk_type(type_annotation, visitor, cx);
+            }
+            walk_expr(iterable, visitor, cx);
+            walk_block(stmt, body, visitor, cx);
+        }
+        Stmt::Function {
+            params,
+            return_type,
+            body,
+            ..
+        } => {
+            for (_, param_type) in params {
+                walk_type(param_type, visitor, cx);
+            }
+            if let Some(return_type) = return_type {
+                walk_type(return_type, visitor, cx);
+            }
+            walk_block(stmt, body, visitor, cx);
+        }
+        Stmt::TypeDefinition { type_desc, .. } => walk_type(type_desc, visitor, cx),
+        Stmt::Class { members, .. } => walk_members(members, visitor, cx),
+        Stmt::Listener {
+            type_annotation,
+            initializer,
+            ..
+        } => {
+            if let Some(type_annotation) = type_annotation {
+                walk_type(type_annotation, visitor, cx);
+            }
+            walk_expr(initializer, visitor, cx);
+        }
+        Stmt::Service {
+            type_annotation,
+            listeners,
+            members,
+            ..
+        } => {
+            if let Some(type_annotation) = type_annotation {
+                walk_type(type_annotation, visitor, cx);
+            }
+            for listener in listeners {
+                walk_expr(listener, visitor, cx);
+            }
+            walk_members(members, visitor, cx);
+        }
+        Stmt::ResourceFunction {
+            path,
+            params,
+            return_type,
+            body,
+            ..
+        } => {
+            for segment in path {
+                if let PathSegment::Param { type_desc, .. } | PathSegment::Rest { type_desc, .. } =
+                    segment
+                {
+                    walk_type(type_desc, visitor, cx);
+                }
+            }
+            for (_, param_type) in params {
+                walk_type(param_type, visitor, cx);
+            }
+            if let Some(return_type) = return_type {
+                walk_type(return_type, visitor, cx);
+            }
+            walk_block(stmt, body, visitor, cx);
+        }
+        Stmt::Import { .. } | Stmt::Break { .. } | Stmt::Continue { .. } => {}
+    }
+    cx.ancestors.pop();
+    visitor.exit_stmt(stmt, cx);
+}
+
+/// Visits a block owned by `owner`, which opens a new scope.
+fn walk_block<'a>(
+    owner: &'a Stmt,
+    body: &'a [Stmt],
+    visitor: &mut impl Visitor<'a>,
+    cx: &mut VisitContext<'a>,
+) {
+    cx.scopes.push(owner);
+    for stmt in body {
+        walk_stmt(stmt, visitor, cx);
+    }
+    cx.scopes.pop();
+}
+
+/// Visits the default values of record and object fields within a type
+/// descriptor.
+fn walk_type<'a>(
+    type_desc: &'a TypeDescriptor,
+    visitor: &mut impl Visitor<'a>,
+    cx: &mut VisitContext<'a>,
+) {
+    match type_desc {
+        TypeDescriptor::Basic(_) => {}
+        TypeDescriptor::Array { element_type, .. } => walk_type(element_type, visitor, cx),
+        TypeDescriptor::Map { value_type } => walk_type(value_type, visitor, cx),
+        TypeDescriptor::Optional(inner) => walk_type(inner, visitor, cx),
+        TypeDescriptor::Union(members) => {
+            for member in members {
+                walk_type(member, visitor, cx);
+            }
+        }
+        TypeDescriptor::Record { fields, rest, .. } => {
+            for field in fields {
+                walk_type(&field.type_desc, visitor, cx);
+                if let Some(default) = &field.default {
+                    walk_expr(default, visitor, cx);
+                }
+            }
+            if let Some(rest) = rest {
+                walk_type(rest, visitor, cx);
+            }
+        }
+        TypeDescriptor::Object { members, .. } => {
+            for member in members {
+                match member {
+                    ObjectMember::Field(field) => walk_field(field, visitor, cx),
+                    ObjectMember::Method(method) => {
+                        for (_, param_type) in &method.params {
+                            walk_type(param_type, visitor, cx);
+                        }
+                        if let Some(return_type) = &method.return_type {
+                            walk_type(return_type, visitor, cx);
+                        }
+                    }
+                    ObjectMember::Inclusion { .. } => {}
+                }
+            }
+        }
+    }
+}
+
+/// Visits the members of a class or service in order.
+fn walk_members<'a>(
+    members: &'a [ClassMember],
+    visitor: &mut impl Visitor<'a>,
+    cx: &mut VisitContext<'a>,
+) {
+    for member in members {
+        match member {
+            ClassMember::Field(field) => walk_field(field, visitor, cx),
+            ClassMember::Method(method) => walk_stmt(method, visitor, cx),
+            ClassMember::Inclusion { .. } => {}
+        }
+    }
+}
+
+/// Visits the type and default value of a class or object field.
+fn walk_field<'a>(
+    field: &'a ObjectField,
+    visitor: &mut impl Visitor<'a>,
+    cx: &mut VisitContext<'a>,
+) {
+    walk_type(&field.type_desc, visitor, cx);
+    if let Some(default) = &field.default {
+        walk_expr(default, visitor, cx);
+    }
+}
+
+fn walk_expr<'a>(expr: &'a Expr, visitor: &mut impl Visitor<'a>, cx: &mut VisitContext<'a>) {
+    visitor.enter_expr(expr, cx);
+    cx.ancestors.push(Node::Expr(expr));
+    match expr {
+        Expr::Binary { left, right, .. } => {
+            walk_expr(left, visitor, cx);
+            walk_expr(right, visitor, cx);
+        }
+        Expr::Unary { operand, .. } => walk_expr(operand, visitor, cx),
+        Expr::Grouping { expression, .. } => walk_expr(expression, visitor, cx),
+        Expr::Call {
+            callee, arguments, ..
+        } => {
+            walk_expr(callee, visitor, cx);
+            for argument in arguments {
+                walk_expr(argument, visitor, cx);
+            }
+        }
+        Expr::Assign { value, .. } => walk_expr(value, visitor, cx),
+        Expr::FieldAssign { object, value, .. } => {
+            walk_expr(object, visitor, cx);
+            walk_expr(value, visitor, cx);
+        }
+        Expr::MemberAccess { object, member, .. } => {
+            walk_expr(object, visitor, cx);
+            walk_expr(member, visitor, cx);
+        }
+        Expr::FieldAccess { object, .. } => walk_expr(object, visitor, cx),
+        Expr::MethodCall {
+            object, arguments, ..
+        } => {
+            walk_expr(object, visitor, cx);
+            for argument in arguments {
+                walk_expr(argument, visitor, cx);
+            }
+        }
+        Expr::New { arguments, .. } => {
+            for argument in arguments {
+                walk_expr(argument, visitor, cx);
+            }
+        }
+        Expr::ArrayLiteral { elements, .. } => {
+            for element in elements {
+                walk_expr(element, visitor, cx);
+            }
+        }
+        Expr::MapLiteral { entries, .. } => {
+            for entry in entries {
+                walk_expr(&entry.value, visitor, cx);
+            }
+        }
+        Expr::Ternary {
+            condition,
+            true_expr,
+            false_expr,
+            ..
+        } => {
+            walk_expr(condition, visitor, cx);
+            walk_expr(true_expr, visitor, cx);
+            walk_expr(false_expr, visitor, cx);
+        }
+        Expr::Elvis { expr, default, .. } => {
+            walk_expr(expr, visitor, cx);
+            walk_expr(default, visitor, cx);
+        }
+        Expr::Range { start, end, .. } => {
+            walk_expr(start, visitor, cx);
+            walk_expr(end, visitor, cx);
+        }
+        Expr::Cast {
+            type_desc, expr, ..
+        } => {
+            walk_type(type_desc, visitor, cx);
+            walk_expr(expr, visitor, cx);
         }
+        Expr::Literal { .. } | Expr::Variable { .. } => {}
     }
+    cx.ancestors.pop();
+    visitor.exit_expr(expr, cx);
 }
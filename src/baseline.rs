@@ -0,0 +1,254 @@
+//! Baseline files, for adopting the linter on code that already has findings.
+//!
+//! A baseline records the diagnostics a codebase produces today so that later
+//! runs report only new ones. Each finding is keyed by its file, its rule (or
+//! diagnostic code, for problems outside the lint rules), and a fingerprint of
+//! the code around it. The fingerprint hashes the text of the lines the span
+//! covers and the spanned text itself, with whitespace runs collapsed, but not
+//! the position: adding or removing lines elsewhere in the file, or
+//! re-indenting, keeps a finding baselined, while editing the offending line
+//! makes it new again.
+//!
+//! Identical findings are counted, so a baseline holding two copies of a key
+//! absorbs two matching diagnostics and reports a third. The file is JSON:
+//!
+//! ```json
+//! {
+//!   "version": 1,
+//!   "entries": [
+//!     {
+//!       "file": "main.bal",
+//!       "rule": "camel_case",
+//!       "fingerprint": "5be8a6a0b3d1c9f2",
+//!       "count": 1,
+//!       "message": "Variable \"bad_name\" is not in camelCase."
+//!     }
+//!   ]
+//! }
+//! ```
+//!
+//! `message` is written for readers of the file and ignored when loading.
+
+use crate::discovery::normalize_file_name;
+use crate::errors::Diagnostic;
+use crate::json::JsonValue;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Version written to, and required of, baseline files.
+pub const BASELINE_VERSION: usize = 1;
+
+/// What identifies a finding across runs.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+struct Key {
+    file: String,
+    rule: String,
+    fingerprint: String,
+}
+
+/// How many findings share a key, and a message to show for them.
+#[derive(Debug, Clone)]
+struct Entry {
+    count: usize,
+    message: String,
+}
+
+/// Recorded findings, keyed by file, rule, and fingerprint.
+#[derive(Debug, Clone, Default)]
+pub struct Baseline {
+    entries: BTreeMap<Key, Entry>,
+}
+
+impl Baseline {
+    /// Records `diagnostic`, found in `source` of the file named `file`.
+    pub fn add(&mut self, file: &str, source: &str, diagnostic: &Diagnostic) {
+        self.entries
+            .entry(key(file, source, diagnostic))
+            .or_insert_with(|| Entry {
+                count: 0,
+                message: diagnostic.message.clone(),
+            })
+            .count += 1;
+    }
+
+    /// Total number of findings recorded.
+    pub fn len(&self) -> usize {
+        self.entries.values().map(|entry| entry.count).sum()
+    }
+
+    /// Whether no findings remain recorded, counting entries used up by
+    /// [`Baseline::partition`] as gone.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Splits the diagnostics of one file into those the baseline does not
+    /// cover and those it does, using up the matched entries so each recorded
+    /// finding absorbs at most one diagnostic.
+    pub fn partition(
+        &mut self,
+        file: &str,
+        source: &str,
+        diagnostics: Vec<Diagnostic>,
+    ) -> (Vec<Diagnostic>, Vec<Diagnostic>) {
+        let mut new = Vec::new();
+        let mut baselined = Vec::new();
+        for diagnostic in diagnostics {
+            match self.entries.get_mut(&key(file, source, &diagnostic)) {
+                Some(entry) if entry.count > 0 => {
+                    entry.count -= 1;
+                    baselined.push(diagnostic);
+                }
+                _ => new.push(diagnostic),
+            }
+        }
+        (new, baselined)
+    }
+
+    /// Parses a baseline file's contents.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let document = JsonValue::parse(text)?;
+        match document.get("version").and_then(JsonValue::as_usize) {
+            Some(BASELINE_VERSION) => {}
+            Some(version) => return Err(format!("unsupported baseline version {version}")),
+            None => return Err("missing baseline version".to_string()),
+        }
+        let entries = document
+            .get("entries")
+            .and_then(JsonValue::as_array)
+            .ok_or("missing baseline entries")?;
+
+        let mut baseline = Baseline::default();
+        for (index, value) in entries.iter().enumerate() {
+            let field = |name: &str| {
+                value
+                    .get(name)
+                    .and_then(JsonValue::as_str)
+                    .map(str::to_string)
+                    .ok_or_else(|| format!("entry {index} has no '{name}'"))
+            };
+            let key = Key {
+                file: field("file")?,
+                rule: field("rule")?,
+                fingerprint: field("fingerprint")?,
+            };
+            let count = value
+                .get("count")
+                .and_then(JsonValue::as_usize)
+                .ok_or_else(|| format!("entry {index} has no 'count'"))?;
+            if count == 0 {
+                continue;
+            }
+            let message = field("message").unwrap_or_default();
+            baseline
+                .entries
+                .entry(key)
+                .or_insert(Entry { count: 0, message })
+                .count += count;
+        }
+        Ok(baseline)
+    }
+
+    /// Reads and parses the baseline file at `path`. Malformed files are
+    /// reported as [`io::ErrorKind::InvalidData`].
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let text = fs::read_to_string(path)?;
+        Baseline::parse(&text)
+            .map_err(|message| io::Error::new(io::ErrorKind::InvalidData, message))
+    }
+
+    /// The baseline as a JSON document, entries sorted by key.
+    pub fn to_json(&self) -> JsonValue {
+        let entries = self
+            .entries
+            .iter()
+            .map(|(key, entry)| {
+                JsonValue::object([
+                    ("file", key.file.as_str().into()),
+                    ("rule", key.rule.as_str().into()),
+                    ("fingerprint", key.fingerprint.as_str().into()),
+                    ("count", entry.count.into()),
+                    ("message", entry.message.as_str().into()),
+                ])
+            })
+            .collect();
+        JsonValue::object([
+            ("version", BASELINE_VERSION.into()),
+            ("entries", JsonValue::Array(entries)),
+        ])
+    }
+
+    /// Writes the baseline to `path`.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        fs::write(path, self.to_json().to_pretty_string() + "\n")
+    }
+}
+
+fn key(file: &str, source: &str, diagnostic: &Diagnostic) -> Key {
+    Key {
+        file: normalize_file_name(file),
+        rule: rule_key(diagnostic),
+        fingerprint: fingerprint(source, diagnostic),
+    }
+}
+
+/// The rule name, else the diagnostic code, else the diagnostic kind.
+fn rule_key(diagnostic: &Diagnostic) -> String {
+    diagnostic
+        .rule
+        .clone()
+        .or_else(|| diagnostic.code.map(str::to_string))
+        .unwrap_or_else(|| diagnostic.kind.as_str().to_string())
+}
+
+/// Hashes the lines `diagnostic` spans and the spanned text, ignoring
+/// whitespace differences and where in the file they are.
+pub fn fingerprint(source: &str, diagnostic: &Diagnostic) -> String {
+    let bytes = source.as_bytes();
+    let start = diagnostic.span.start.min(bytes.len());
+    let end = diagnostic.span.end.clamp(start, bytes.len());
+    let last = if end > start { end - 1 } else { start };
+
+    let line_start = bytes[..start]
+        .iter()
+        .rposition(|&byte| byte == b'\n')
+        .map_or(0, |newline| newline + 1);
+    let line_end = bytes[last.min(bytes.len())..]
+        .iter()
+        .position(|&byte| byte == b'\n')
+        .map_or(bytes.len(), |newline| last + newline);
+
+    let lines = String::from_utf8_lossy(&bytes[line_start..line_end.max(line_start)]);
+    let spanned = String::from_utf8_lossy(&bytes[start..end]);
+
+    let mut hash = Fnv1a::default();
+    hash.write(&collapse_whitespace(&lines));
+    hash.write("\0");
+    hash.write(&collapse_whitespace(&spanned));
+    format!("{:016x}", hash.0)
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// 64-bit FNV-1a, used because its output is fixed across Rust releases and
+/// platforms, unlike [`std::collections::hash_map::DefaultHasher`].
+struct Fnv1a(u64);
+
+impl Default for Fnv1a {
+    fn default() -> Self {
+        Fnv1a(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Fnv1a {
+    fn write(&mut self, text: &str) {
+        for &byte in text.as_bytes() {
+            self.0 ^= u64::from(byte);
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+}
@@ -0,0 +1,986 @@
+//! Command-line handling for the `blazelint` binary.
+//!
+//! Argument parsing is hand-rolled to keep the binary dependency-free; every
+//! option is documented in [`USAGE`].
+
+use blazelint::baseline::Baseline;
+use blazelint::codes;
+use blazelint::config::Config;
+use blazelint::diff::ChangedLines;
+use blazelint::discovery::{collect_files, IgnoreSet};
+use blazelint::errors::{Diagnostic, Severity};
+use blazelint::fix::{apply_fixes, unified_diff};
+use blazelint::format::{format_source, unformatted_diagnostic, FormatOptions};
+use blazelint::linter::{default_rules, RuleSet};
+use blazelint::lsp;
+use blazelint::report::{
+    compute_line_starts, json_report, print_diagnostics, print_rules, rules_json, sarif_report,
+    FileDiagnostics,
+};
+use blazelint::{lint_source, LintOptions, LintReport};
+
+use crate::emit::{EmitRequest, Emitter};
+use std::collections::HashMap;
+use std::fs;
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+
+/// Help text printed for `--help` and on usage errors.
+pub const USAGE: &str = "\
+Usage: blazelint [OPTIONS] <PATH>...
+       blazelint [OPTIONS] --stdin [--stdin-filename <PATH>]
+       blazelint --explain <CODE>
+       blazelint --list-rules [--format <FORMAT>]
+       blazelint fmt [--check] [--color <WHEN>] [--config <FILE>] [--ignore <GLOB>]... <PATH>...
+       blazelint lsp
+
+Lints Ballerina source files. Each PATH may be a `.bal` file or a directory,
+which is searched recursively for `.bal` files. `-` (or --stdin) lints the
+source piped to stdin instead, for editors and hooks. `blazelint fmt` instead
+rewrites the files in the standard style, wrapping lines at the line_length
+rule's max_length; with --check it changes nothing and reports each file that
+is not formatted as an error. `blazelint lsp` runs a language server for
+editors, speaking LSP over stdin and stdout.
+
+Options:
+  --config <FILE>       Use FILE instead of discovering the nearest .blazerc
+  --stdin               Lint source read from stdin (same as the path `-`)
+  --stdin-filename <PATH>
+                        Name the stdin source PATH in the output and discover
+                        its .blazerc from PATH's directory
+  --ignore <GLOB>       Skip files and directories matching GLOB (repeatable)
+  --deny-warnings       Exit with a failure status when any warning is reported
+  --max-warnings <N>    Exit with a failure status when more than N warnings are reported
+  --baseline <FILE>     Report only findings not recorded in the baseline FILE
+  --write-baseline      Record every current finding in the --baseline FILE
+                        instead of reporting them
+  --diff <FILE>         Report only findings on lines added or modified by the
+                        unified diff in FILE (`-` reads it from stdin); whole
+                        files are still linted
+  --diff-root <DIR>     Directory the paths in the --diff are relative to
+                        (default: the enclosing git repository, or the
+                        current directory)
+  --format <FORMAT>     Output format: `text` (default), `json`, or `sarif`
+  --color <WHEN>        Color text output: `auto` (default, when stdout is a
+                        terminal and NO_COLOR is unset), `always`, or `never`
+  --emit <KIND>[=FILE]  Also dump `tokens`, `ast`, `ast-json`, `symbols`, or `cst`
+                        for each file, to FILE or stdout (repeatable)
+  --fix                 Apply safe automatic fixes to the files, then report
+                        what remains
+  --fix-dry-run         Print the changes --fix would make without writing them
+  --explain <CODE>      Describe the diagnostic CODE (such as BL3001) with examples
+  --list-rules          List every lint rule with its code, category, default
+                        severity, fix support, and options, as a table or, with
+                        `--format json`, as JSON
+  -h, --help            Print this help text
+
+Exit status is 1 when any error-level diagnostic is reported (or the warning
+limits above are exceeded), and 0 otherwise. Info and hint diagnostics never
+affect the exit status.";
+
+/// How diagnostics are written to stdout.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Source snippets with caret underlines.
+    #[default]
+    Text,
+    /// A single JSON document, see [`blazelint::report::json_report`].
+    Json,
+    /// A SARIF 2.1.0 log, see [`blazelint::report::sarif_report`].
+    Sarif,
+}
+
+impl OutputFormat {
+    fn parse(value: &str) -> Result<Self, String> {
+        match value {
+            "text" => Ok(OutputFormat::Text),
+            "json" => Ok(OutputFormat::Json),
+            "sarif" => Ok(OutputFormat::Sarif),
+            other => Err(format!(
+                "option '--format' expects 'text', 'json', or 'sarif', found '{other}'"
+            )),
+        }
+    }
+}
+
+/// Whether text output uses ANSI colors.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ColorChoice {
+    /// Color when stdout is a terminal and `NO_COLOR` is not set.
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorChoice {
+    fn parse(value: &str) -> Result<Self, String> {
+        match value {
+            "auto" => Ok(ColorChoice::Auto),
+            "always" => Ok(ColorChoice::Always),
+            "never" => Ok(ColorChoice::Never),
+            other => Err(format!(
+                "option '--color' expects 'auto', 'always', or 'never', found '{other}'"
+            )),
+        }
+    }
+
+    /// Resolves `Auto` against the environment.
+    fn enabled(self) -> bool {
+        match self {
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+            ColorChoice::Auto => {
+                std::io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none()
+            }
+        }
+    }
+}
+
+/// Whether suggested fixes are applied.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FixMode {
+    /// Report diagnostics only.
+    #[default]
+    Off,
+    /// Write fixed files back to disk.
+    Apply,
+    /// Show the fixes as a diff without touching the files.
+    DryRun,
+}
+
+/// Parsed command-line options.
+#[derive(Debug, Default)]
+pub struct CliOptions {
+    pub paths: Vec<PathBuf>,
+    /// Lint the source read from stdin instead of `paths`.
+    pub stdin: bool,
+    /// Where the stdin source would live on disk, for output and config
+    /// discovery.
+    pub stdin_filename: Option<PathBuf>,
+    pub ignore: Vec<String>,
+    pub config: Option<PathBuf>,
+    pub deny_warnings: bool,
+    pub max_warnings: Option<usize>,
+    /// Baseline file of known findings to leave unreported.
+    pub baseline: Option<PathBuf>,
+    /// Record the current findings in `baseline` instead of filtering by it.
+    pub write_baseline: bool,
+    /// Unified diff whose changed lines limit the findings reported; `-` is
+    /// stdin.
+    pub diff: Option<PathBuf>,
+    /// Directory the `diff` paths are relative to, overriding discovery of
+    /// the git repository.
+    pub diff_root: Option<PathBuf>,
+    pub format: OutputFormat,
+    pub color: ColorChoice,
+    pub emit: Vec<EmitRequest>,
+    pub fix: FixMode,
+    /// Diagnostic code to describe instead of linting.
+    pub explain: Option<String>,
+    /// List the rule registry instead of linting.
+    pub list_rules: bool,
+    pub help: bool,
+}
+
+/// Parses the process arguments (excluding the program name).
+///
+/// Options taking a value accept it either as the next argument or inline as
+/// `--option=value`.
+pub fn parse_args<I>(args: I) -> Result<CliOptions, String>
+where
+    I: IntoIterator<Item = String>,
+{
+    let mut options = CliOptions::default();
+    let mut args = args.into_iter();
+
+    while let Some(arg) = args.next() {
+        let (flag, inline) = match arg.split_once('=') {
+            Some((flag, value)) if flag.starts_with("--") => (flag, Some(value.to_string())),
+            _ => (arg.as_str(), None),
+        };
+
+        match flag {
+            "-h" | "--help" => {
+                no_value(flag, inline)?;
+                options.help = true;
+            }
+            "-" | "--stdin" => {
+                no_value(flag, inline)?;
+                options.stdin = true;
+            }
+            "--stdin-filename" => {
+                options.stdin_filename = Some(take_value(flag, inline, &mut args)?.into())
+            }
+            "--ignore" => options.ignore.push(take_value(flag, inline, &mut args)?),
+            "--config" => options.config = Some(take_value(flag, inline, &mut args)?.into()),
+            "--deny-warnings" => {
+                no_value(flag, inline)?;
+                options.deny_warnings = true;
+            }
+            "--max-warnings" => {
+                let value = take_value(flag, inline, &mut args)?;
+                options.max_warnings = Some(parse_count(&value, flag)?);
+            }
+            "--baseline" => options.baseline = Some(take_value(flag, inline, &mut args)?.into()),
+            "--write-baseline" => {
+                no_value(flag, inline)?;
+                options.write_baseline = true;
+            }
+            "--diff" => options.diff = Some(take_value(flag, inline, &mut args)?.into()),
+            "--diff-root" => options.diff_root = Some(take_value(flag, inline, &mut args)?.into()),
+            "--format" => {
+                options.format = OutputFormat::parse(&take_value(flag, inline, &mut args)?)?
+            }
+            "--color" => options.color = ColorChoice::parse(&take_value(flag, inline, &mut args)?)?,
+            "--fix" => {
+                no_value(flag, inline)?;
+                options.fix = FixMode::Apply;
+            }
+            "--fix-dry-run" => {
+                no_value(flag, inline)?;
+                options.fix = FixMode::DryRun;
+            }
+            "--emit" => options
+                .emit
+                .push(EmitRequest::parse(&take_value(flag, inline, &mut args)?)?),
+            "--explain" => options.explain = Some(take_value(flag, inline, &mut args)?),
+            "--list-rules" => {
+                no_value(flag, inline)?;
+                options.list_rules = true;
+            }
+            _ if flag.starts_with('-') && flag.len() > 1 => {
+                return Err(format!("unknown option '{flag}'"));
+            }
+            _ => options.paths.push(PathBuf::from(arg)),
+        }
+    }
+
+    if options.paths.is_empty()
+        && !options.stdin
+        && !options.help
+        && options.explain.is_none()
+        && !options.list_rules
+    {
+        return Err("no input paths given".to_string());
+    }
+    if options.stdin && !options.paths.is_empty() {
+        return Err("stdin input cannot be combined with paths".to_string());
+    }
+    if options.stdin_filename.is_some() && !options.stdin {
+        return Err("'--stdin-filename' requires '--stdin' or '-'".to_string());
+    }
+    if options.stdin && options.fix == FixMode::Apply {
+        return Err(
+            "'--fix' cannot write back to stdin; use '--fix-dry-run' to see the changes"
+                .to_string(),
+        );
+    }
+    if options.stdin && options.diff.as_deref() == Some(Path::new("-")) {
+        return Err("the source and '--diff' cannot both be read from stdin".to_string());
+    }
+    if options.stdin && options.diff.is_some() && options.stdin_filename.is_none() {
+        return Err(
+            "'--diff' with stdin input requires '--stdin-filename' to find the file in the diff"
+                .to_string(),
+        );
+    }
+    if options.diff_root.is_some() && options.diff.is_none() {
+        return Err("'--diff-root' requires '--diff <FILE>'".to_string());
+    }
+    if options.write_baseline && options.baseline.is_none() {
+        return Err("'--write-baseline' requires '--baseline <FILE>'".to_string());
+    }
+    if options.write_baseline && options.diff.is_some() {
+        return Err(
+            "'--write-baseline' records every finding and cannot be combined with '--diff'"
+                .to_string(),
+        );
+    }
+    if options.list_rules && options.format == OutputFormat::Sarif {
+        return Err("'--list-rules' supports only the text and json formats".to_string());
+    }
+    if options.format != OutputFormat::Text
+        && options.emit.iter().any(|request| request.path.is_none())
+    {
+        return Err(
+            "'--emit' needs a file name (KIND=FILE) when '--format' is not text".to_string(),
+        );
+    }
+    Ok(options)
+}
+
+/// Options for `blazelint fmt`.
+#[derive(Debug, Default)]
+pub struct FmtOptions {
+    pub paths: Vec<PathBuf>,
+    pub ignore: Vec<String>,
+    pub config: Option<PathBuf>,
+    /// Report unformatted files instead of rewriting them.
+    pub check: bool,
+    pub color: ColorChoice,
+    pub help: bool,
+}
+
+/// Parses the arguments following `fmt`.
+pub fn parse_fmt_args<I>(args: I) -> Result<FmtOptions, String>
+where
+    I: IntoIterator<Item = String>,
+{
+    let mut options = FmtOptions::default();
+    let mut args = args.into_iter();
+
+    while let Some(arg) = args.next() {
+        let (flag, inline) = match arg.split_once('=') {
+            Some((flag, value)) if flag.starts_with("--") => (flag, Some(value.to_string())),
+            _ => (arg.as_str(), None),
+        };
+
+        match flag {
+            "-h" | "--help" => {
+                no_value(flag, inline)?;
+                options.help = true;
+            }
+            "--check" => {
+                no_value(flag, inline)?;
+                options.check = true;
+            }
+            "--color" => options.color = ColorChoice::parse(&take_value(flag, inline, &mut args)?)?,
+            "--ignore" => options.ignore.push(take_value(flag, inline, &mut args)?),
+            "--config" => options.config = Some(take_value(flag, inline, &mut args)?.into()),
+            _ if flag.starts_with('-') && flag.len() > 1 => {
+                return Err(format!("unknown option '{flag}' for 'fmt'"));
+            }
+            _ => options.paths.push(PathBuf::from(arg)),
+        }
+    }
+
+    if options.paths.is_empty() && !options.help {
+        return Err("no input paths given".to_string());
+    }
+    Ok(options)
+}
+
+/// Runs `blazelint fmt`, returning the exit status.
+///
+/// Files with syntax errors are reported and left alone. The exit status is 1
+/// when any file could not be formatted, or with `--check` when any file is
+/// not formatted.
+pub fn run_fmt(args: &[String]) -> i32 {
+    let options = match parse_fmt_args(args.iter().cloned()) {
+        Ok(options) => options,
+        Err(err) => {
+            eprintln!("Error: {err}\n\n{USAGE}");
+            return 1;
+        }
+    };
+    if options.help {
+        println!("{USAGE}");
+        return 0;
+    }
+
+    let ignore = IgnoreSet::new(options.ignore.iter().cloned());
+    let files = match collect_files(&options.paths, &ignore) {
+        Ok(files) => files,
+        Err(err) => {
+            eprintln!("Error: {err}");
+            return 1;
+        }
+    };
+    if files.is_empty() {
+        eprintln!("Error: no .bal files found");
+        return 1;
+    }
+
+    let mut tally = Tally::default();
+    let mut output = Output::new(OutputFormat::Text, options.color.enabled());
+    let mut configs = ConfigCache::default();
+    let default_options = LintOptions::default();
+
+    for path in &files {
+        let display_name = path.display().to_string();
+        let config_path = match &options.config {
+            Some(explicit) => Some(explicit.clone()),
+            None => Config::discover(path.parent().unwrap_or(Path::new("."))),
+        };
+        let lint_options = match &config_path {
+            Some(config_path) => match configs.get(config_path, &mut output) {
+                Some(lint_options) => lint_options,
+                None => {
+                    tally.unusable_files += 1;
+                    continue;
+                }
+            },
+            None => &default_options,
+        };
+
+        let source = match fs::read_to_string(path) {
+            Ok(source) => source,
+            Err(err) => {
+                eprintln!("Error reading file {}: {}", display_name, err);
+                tally.unusable_files += 1;
+                continue;
+            }
+        };
+
+        let format_options = FormatOptions::from_config(&lint_options.config);
+        let diagnostics = match format_source(&source, &format_options) {
+            Ok(formatted) if formatted == source => Vec::new(),
+            Ok(formatted) if options.check => vec![unformatted_diagnostic(&source, &formatted)],
+            Ok(formatted) => {
+                if let Err(err) = fs::write(path, &formatted) {
+                    eprintln!("Error writing file {}: {}", display_name, err);
+                    tally.unusable_files += 1;
+                } else {
+                    println!("Formatted {display_name}");
+                }
+                Vec::new()
+            }
+            Err(diagnostics) => diagnostics,
+        };
+        for diagnostic in &diagnostics {
+            tally.record(diagnostic.severity);
+        }
+        output.file(display_name, source, diagnostics, Vec::new());
+    }
+
+    output.finish(files.len(), &tally);
+    tally.exit_code(&CliOptions::default())
+}
+
+/// Prints every registered rule for `--list-rules`, returning the exit status.
+pub fn run_list_rules(format: OutputFormat) -> i32 {
+    let rules = default_rules();
+    match format {
+        OutputFormat::Json => println!("{}", rules_json(&rules).to_pretty_string()),
+        _ => print_rules(&rules),
+    }
+    0
+}
+
+/// Prints the documentation of a diagnostic code for `--explain`, returning
+/// the exit status.
+pub fn run_explain(code: &str) -> i32 {
+    let Some(info) = codes::lookup(code) else {
+        eprintln!("Error: unknown diagnostic code '{code}'");
+        return 1;
+    };
+    println!("{}: {}\n", info.code, info.title);
+    println!("{}\n", info.explanation);
+    println!("Example of code that reports {}:\n", info.code);
+    print_indented(info.bad);
+    println!("\nCorrected:\n");
+    print_indented(info.good);
+    0
+}
+
+/// Prints `text` with every non-empty line indented by four spaces.
+fn print_indented(text: &str) {
+    for line in text.lines() {
+        if line.is_empty() {
+            println!();
+        } else {
+            println!("    {line}");
+        }
+    }
+}
+
+/// Runs `blazelint lsp` on stdin and stdout, returning the exit status.
+pub fn run_lsp(args: &[String]) -> i32 {
+    match args.first().map(String::as_str) {
+        None => {}
+        Some("-h" | "--help") => {
+            println!("{USAGE}");
+            return 0;
+        }
+        Some(other) => {
+            eprintln!("Error: 'lsp' takes no arguments, found '{other}'\n\n{USAGE}");
+            return 1;
+        }
+    }
+
+    let stdin = std::io::stdin();
+    match lsp::serve(stdin.lock(), std::io::stdout().lock()) {
+        Ok(code) => code,
+        Err(err) => {
+            eprintln!("Error: language server stopped: {err}");
+            1
+        }
+    }
+}
+
+/// Returns the value for `flag`, given inline or as the following argument.
+fn take_value(
+    flag: &str,
+    inline: Option<String>,
+    args: &mut impl Iterator<Item = String>,
+) -> Result<String, String> {
+    inline
+        .or_else(|| args.next())
+        .ok_or_else(|| format!("option '{flag}' requires a value"))
+}
+
+/// Rejects `--flag=value` for flags that take no value.
+fn no_value(flag: &str, inline: Option<String>) -> Result<(), String> {
+    match inline {
+        Some(_) => Err(format!("option '{flag}' does not take a value")),
+        None => Ok(()),
+    }
+}
+
+/// Parses a non-negative count given to `flag`.
+fn parse_count(value: &str, flag: &str) -> Result<usize, String> {
+    value
+        .parse()
+        .map_err(|_| format!("option '{flag}' expects a non-negative integer, found '{value}'"))
+}
+
+/// Running totals used for the summary line and the exit status.
+#[derive(Default)]
+struct Tally {
+    errors: usize,
+    warnings: usize,
+    /// Findings left unreported because the baseline covers them.
+    baselined: usize,
+    /// Files that could not be linted at all (unreadable, or bad config).
+    unusable_files: usize,
+}
+
+impl Tally {
+    fn record(&mut self, severity: Severity) {
+        match severity {
+            Severity::Error => self.errors += 1,
+            Severity::Warning => self.warnings += 1,
+            Severity::Info | Severity::Hint => {}
+        }
+    }
+
+    /// Applies the exit-code policy, explaining warning-limit failures on stderr.
+    fn exit_code(&self, options: &CliOptions) -> i32 {
+        if self.errors > 0 || self.unusable_files > 0 {
+            return 1;
+        }
+        if options.deny_warnings && self.warnings > 0 {
+            eprintln!("Error: warnings are denied (--deny-warnings)");
+            return 1;
+        }
+        if let Some(max) = options.max_warnings {
+            if self.warnings > max {
+                eprintln!(
+                    "Error: {} warning(s) exceed the limit of {max} (--max-warnings)",
+                    self.warnings
+                );
+                return 1;
+            }
+        }
+        0
+    }
+}
+
+/// Collects or prints diagnostics according to the output format.
+struct Output {
+    format: OutputFormat,
+    /// Whether text output uses ANSI colors.
+    color: bool,
+    /// Files with diagnostics, kept for formats written once at the end.
+    files: Vec<(String, String, Vec<Diagnostic>)>,
+}
+
+impl Output {
+    fn new(format: OutputFormat, color: bool) -> Self {
+        Self {
+            format,
+            color,
+            files: Vec::new(),
+        }
+    }
+
+    /// Reports the diagnostics found in one file. Suppressed diagnostics are
+    /// only listed by formats that can mark them as such.
+    fn file(
+        &mut self,
+        file_name: String,
+        source: String,
+        mut diagnostics: Vec<Diagnostic>,
+        suppressed: Vec<Diagnostic>,
+    ) {
+        if self.format == OutputFormat::Sarif {
+            diagnostics.extend(suppressed);
+        }
+        if diagnostics.is_empty() {
+            return;
+        }
+        match self.format {
+            OutputFormat::Text => {
+                let line_starts = compute_line_starts(&source);
+                print_diagnostics(&file_name, &source, &line_starts, &diagnostics, self.color);
+            }
+            OutputFormat::Json | OutputFormat::Sarif => {
+                self.files.push((file_name, source, diagnostics))
+            }
+        }
+    }
+
+    /// Writes the summary, or the whole document for machine-readable formats.
+    fn finish(&self, files_checked: usize, tally: &Tally) {
+        match self.format {
+            OutputFormat::Text => {
+                if files_checked > 1 || tally.errors + tally.warnings + tally.baselined > 0 {
+                    print!(
+                        "Checked {} file(s): {} error(s), {} warning(s)",
+                        files_checked, tally.errors, tally.warnings
+                    );
+                    if tally.baselined > 0 {
+                        print!(", {} baselined", tally.baselined);
+                    }
+                    println!();
+                }
+            }
+            OutputFormat::Json | OutputFormat::Sarif => {
+                let files: Vec<_> = self
+                    .files
+                    .iter()
+                    .map(|(file_name, source, diagnostics)| FileDiagnostics {
+                        file_name,
+                        source,
+                        diagnostics,
+                    })
+                    .collect();
+                let document = if self.format == OutputFormat::Json {
+                    json_report(&files, files_checked)
+                } else {
+                    sarif_report(&files, &default_rules())
+                };
+                println!("{}", document.to_pretty_string());
+            }
+        }
+    }
+}
+
+/// Loads configuration files on demand, reporting each file's problems once.
+#[derive(Default)]
+struct ConfigCache {
+    /// Loaded configs keyed by path; `None` marks a file that failed to load.
+    loaded: HashMap<PathBuf, Option<LintOptions>>,
+}
+
+impl ConfigCache {
+    /// Returns lint options for the config at `path`, or `None` when the file
+    /// is unreadable or invalid. Problems are reported the first time only.
+    fn get(&mut self, path: &Path, output: &mut Output) -> Option<&LintOptions> {
+        self.loaded
+            .entry(path.to_path_buf())
+            .or_insert_with(|| load_config(path, output))
+            .as_ref()
+    }
+}
+
+/// Loads and validates a configuration file, reporting any problems found.
+fn load_config(path: &Path, output: &mut Output) -> Option<LintOptions> {
+    let display_name = path.display().to_string();
+    let (config, source, mut diagnostics) = match Config::load(path) {
+        Ok(loaded) => loaded,
+        Err(err) => {
+            eprintln!("Error reading config {}: {}", display_name, err);
+            return None;
+        }
+    };
+    diagnostics.extend(RuleSet::from_config(&config).1);
+
+    // Warnings, such as a deprecated rule name, are reported but the
+    // configuration is still used.
+    let failed = diagnostics
+        .iter()
+        .any(|diagnostic| diagnostic.severity == Severity::Error);
+    if !diagnostics.is_empty() {
+        output.file(display_name, source, diagnostics, Vec::new());
+    }
+    if failed {
+        return None;
+    }
+
+    let mut options = LintOptions::default();
+    options.config = config;
+    Some(options)
+}
+
+/// One source to lint: a file on disk, or the buffer piped to stdin.
+enum Input {
+    File(PathBuf),
+    /// Stdin, optionally named by `--stdin-filename`.
+    Stdin(Option<PathBuf>),
+}
+
+impl Input {
+    /// The name diagnostics are reported under.
+    fn display_name(&self) -> String {
+        match self {
+            Input::File(path) | Input::Stdin(Some(path)) => path.display().to_string(),
+            Input::Stdin(None) => "<stdin>".to_string(),
+        }
+    }
+
+    /// Where `.blazerc` discovery starts.
+    fn config_dir(&self) -> &Path {
+        match self {
+            Input::File(path) | Input::Stdin(Some(path)) => path.parent().unwrap_or(Path::new(".")),
+            Input::Stdin(None) => Path::new("."),
+        }
+    }
+}
+
+/// Reads the whole source of `input`.
+fn read_source(input: &Input) -> std::io::Result<String> {
+    match input {
+        Input::File(path) => fs::read_to_string(path),
+        Input::Stdin(_) => std::io::read_to_string(std::io::stdin()),
+    }
+}
+
+/// Lints every file selected by `options`, returning the process exit code.
+///
+/// Diagnostics are printed grouped per file, or as one JSON or SARIF document
+/// with `--format`. Each file uses `--config` when given, and otherwise the
+/// nearest `.blazerc` above it. The exit code follows the policy described in
+/// [`USAGE`]; unreadable files and invalid configuration files always fail
+/// the run.
+pub fn run(options: &CliOptions) -> i32 {
+    let inputs = if options.stdin {
+        vec![Input::Stdin(options.stdin_filename.clone())]
+    } else {
+        let ignore = IgnoreSet::new(options.ignore.iter().cloned());
+        match collect_files(&options.paths, &ignore) {
+            Ok(files) => files.into_iter().map(Input::File).collect(),
+            Err(err) => {
+                eprintln!("Error: {err}");
+                return 1;
+            }
+        }
+    };
+
+    if inputs.is_empty() {
+        eprintln!("Error: no .bal files found");
+        return 1;
+    }
+
+    let mut baseline = match &options.baseline {
+        Some(_) if options.write_baseline => Some(Baseline::default()),
+        Some(path) => match Baseline::load(path) {
+            Ok(baseline) => Some(baseline),
+            Err(err) => {
+                eprintln!("Error reading baseline {}: {}", path.display(), err);
+                return 1;
+            }
+        },
+        None => None,
+    };
+
+    let changed_lines = match &options.diff {
+        Some(path) => match read_diff(path) {
+            Ok(changed_lines) => Some(changed_lines),
+            Err(err) => {
+                eprintln!("Error reading diff {}: {}", path.display(), err);
+                return 1;
+            }
+        },
+        None => None,
+    };
+    let diff_root = match (&changed_lines, &options.diff_root) {
+        (None, _) => None,
+        (Some(_), Some(dir)) => match dir.canonicalize() {
+            Ok(root) => Some(root),
+            Err(err) => {
+                eprintln!("Error reading diff root {}: {}", dir.display(), err);
+                return 1;
+            }
+        },
+        (Some(_), None) => discover_diff_root(),
+    };
+
+    let mut tally = Tally::default();
+    let mut output = Output::new(options.format, options.color.enabled());
+    let mut emitter = Emitter::new(&options.emit);
+    let mut configs = ConfigCache::default();
+    let default_options = LintOptions::default();
+
+    for input in &inputs {
+        let display_name = input.display_name();
+        let config_path = match &options.config {
+            Some(explicit) => Some(explicit.clone()),
+            None => Config::discover(input.config_dir()),
+        };
+        let lint_options = match &config_path {
+            Some(config_path) => match configs.get(config_path, &mut output) {
+                Some(lint_options) => lint_options,
+                None => {
+                    tally.unusable_files += 1;
+                    continue;
+                }
+            },
+            None => &default_options,
+        };
+
+        let mut input_code = match read_source(input) {
+            Ok(code) => code,
+            Err(err) => {
+                eprintln!("Error reading file {}: {}", display_name, err);
+                tally.unusable_files += 1;
+                continue;
+            }
+        };
+
+        let mut report = lint_source(&input_code, &display_name, lint_options);
+        if options.fix != FixMode::Off {
+            let (fixed_code, fixed_report, applied) =
+                fix_source(&input_code, report, &display_name, lint_options);
+            report = fixed_report;
+            if applied > 0 {
+                if let (FixMode::Apply, Input::File(path)) = (options.fix, input) {
+                    if let Err(err) = fs::write(path, &fixed_code) {
+                        eprintln!("Error writing file {}: {}", display_name, err);
+                        tally.unusable_files += 1;
+                        continue;
+                    }
+                }
+                if options.format == OutputFormat::Text {
+                    match options.fix {
+                        FixMode::Apply => {
+                            println!("Fixed {applied} problem(s) in {display_name}\n")
+                        }
+                        _ => println!("{}", unified_diff(&display_name, &input_code, &fixed_code)),
+                    }
+                }
+            }
+            input_code = fixed_code;
+        }
+
+        emitter.record(&report, &input_code);
+        if let Some(changed_lines) = &changed_lines {
+            let diff_name = diff_name(input, diff_root.as_deref());
+            (report.diagnostics, _) =
+                changed_lines.partition(&diff_name, &input_code, report.diagnostics);
+        }
+        if let Some(baseline) = &mut baseline {
+            let baselined = if options.write_baseline {
+                for diagnostic in &report.diagnostics {
+                    baseline.add(&display_name, &input_code, diagnostic);
+                }
+                std::mem::take(&mut report.diagnostics)
+            } else {
+                let (new, baselined) =
+                    baseline.partition(&display_name, &input_code, report.diagnostics);
+                report.diagnostics = new;
+                baselined
+            };
+            tally.baselined += baselined.len();
+        }
+        for diagnostic in &report.diagnostics {
+            tally.record(diagnostic.severity);
+        }
+        output.file(
+            display_name,
+            input_code,
+            report.diagnostics,
+            report.suppressed,
+        );
+    }
+
+    if let Err(err) = emitter.finish() {
+        eprintln!("Error: {err}");
+        return 1;
+    }
+    if let (Some(baseline), Some(path), true) =
+        (&baseline, &options.baseline, options.write_baseline)
+    {
+        if let Err(err) = baseline.save(path) {
+            eprintln!("Error writing baseline {}: {}", path.display(), err);
+            return 1;
+        }
+        if options.format == OutputFormat::Text {
+            println!(
+                "Wrote {} finding(s) to baseline {}",
+                baseline.len(),
+                path.display()
+            );
+        }
+    }
+    output.finish(inputs.len(), &tally);
+    tally.exit_code(options)
+}
+
+/// The directory `git diff` paths are relative to: the top of the git
+/// repository around the working directory, or the working directory itself.
+fn discover_diff_root() -> Option<PathBuf> {
+    let cwd = std::env::current_dir().ok()?.canonicalize().ok()?;
+    let repository = cwd
+        .ancestors()
+        .find(|dir| dir.join(".git").exists())
+        .map(Path::to_path_buf);
+    Some(repository.unwrap_or(cwd))
+}
+
+/// The name `input` goes by in a diff taken at `root`: its path relative to
+/// `root`, or its display name when it lies outside `root`.
+fn diff_name(input: &Input, root: Option<&Path>) -> String {
+    let (Input::File(path) | Input::Stdin(Some(path))) = input else {
+        return input.display_name();
+    };
+    // A `--stdin-filename` need not exist, so fall back to joining it to the
+    // working directory.
+    let absolute = path.canonicalize().ok().or_else(|| {
+        let cwd = std::env::current_dir().ok()?.canonicalize().ok()?;
+        Some(cwd.join(path))
+    });
+    match (absolute, root) {
+        (Some(absolute), Some(root)) => match absolute.strip_prefix(root) {
+            Ok(relative) => relative.display().to_string(),
+            Err(_) => input.display_name(),
+        },
+        _ => input.display_name(),
+    }
+}
+
+/// Reads and parses the unified diff at `path`, or on stdin for `-`.
+fn read_diff(path: &Path) -> std::io::Result<ChangedLines> {
+    let text = if path == Path::new("-") {
+        std::io::read_to_string(std::io::stdin())?
+    } else {
+        fs::read_to_string(path)?
+    };
+    ChangedLines::parse(&text)
+        .map_err(|message| std::io::Error::new(std::io::ErrorKind::InvalidData, message))
+}
+
+/// Upper bound on fix-and-relint rounds for one file; overlapping fixes that
+/// were skipped get another chance in the next round.
+const MAX_FIX_PASSES: usize = 10;
+
+/// Applies machine-applicable fixes to `source`, re-linting after each round.
+///
+/// A round whose result has more error-level diagnostics than its input is
+/// discarded. Returns the fixed source, its lint report, and the number of
+/// fixes applied.
+fn fix_source(
+    source: &str,
+    mut report: LintReport,
+    file_name: &str,
+    lint_options: &LintOptions,
+) -> (String, LintReport, usize) {
+    let mut current = source.to_string();
+    let mut applied = 0;
+    for _ in 0..MAX_FIX_PASSES {
+        let result = apply_fixes(&current, &report.diagnostics);
+        if result.applied == 0 {
+            break;
+        }
+        let relinted = lint_source(&result.source, file_name, lint_options);
+        if relinted.count(Severity::Error) > report.count(Severity::Error) {
+            eprintln!("Warning: skipped fixes for {file_name} because they introduced new errors");
+            break;
+        }
+        current = result.source;
+        applied += result.applied;
+        report = relinted;
+    }
+    (current, report, applied)
+}
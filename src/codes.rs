@@ -0,0 +1,408 @@
+//! Stable diagnostic codes.
+//!
+//! Every diagnostic carries a code such as `BL3001` that stays the same when
+//! its message is reworded, so CI logs can be searched and suppressions
+//! documented. The first digit names the stage that reports it:
+//!
+//! | Range    | Stage                                      |
+//! |----------|--------------------------------------------|
+//! | `BL1xxx` | lexer                                      |
+//! | `BL2xxx` | parser                                     |
+//! | `BL3xxx` | semantic analysis                          |
+//! | `BL4xxx` | lint rules, one code per rule              |
+//! | `BL5xxx` | configuration, suppression comments, `fmt` |
+//!
+//! Codes are never reused. [`CODES`] documents each one for
+//! `blazelint --explain`.
+
+pub const UNTERMINATED_COMMENT: &str = "BL1001";
+pub const UNTERMINATED_STRING: &str = "BL1002";
+pub const INVALID_NUMBER: &str = "BL1003";
+pub const UNEXPECTED_CHARACTER: &str = "BL1004";
+
+pub const EXPECTED_SYNTAX: &str = "BL2001";
+pub const UNEXPECTED_TOKEN: &str = "BL2002";
+pub const UNEXPECTED_EOF: &str = "BL2003";
+pub const INVALID_ASSIGNMENT_TARGET: &str = "BL2004";
+pub const CONST_TYPE_ANNOTATION: &str = "BL2005";
+
+pub const REDECLARATION: &str = "BL3001";
+pub const UNDECLARED_VARIABLE: &str = "BL3002";
+pub const UNKNOWN_FUNCTION: &str = "BL3003";
+pub const UNKNOWN_TYPE: &str = "BL3004";
+pub const UNINITIALIZED_USE: &str = "BL3005";
+pub const FINAL_NOT_INITIALIZED: &str = "BL3006";
+pub const ASSIGN_TO_IMMUTABLE: &str = "BL3007";
+pub const TYPE_MISMATCH: &str = "BL3008";
+pub const MISSING_RETURN_VALUE: &str = "BL3009";
+pub const NON_BOOLEAN_CONDITION: &str = "BL3010";
+pub const INVALID_OPERAND: &str = "BL3011";
+pub const INCOMPATIBLE_ELEMENTS: &str = "BL3012";
+pub const INVALID_INDEX: &str = "BL3013";
+pub const LOOP_CONTROL_OUTSIDE_LOOP: &str = "BL3014";
+pub const PANIC_NON_ERROR: &str = "BL3015";
+pub const UNKNOWN_FIELD: &str = "BL3016";
+pub const MISSING_FIELD: &str = "BL3017";
+pub const UNKNOWN_METHOD: &str = "BL3018";
+pub const CHECK_WITHOUT_ERROR_RETURN: &str = "BL3019";
+pub const PRIVATE_FIELD: &str = "BL3020";
+pub const MISSING_METHOD: &str = "BL3021";
+pub const ARGUMENT_COUNT: &str = "BL3022";
+
+pub const CAMEL_CASE: &str = "BL4001";
+pub const CONSTANT_CASE: &str = "BL4002";
+pub const LINE_LENGTH: &str = "BL4003";
+
+pub const INVALID_CONFIG: &str = "BL5001";
+pub const UNUSED_SUPPRESSION: &str = "BL5002";
+pub const INVALID_SUPPRESSION: &str = "BL5003";
+pub const UNFORMATTED_FILE: &str = "BL5004";
+
+/// Long-form documentation of one diagnostic code.
+#[derive(Debug, Clone, Copy)]
+pub struct CodeInfo {
+    pub code: &'static str,
+    /// One-line summary of the problem.
+    pub title: &'static str,
+    /// What triggers the diagnostic and how to resolve it.
+    pub explanation: &'static str,
+    /// Input that reports the diagnostic.
+    pub bad: &'static str,
+    /// The same input, corrected.
+    pub good: &'static str,
+}
+
+/// Every diagnostic code, in numeric order.
+pub const CODES: &[CodeInfo] = &[
+    CodeInfo {
+        code: UNTERMINATED_COMMENT,
+        title: "Unterminated block comment",
+        explanation: "A `/*` block comment was still open at the end of the file. \
+                      Close it with `*/`.",
+        bad: "/* Adds one.\nfunction inc(int n) returns int {\n    return n + 1;\n}\n",
+        good: "/* Adds one. */\nfunction inc(int n) returns int {\n    return n + 1;\n}\n",
+    },
+    CodeInfo {
+        code: UNTERMINATED_STRING,
+        title: "Unterminated string literal or template",
+        explanation: "A string literal or string template was not closed before the end \
+                      of the line or file, or a backslash escape was cut off. Add the \
+                      closing quote or backtick.",
+        bad: "string greeting = \"hello;\n",
+        good: "string greeting = \"hello\";\n",
+    },
+    CodeInfo {
+        code: INVALID_NUMBER,
+        title: "Invalid number literal",
+        explanation: "A number literal could not be read, for example because an \
+                      exponent has no digits or the value does not fit its type.",
+        bad: "float ratio = 1.5e;\n",
+        good: "float ratio = 1.5e3;\n",
+    },
+    CodeInfo {
+        code: UNEXPECTED_CHARACTER,
+        title: "Unexpected character",
+        explanation: "The file contains a character that cannot start any token of the \
+                      language. Remove it, or move it into a string or comment.",
+        bad: "int count = 1 @ 2;\n",
+        good: "int count = 1 + 2;\n",
+    },
+    CodeInfo {
+        code: EXPECTED_SYNTAX,
+        title: "Expected syntax is missing",
+        explanation: "The parser needed a specific token or construct, such as a `;` \
+                      after a statement or a name after `function`, and found something \
+                      else. The message and note name what was expected.",
+        bad: "int total = 1\n",
+        good: "int total = 1;\n",
+    },
+    CodeInfo {
+        code: UNEXPECTED_TOKEN,
+        title: "Unexpected token in expression",
+        explanation: "A token appeared where an expression had to start, such as an \
+                      operator with no left operand or a missing value after `=`.",
+        bad: "int total = ;\n",
+        good: "int total = 0;\n",
+    },
+    CodeInfo {
+        code: UNEXPECTED_EOF,
+        title: "Unexpected end of input",
+        explanation: "The file ended in the middle of a statement or expression. \
+                      Complete the construct or remove the partial code.",
+        bad: "int total = 1 +",
+        good: "int total = 1 + 2;\n",
+    },
+    CodeInfo {
+        code: INVALID_ASSIGNMENT_TARGET,
+        title: "Invalid assignment target",
+        explanation: "The left-hand side of `=` must be a variable name; values such as \
+                      literals or calls cannot be assigned to.",
+        bad: "function main() {\n    int total = 0;\n    1 = total;\n}\n",
+        good: "function main() {\n    int total = 0;\n    total = 1;\n}\n",
+    },
+    CodeInfo {
+        code: CONST_TYPE_ANNOTATION,
+        title: "Constant with a type annotation",
+        explanation: "A `const` declaration takes its type from its value and cannot \
+                      name a type.",
+        bad: "const int MAX_SIZE = 10;\n",
+        good: "const MAX_SIZE = 10;\n",
+    },
+    CodeInfo {
+        code: REDECLARATION,
+        title: "Name declared twice in the same scope",
+        explanation: "A variable or constant was declared again in a scope that already \
+                      declares that name. Rename one of them, or assign to the existing \
+                      variable instead of declaring it again.",
+        bad: "function main() {\n    int count = 1;\n    int count = 2;\n}\n",
+        good: "function main() {\n    int count = 1;\n    count = 2;\n}\n",
+    },
+    CodeInfo {
+        code: UNDECLARED_VARIABLE,
+        title: "Use of an undeclared variable",
+        explanation: "A name was used that no enclosing scope declares. Declare it \
+                      before use, or fix the spelling.",
+        bad: "function main() {\n    int count = 1;\n    int next = cuont + 1;\n}\n",
+        good: "function main() {\n    int count = 1;\n    int next = count + 1;\n}\n",
+    },
+    CodeInfo {
+        code: UNKNOWN_FUNCTION,
+        title: "Call to an unknown function",
+        explanation: "A call names a function that the file does not define. Qualified \
+                      calls such as `io:println` are not checked.",
+        bad: "function main() {\n    helper();\n}\n",
+        good: "function helper() {\n}\n\nfunction main() {\n    helper();\n}\n",
+    },
+    CodeInfo {
+        code: UNKNOWN_TYPE,
+        title: "Unknown type",
+        explanation: "A type annotation names a type that is neither built in nor \
+                      defined in the file.",
+        bad: "strng name = \"blaze\";\n",
+        good: "string name = \"blaze\";\n",
+    },
+    CodeInfo {
+        code: UNINITIALIZED_USE,
+        title: "Variable may be used before it is initialised",
+        explanation: "A variable declared without a value is read on a path where no \
+                      value has been assigned to it yet. Initialise it when declaring \
+                      it, or assign it on every path before the read.",
+        bad: "function main() {\n    int count;\n    int next = count + 1;\n}\n",
+        good: "function main() {\n    int count = 0;\n    int next = count + 1;\n}\n",
+    },
+    CodeInfo {
+        code: FINAL_NOT_INITIALIZED,
+        title: "Final variable without a value",
+        explanation: "A `final` variable can never be assigned after its declaration, \
+                      so it must be initialised there.",
+        bad: "function main() {\n    final int limit;\n}\n",
+        good: "function main() {\n    final int limit = 10;\n}\n",
+    },
+    CodeInfo {
+        code: ASSIGN_TO_IMMUTABLE,
+        title: "Assignment to a constant, final variable, or final field",
+        explanation: "Constants and initialised `final` variables cannot be changed. \
+                      `final` fields, and every field of a `readonly` class, can only be \
+                      set through `self` in the class's `init` method. Declare a regular \
+                      variable or field if the value must change.",
+        bad: "function main() {\n    final int limit = 10;\n    limit = 20;\n}\n",
+        good: "function main() {\n    int limit = 10;\n    limit = 20;\n}\n",
+    },
+    CodeInfo {
+        code: TYPE_MISMATCH,
+        title: "Type mismatch",
+        explanation: "A value in an initializer, assignment, or `return` does not have \
+                      the type the variable or function declares.",
+        bad: "function main() {\n    int count = \"three\";\n}\n",
+        good: "function main() {\n    int count = 3;\n}\n",
+    },
+    CodeInfo {
+        code: MISSING_RETURN_VALUE,
+        title: "Missing return value",
+        explanation: "A bare `return;` appears in a function that declares a return \
+                      type. Return a value of that type.",
+        bad: "function zero() returns int {\n    return;\n}\n",
+        good: "function zero() returns int {\n    return 0;\n}\n",
+    },
+    CodeInfo {
+        code: NON_BOOLEAN_CONDITION,
+        title: "Condition is not boolean",
+        explanation: "The condition of an `if`, `while`, or `? :` expression must be a \
+                      `boolean`; other values are not converted. Compare the value \
+                      explicitly.",
+        bad: "function main() {\n    int count = 1;\n    if (count) {\n        count = 0;\n    }\n}\n",
+        good: "function main() {\n    int count = 1;\n    if (count > 0) {\n        count = 0;\n    }\n}\n",
+    },
+    CodeInfo {
+        code: INVALID_OPERAND,
+        title: "Operator applied to the wrong type",
+        explanation: "The operands do not suit the operator: arithmetic and ordering \
+                      need numbers, `!`, `&&`, and `||` need booleans, bitwise and shift \
+                      operators need integers, and `==` needs matching types.",
+        bad: "function main() {\n    boolean done = true;\n    int next = done + 1;\n}\n",
+        good: "function main() {\n    int done = 1;\n    int next = done + 1;\n}\n",
+    },
+    CodeInfo {
+        code: INCOMPATIBLE_ELEMENTS,
+        title: "Incompatible elements in a list or map literal",
+        explanation: "Every element of an array literal, and every value of a map \
+                      literal, must be compatible with the first one.",
+        bad: "int[] sizes = [1, \"two\", 3];\n",
+        good: "int[] sizes = [1, 2, 3];\n",
+    },
+    CodeInfo {
+        code: INVALID_INDEX,
+        title: "Indexing a value that cannot be indexed",
+        explanation: "Only arrays, maps, and strings can be indexed with `[...]`.",
+        bad: "function main() {\n    int count = 3;\n    int first = count[0];\n}\n",
+        good: "function main() {\n    int[] counts = [3];\n    int first = counts[0];\n}\n",
+    },
+    CodeInfo {
+        code: LOOP_CONTROL_OUTSIDE_LOOP,
+        title: "`break` or `continue` outside a loop",
+        explanation: "`break` and `continue` only make sense inside `while` or \
+                      `foreach`. Use `return` to leave a function early.",
+        bad: "function main() {\n    break;\n}\n",
+        good: "function main() {\n    return;\n}\n",
+    },
+    CodeInfo {
+        code: PANIC_NON_ERROR,
+        title: "`panic` with a value that is not an error",
+        explanation: "`panic` takes a value of type `error`. Wrap the message with \
+                      `error(...)`.",
+        bad: "function main() {\n    panic \"failed\";\n}\n",
+        good: "function main() {\n    panic error(\"failed\");\n}\n",
+    },
+    CodeInfo {
+        code: UNKNOWN_FIELD,
+        title: "Access to a field the type does not have",
+        explanation: "`.name` names a field that the record, class, or object type does \
+                      not declare, or is applied to a value that has no fields. A record value may also \
+                      give a field that its closed record type does not declare. Fix the \
+                      field name, or use member access (`value[\"name\"]`) for fields an \
+                      open record does not declare.",
+        bad: "type Person record {|\n    string name;\n|};\n\nfunction main() {\n    Person p = {name: \"Ada\"};\n    string n = p.nmae;\n}\n",
+        good: "type Person record {|\n    string name;\n|};\n\nfunction main() {\n    Person p = {name: \"Ada\"};\n    string n = p.name;\n}\n",
+    },
+    CodeInfo {
+        code: MISSING_FIELD,
+        title: "Record value is missing a required field",
+        explanation: "A record value leaves out a field that its record type requires. \
+                      Fields are required unless declared optional (`int age?;`) or \
+                      given a default (`int age = 0;`).",
+        bad: "type Person record {\n    string name;\n    int age;\n};\n\nPerson p = {name: \"Ada\"};\n",
+        good: "type Person record {\n    string name;\n    int age?;\n};\n\nPerson p = {name: \"Ada\"};\n",
+    },
+    CodeInfo {
+        code: UNKNOWN_METHOD,
+        title: "Call to a method the type does not have",
+        explanation: "`.name(...)` calls a method that the class or object type neither \
+                      declares nor includes with `*T;`. Fix the method name, or add the \
+                      method to the class.",
+        bad: "class Counter {\n    int count = 0;\n\n    function increment() {\n        self.count += 1;\n    }\n}\n\nfunction main() {\n    Counter counter = new;\n    counter.incremnt();\n}\n",
+        good: "class Counter {\n    int count = 0;\n\n    function increment() {\n        self.count += 1;\n    }\n}\n\nfunction main() {\n    Counter counter = new;\n    counter.increment();\n}\n",
+    },
+    CodeInfo {
+        code: CHECK_WITHOUT_ERROR_RETURN,
+        title: "`check` in a function that cannot return an error",
+        explanation: "`check e` returns the error from the enclosing function when `e` is \
+                      an error, so the function's return type must include `error`. Add \
+                      `|error` to the return type, or use `checkpanic` to panic instead.",
+        bad: "function main() {\n    int|error parsed = 42;\n    int value = check parsed;\n}\n",
+        good: "function main() returns error? {\n    int|error parsed = 42;\n    int value = check parsed;\n}\n",
+    },
+    CodeInfo {
+        code: PRIVATE_FIELD,
+        title: "Use of a private field outside its class",
+        explanation: "A `private` field can only be read or assigned by the methods of \
+                      the class that declares it. Add a method that does what the caller \
+                      needs, or drop `private` from the field.",
+        bad: "class Account {\n    private int balance = 0;\n}\n\nfunction main() {\n    Account account = new;\n    int balance = account.balance;\n}\n",
+        good: "class Account {\n    private int balance = 0;\n\n    function getBalance() returns int {\n        return self.balance;\n    }\n}\n\nfunction main() {\n    Account account = new;\n    int balance = account.getBalance();\n}\n",
+    },
+    CodeInfo {
+        code: MISSING_METHOD,
+        title: "Class does not define a method of an included type",
+        explanation: "`*T;` in a class includes the fields and methods of object type `T`, \
+                      but only the class can give the methods a body. Define every method \
+                      that `T` declares, with the same signature.",
+        bad: "type Shape object {\n    function area() returns int;\n};\n\nclass Square {\n    *Shape;\n}\n",
+        good: "type Shape object {\n    function area() returns int;\n};\n\nclass Square {\n    *Shape;\n\n    function area() returns int {\n        return 4;\n    }\n}\n",
+    },
+    CodeInfo {
+        code: ARGUMENT_COUNT,
+        title: "Wrong number of arguments",
+        explanation: "The arguments of `new C(...)` are passed to the `init` method of \
+                      class `C`, so there must be one for each of its parameters; a class \
+                      without `init` takes none.",
+        bad: "class Point {\n    int x;\n\n    function init(int x) {\n        self.x = x;\n    }\n}\n\nPoint origin = new Point(0, 0);\n",
+        good: "class Point {\n    int x;\n\n    function init(int x) {\n        self.x = x;\n    }\n}\n\nPoint origin = new Point(0);\n",
+    },
+    CodeInfo {
+        code: CAMEL_CASE,
+        title: "Variable name is not camelCase (rule `camel_case`)",
+        explanation: "Variables and parameters are named in camelCase, starting with a \
+                      lowercase letter and without underscores.",
+        bad: "int retry_count = 3;\n",
+        good: "int retryCount = 3;\n",
+    },
+    CodeInfo {
+        code: CONSTANT_CASE,
+        title: "Constant name is not SCREAMING_SNAKE_CASE (rule `constant_case`)",
+        explanation: "Constants are named in SCREAMING_SNAKE_CASE: uppercase letters, \
+                      digits, and underscores.",
+        bad: "const maxRetries = 3;\n",
+        good: "const MAX_RETRIES = 3;\n",
+    },
+    CodeInfo {
+        code: LINE_LENGTH,
+        title: "Line is too long (rule `line_length`)",
+        explanation: "A line has more characters than the rule's `max_length` option \
+                      allows, 120 by default. Split the statement or the text it contains.",
+        bad: "string message = \"This line goes on and on, well past the one hundred and \
+              twenty characters that the rule allows by default.\";\n",
+        good: "string opening = \"This line goes on and on,\";\n\
+               string closing = \"well past the limit, so it was split in two.\";\n",
+    },
+    CodeInfo {
+        code: INVALID_CONFIG,
+        title: "Invalid configuration",
+        explanation: "The `.blazerc` file contains a malformed line, an unknown section, \
+                      rule, or option, or a value of the wrong type. Rules that could \
+                      not be configured keep their defaults.",
+        bad: "[rules.camelcase]\nseverity = \"error\"\n",
+        good: "[rules.camel_case]\nseverity = \"error\"\n",
+    },
+    CodeInfo {
+        code: UNUSED_SUPPRESSION,
+        title: "Suppression comment silences nothing",
+        explanation: "A `blazelint-disable` comment does not silence any diagnostic, \
+                      usually because the code it covered was fixed. Remove the comment.",
+        bad: "// blazelint-disable-next-line camel_case\nint retryCount = 3;\n",
+        good: "int retryCount = 3;\n",
+    },
+    CodeInfo {
+        code: INVALID_SUPPRESSION,
+        title: "Malformed suppression comment",
+        explanation: "A suppression comment uses an unknown directive or rule name, or a \
+                      `blazelint-enable` has no matching `blazelint-disable`. The comment \
+                      silences nothing until it is corrected.",
+        bad: "// blazelint-disable-next-line camelcase\nint retry_count = 3;\n",
+        good: "// blazelint-disable-next-line camel_case\nint retry_count = 3;\n",
+    },
+    CodeInfo {
+        code: UNFORMATTED_FILE,
+        title: "File is not formatted",
+        explanation: "`blazelint fmt --check` found a file that differs from the \
+                      standard style. Run `blazelint fmt` on it.",
+        bad: "int total=1+2;\n",
+        good: "int total = 1 + 2;\n",
+    },
+];
+
+/// Looks up the documentation of `code`, ignoring ASCII case.
+pub fn lookup(code: &str) -> Option<&'static CodeInfo> {
+    CODES
+        .iter()
+        .find(|info| info.code.eq_ignore_ascii_case(code))
+}
@@ -0,0 +1,369 @@
+//! Loading and parsing of `.blazerc` configuration files.
+//!
+//! A `.blazerc` uses a small subset of TOML: `[section]` headers, `key = value`
+//! pairs whose values are booleans, integers, or double-quoted strings, and `#`
+//! comments. Rules are configured either inline under `[rules]` or in their own
+//! `[rules.<name>]` table:
+//!
+//! ```toml
+//! [rules]
+//! camel_case = "warning"   # a severity, "off", or true/false
+//!
+//! [rules.line_length]
+//! severity = "error"
+//! max_length = 100
+//! ```
+//!
+//! Parsing is independent of the rule registry; unknown rule names and options
+//! are reported when the configuration is applied by
+//! [`RuleSet::from_config`](crate::linter::RuleSet::from_config).
+
+use crate::codes;
+use crate::errors::{Diagnostic, DiagnosticKind, Severity, Span};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// File name searched for when discovering configuration.
+pub const CONFIG_FILE_NAME: &str = ".blazerc";
+
+/// A scalar value from the configuration file.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfigValue {
+    Bool(bool),
+    Integer(i64),
+    String(String),
+}
+
+impl ConfigValue {
+    /// Human-readable name of the value's type, used in diagnostics.
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            ConfigValue::Bool(_) => "boolean",
+            ConfigValue::Integer(_) => "integer",
+            ConfigValue::String(_) => "string",
+        }
+    }
+}
+
+impl fmt::Display for ConfigValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigValue::Bool(value) => write!(f, "{value}"),
+            ConfigValue::Integer(value) => write!(f, "{value}"),
+            ConfigValue::String(value) => write!(f, "\"{value}\""),
+        }
+    }
+}
+
+/// A rule-specific option together with the spans needed to report on it.
+#[derive(Debug, Clone)]
+pub struct RuleOption {
+    pub value: ConfigValue,
+    pub key_span: Span,
+    pub value_span: Span,
+}
+
+/// Settings for a single rule.
+#[derive(Debug, Clone)]
+pub struct RuleSettings {
+    /// Whether the rule runs at all.
+    pub enabled: bool,
+    /// Overrides the rule's default severity when set.
+    pub severity: Option<Severity>,
+    /// Rule-specific options, keyed by option name.
+    pub options: BTreeMap<String, RuleOption>,
+    /// Span of the rule name where it was first mentioned.
+    pub name_span: Span,
+}
+
+impl RuleSettings {
+    fn new(name_span: Span) -> Self {
+        Self {
+            enabled: true,
+            severity: None,
+            options: BTreeMap::new(),
+            name_span,
+        }
+    }
+}
+
+/// Parsed contents of a configuration file.
+#[derive(Debug, Clone, Default)]
+pub struct Config {
+    /// Path the configuration was loaded from, if any.
+    pub path: Option<PathBuf>,
+    /// Per-rule settings, keyed by rule name.
+    pub rules: BTreeMap<String, RuleSettings>,
+}
+
+/// Where the parser currently adds keys.
+enum Section {
+    /// Before any header, or inside a header that was rejected.
+    None,
+    /// The `[rules]` table, whose keys name rules.
+    Rules,
+    /// A `[rules.<name>]` table, whose keys configure that rule.
+    Rule(String),
+}
+
+impl Config {
+    /// Parses configuration text, returning the config and any problems found.
+    ///
+    /// Invalid lines are reported and skipped, so the returned config reflects
+    /// every line that could be understood.
+    pub fn parse(source: &str) -> (Config, Vec<Diagnostic>) {
+        let mut config = Config::default();
+        let mut diagnostics = Vec::new();
+        let mut section = Section::None;
+        let mut offset = 0;
+
+        for raw_line in source.split_inclusive('\n') {
+            let line_start = offset;
+            offset += raw_line.len();
+
+            let content = strip_comment(raw_line.trim_end_matches(['\n', '\r']));
+            let leading = content.len() - content.trim_start().len();
+            let line = content.trim();
+            if line.is_empty() {
+                continue;
+            }
+            let line_span = line_start + leading..line_start + leading + line.len();
+
+            if let Some(header) = line.strip_prefix('[') {
+                section = match parse_header(header, line_span.clone()) {
+                    Ok(Some(name)) => {
+                        // The header is `[rules.<name>]`, possibly padded with
+                        // spaces; the name follows the first `rules.`.
+                        let after_prefix = line.find("rules.").map_or(1, |i| i + "rules.".len());
+                        let rest = &line[after_prefix..];
+                        let name_offset = after_prefix + rest.len() - rest.trim_start().len();
+                        let name_start = line_span.start + name_offset;
+                        let name_span = name_start..name_start + name.len();
+                        config
+                            .rules
+                            .entry(name.clone())
+                            .or_insert_with(|| RuleSettings::new(name_span));
+                        Section::Rule(name)
+                    }
+                    Ok(None) => Section::Rules,
+                    Err(diagnostic) => {
+                        diagnostics.push(*diagnostic);
+                        Section::None
+                    }
+                };
+                continue;
+            }
+
+            let Some(eq) = line.find('=') else {
+                diagnostics.push(config_error(
+                    "Expected 'key = value' or a '[section]' header",
+                    line_span,
+                ));
+                continue;
+            };
+
+            let key = line[..eq].trim();
+            let key_start = line_span.start;
+            let key_span = key_start..key_start + key.len();
+            let raw_value = line[eq + 1..].trim();
+            let value_start = line_span.end - raw_value.len();
+            let value_span = value_start..line_span.end;
+
+            if key.is_empty() {
+                diagnostics.push(config_error("Missing key before '='", line_span));
+                continue;
+            }
+            let value = match parse_value(raw_value) {
+                Ok(value) => value,
+                Err(message) => {
+                    diagnostics.push(config_error(message, value_span));
+                    continue;
+                }
+            };
+
+            match &section {
+                Section::None => {
+                    diagnostics.push(
+                        config_error(format!("Unknown top-level key '{key}'"), key_span)
+                            .with_note("keys must appear under a [rules] or [rules.<name>] table"),
+                    );
+                }
+                Section::Rules => {
+                    let settings = config
+                        .rules
+                        .entry(key.to_string())
+                        .or_insert_with(|| RuleSettings::new(key_span.clone()));
+                    if let Err(message) = apply_shorthand(settings, &value) {
+                        diagnostics.push(config_error(message, value_span));
+                    }
+                }
+                Section::Rule(rule) => {
+                    let settings = config.rules.get_mut(rule).expect("rule table registered");
+                    if let Err(message) =
+                        apply_rule_key(settings, key, value, key_span, value_span.clone())
+                    {
+                        diagnostics.push(config_error(message, value_span));
+                    }
+                }
+            }
+        }
+
+        (config, diagnostics)
+    }
+
+    /// Reads and parses the configuration file at `path`.
+    ///
+    /// Returns the file contents alongside the parse results so callers can
+    /// render diagnostics against the original text.
+    pub fn load(path: &Path) -> io::Result<(Config, String, Vec<Diagnostic>)> {
+        let source = fs::read_to_string(path)?;
+        let (mut config, diagnostics) = Config::parse(&source);
+        config.path = Some(path.to_path_buf());
+        Ok((config, source, diagnostics))
+    }
+
+    /// Finds the nearest `.blazerc`, searching `start` and then its ancestors.
+    pub fn discover(start: &Path) -> Option<PathBuf> {
+        let start = if start.as_os_str().is_empty() {
+            Path::new(".")
+        } else {
+            start
+        };
+        let start = start.canonicalize().ok()?;
+        start
+            .ancestors()
+            .map(|dir| dir.join(CONFIG_FILE_NAME))
+            .find(|candidate| candidate.is_file())
+    }
+}
+
+/// Parses the inside of a `[...]` header. `Ok(None)` means `[rules]`.
+fn parse_header(header: &str, span: Span) -> Result<Option<String>, Box<Diagnostic>> {
+    let Some(name) = header.strip_suffix(']') else {
+        return Err(Box::new(config_error("Unterminated section header", span)));
+    };
+    let name = name.trim();
+    if name == "rules" {
+        return Ok(None);
+    }
+    match name.strip_prefix("rules.") {
+        Some(rule) if !rule.trim().is_empty() => Ok(Some(rule.trim().to_string())),
+        _ => Err(Box::new(
+            config_error(format!("Unknown section '[{name}]'"), span)
+                .with_note("expected [rules] or [rules.<name>]"),
+        )),
+    }
+}
+
+/// Applies `name = value` from the `[rules]` table.
+fn apply_shorthand(settings: &mut RuleSettings, value: &ConfigValue) -> Result<(), String> {
+    match value {
+        ConfigValue::Bool(enabled) => settings.enabled = *enabled,
+        ConfigValue::String(level) if level == "off" => settings.enabled = false,
+        ConfigValue::String(level) => {
+            settings.severity = Some(level.parse()?);
+            settings.enabled = true;
+        }
+        other => {
+            return Err(format!(
+                "Expected true, false, \"off\", or a severity, found {}",
+                other.type_name()
+            ))
+        }
+    }
+    Ok(())
+}
+
+/// Applies a key from a `[rules.<name>]` table.
+fn apply_rule_key(
+    settings: &mut RuleSettings,
+    key: &str,
+    value: ConfigValue,
+    key_span: Span,
+    value_span: Span,
+) -> Result<(), String> {
+    match (key, value) {
+        ("enabled", ConfigValue::Bool(enabled)) => settings.enabled = enabled,
+        ("enabled", other) => return Err(format!("Expected boolean, found {}", other.type_name())),
+        ("severity", ConfigValue::String(level)) if level == "off" => settings.enabled = false,
+        ("severity", ConfigValue::String(level)) => settings.severity = Some(level.parse()?),
+        ("severity", other) => return Err(format!("Expected string, found {}", other.type_name())),
+        (option, value) => {
+            settings.options.insert(
+                option.to_string(),
+                RuleOption {
+                    value,
+                    key_span,
+                    value_span,
+                },
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Parses a scalar value: `true`, `false`, an integer, or a quoted string.
+fn parse_value(raw: &str) -> Result<ConfigValue, String> {
+    match raw {
+        "" => return Err("Missing value after '='".to_string()),
+        "true" => return Ok(ConfigValue::Bool(true)),
+        "false" => return Ok(ConfigValue::Bool(false)),
+        _ => {}
+    }
+
+    if let Some(body) = raw.strip_prefix('"') {
+        let Some(body) = body.strip_suffix('"') else {
+            return Err("Unterminated string value".to_string());
+        };
+        let mut value = String::new();
+        let mut chars = body.chars();
+        while let Some(c) = chars.next() {
+            if c == '\\' {
+                match chars.next() {
+                    Some('"') => value.push('"'),
+                    Some('\\') => value.push('\\'),
+                    Some('n') => value.push('\n'),
+                    Some('t') => value.push('\t'),
+                    _ => return Err("Invalid escape sequence in string value".to_string()),
+                }
+            } else {
+                value.push(c);
+            }
+        }
+        return Ok(ConfigValue::String(value));
+    }
+
+    raw.replace('_', "")
+        .parse::<i64>()
+        .map(ConfigValue::Integer)
+        .map_err(|_| {
+            format!("Invalid value '{raw}': expected true, false, an integer, or a string")
+        })
+}
+
+/// Removes a trailing `#` comment, ignoring `#` characters inside strings.
+fn strip_comment(line: &str) -> &str {
+    let mut in_string = false;
+    let mut escaped = false;
+    for (idx, c) in line.char_indices() {
+        match c {
+            '\\' if in_string && !escaped => {
+                escaped = true;
+                continue;
+            }
+            '"' if !escaped => in_string = !in_string,
+            '#' if !in_string => return &line[..idx],
+            _ => {}
+        }
+        escaped = false;
+    }
+    line
+}
+
+/// Creates an error-level configuration diagnostic.
+pub(crate) fn config_error(message: impl Into<String>, span: Span) -> Diagnostic {
+    Diagnostic::new(DiagnosticKind::Config, message, span).with_code(codes::INVALID_CONFIG)
+}
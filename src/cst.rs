@@ -0,0 +1,299 @@
+//! Lossless concrete syntax tree.
+//!
+//! The AST keeps only what the analyzer needs. The concrete syntax tree mirrors
+//! its statements and expressions but keeps every token and every piece of
+//! trivia (whitespace, line breaks, comments) as leaves, so
+//! [`SyntaxNode::text`] reproduces the source byte for byte. Tools that rewrite
+//! code, such as fixers and the formatter, work on this tree.
+//!
+//! A node starts at its first token and ends at its last; trivia before or
+//! after a node belongs to the enclosing node. Tokens the parser could not
+//! place in any statement become direct children of the [`SyntaxKind::SourceFile`]
+//! root, so the tree stays lossless even for code with syntax errors.
+
+use crate::ast::{walk, Expr, NodeKind, Stmt, VisitContext, Visitor};
+use crate::errors::Span;
+use crate::lexer::{merge_trivia, Element, SpannedToken, Token, Trivia, TriviaKind};
+use std::fmt;
+
+/// What an interior node of the tree represents.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyntaxKind {
+    /// The whole file.
+    SourceFile,
+    /// A statement or expression of the AST.
+    Node(NodeKind),
+}
+
+/// What a leaf of the tree holds.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SyntaxTokenKind {
+    Token(Token),
+    Trivia(TriviaKind),
+}
+
+/// A leaf: one token or one piece of trivia, with its text.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SyntaxToken {
+    pub kind: SyntaxTokenKind,
+    pub span: Span,
+    pub text: String,
+}
+
+impl SyntaxToken {
+    pub fn is_trivia(&self) -> bool {
+        matches!(self.kind, SyntaxTokenKind::Trivia(_))
+    }
+}
+
+/// A child of a [`SyntaxNode`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum SyntaxElement {
+    Node(SyntaxNode),
+    Token(SyntaxToken),
+}
+
+impl SyntaxElement {
+    pub fn span(&self) -> &Span {
+        match self {
+            SyntaxElement::Node(node) => &node.span,
+            SyntaxElement::Token(token) => &token.span,
+        }
+    }
+}
+
+/// An interior node of the tree.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SyntaxNode {
+    pub kind: SyntaxKind,
+    /// Byte range covered by the node's children.
+    pub span: Span,
+    pub children: Vec<SyntaxElement>,
+}
+
+impl SyntaxNode {
+    /// The exact source text covered by the node.
+    pub fn text(&self) -> String {
+        let mut text = String::with_capacity(self.span.len());
+        for token in self.tokens() {
+            text.push_str(&token.text);
+        }
+        text
+    }
+
+    /// Every leaf under the node, trivia included, in source order.
+    pub fn tokens(&self) -> Vec<&SyntaxToken> {
+        let mut tokens = Vec::new();
+        self.collect_tokens(&mut tokens);
+        tokens
+    }
+
+    fn collect_tokens<'a>(&'a self, tokens: &mut Vec<&'a SyntaxToken>) {
+        for child in &self.children {
+            match child {
+                SyntaxElement::Node(node) => node.collect_tokens(tokens),
+                SyntaxElement::Token(token) => tokens.push(token),
+            }
+        }
+    }
+
+    /// The node and every node below it, in pre-order.
+    pub fn descendants(&self) -> Vec<&SyntaxNode> {
+        let mut nodes = vec![self];
+        let mut index = 0;
+        while index < nodes.len() {
+            let children = nodes[index].child_nodes();
+            nodes.splice(index + 1..index + 1, children);
+            index += 1;
+        }
+        nodes
+    }
+
+    /// Direct children that are nodes.
+    pub fn child_nodes(&self) -> Vec<&SyntaxNode> {
+        self.children
+            .iter()
+            .filter_map(|child| match child {
+                SyntaxElement::Node(node) => Some(node),
+                SyntaxElement::Token(_) => None,
+            })
+            .collect()
+    }
+
+    /// The innermost node whose span contains `span`.
+    pub fn covering_node(&self, span: &Span) -> Option<&SyntaxNode> {
+        if span.start < self.span.start || span.end > self.span.end {
+            return None;
+        }
+        self.child_nodes()
+            .into_iter()
+            .find_map(|child| child.covering_node(span))
+            .or(Some(self))
+    }
+
+    fn write_indented(&self, f: &mut fmt::Formatter<'_>, depth: usize) -> fmt::Result {
+        let name = match self.kind {
+            SyntaxKind::SourceFile => "SourceFile".to_string(),
+            SyntaxKind::Node(kind) => format!("{kind:?}"),
+        };
+        writeln!(
+            f,
+            "{}{name}@{}..{}",
+            "  ".repeat(depth),
+            self.span.start,
+            self.span.end
+        )?;
+        for child in &self.children {
+            match child {
+                SyntaxElement::Node(node) => node.write_indented(f, depth + 1)?,
+                SyntaxElement::Token(token) => {
+                    let name = match &token.kind {
+                        SyntaxTokenKind::Token(token) => token_name(token),
+                        SyntaxTokenKind::Trivia(TriviaKind::Comment(kind)) => {
+                            format!("{kind:?}Comment")
+                        }
+                        SyntaxTokenKind::Trivia(kind) => format!("{kind:?}"),
+                    };
+                    writeln!(
+                        f,
+                        "{}{name}@{}..{} {:?}",
+                        "  ".repeat(depth + 1),
+                        token.span.start,
+                        token.span.end,
+                        token.text
+                    )?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for SyntaxNode {
+    /// Writes the tree one element per line, indented by depth, e.g.
+    /// `VarDecl@0..10` and `Whitespace@3..4 " "`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write_indented(f, 0)
+    }
+}
+
+/// The token's variant name, without any payload.
+fn token_name(token: &Token) -> String {
+    let debug = format!("{token:?}");
+    match debug.find('(') {
+        Some(paren) => debug[..paren].to_string(),
+        None => debug,
+    }
+}
+
+/// Builds the concrete syntax tree of `source` from its lexed tokens and
+/// trivia and the AST parsed from those tokens.
+///
+/// The result reproduces `source` exactly whenever `tokens` and `trivia` come
+/// from lexing it without errors.
+pub fn build(source: &str, tokens: &[SpannedToken], trivia: &[Trivia], ast: &[Stmt]) -> SyntaxNode {
+    let mut builder = Builder {
+        source,
+        elements: merge_trivia(tokens, trivia),
+        cursor: 0,
+        stack: vec![SyntaxNode {
+            kind: SyntaxKind::SourceFile,
+            span: 0..0,
+            children: Vec::new(),
+        }],
+    };
+    walk(ast, &mut builder);
+    let end = builder.elements.len();
+    builder.emit_until(end);
+
+    let mut root = builder.stack.pop().expect("root node");
+    root.span = 0..root.children.last().map_or(0, |child| child.span().end);
+    root
+}
+
+/// Nests leaves into nodes as the AST walk enters and leaves each node.
+struct Builder<'s> {
+    source: &'s str,
+    elements: Vec<Element<'s>>,
+    /// Index of the first element not yet placed in the tree.
+    cursor: usize,
+    /// Nodes under construction, the root first.
+    stack: Vec<SyntaxNode>,
+}
+
+impl Builder<'_> {
+    fn enter(&mut self, kind: NodeKind, span: &Span) {
+        let first = self.elements[self.cursor..]
+            .iter()
+            .position(|element| element.span().start >= span.start)
+            .map_or(self.elements.len(), |offset| self.cursor + offset);
+        self.emit_until(first);
+        self.stack.push(SyntaxNode {
+            kind: SyntaxKind::Node(kind),
+            span: span.start..span.start,
+            children: Vec::new(),
+        });
+    }
+
+    fn exit(&mut self, span: &Span) {
+        // Take everything up to the node's last token; trivia after it stays
+        // with the parent.
+        let last = self.elements[self.cursor..]
+            .iter()
+            .take_while(|element| element.span().end <= span.end)
+            .enumerate()
+            .filter(|(_, element)| matches!(element, Element::Token(_)))
+            .last()
+            .map_or(self.cursor, |(offset, _)| self.cursor + offset + 1);
+        self.emit_until(last);
+
+        let mut node = self.stack.pop().expect("node entered");
+        if let (Some(first), Some(last)) = (node.children.first(), node.children.last()) {
+            node.span = first.span().start..last.span().end;
+        }
+        self.stack
+            .last_mut()
+            .expect("root node")
+            .children
+            .push(SyntaxElement::Node(node));
+    }
+
+    /// Appends elements up to, but not including, `end` to the innermost node.
+    fn emit_until(&mut self, end: usize) {
+        if end <= self.cursor {
+            return;
+        }
+        let parent = self.stack.last_mut().expect("root node");
+        for element in &self.elements[self.cursor..end] {
+            let span = element.span();
+            let kind = match element {
+                Element::Token((_, token, _)) => SyntaxTokenKind::Token(token.clone()),
+                Element::Trivia(trivia) => SyntaxTokenKind::Trivia(trivia.kind),
+            };
+            parent.children.push(SyntaxElement::Token(SyntaxToken {
+                kind,
+                text: self.source[span.clone()].to_string(),
+                span,
+            }));
+        }
+        self.cursor = end;
+    }
+}
+
+impl<'a> Visitor<'a> for Builder<'_> {
+    fn enter_stmt(&mut self, stmt: &'a Stmt, _cx: &VisitContext<'a>) {
+        self.enter(stmt.kind(), stmt.span());
+    }
+
+    fn exit_stmt(&mut self, stmt: &'a Stmt, _cx: &VisitContext<'a>) {
+        self.exit(stmt.span());
+    }
+
+    fn enter_expr(&mut self, expr: &'a Expr, _cx: &VisitContext<'a>) {
+        self.enter(expr.kind(), expr.span());
+    }
+
+    fn exit_expr(&mut self, expr: &'a Expr, _cx: &VisitContext<'a>) {
+        self.exit(expr.span());
+    }
+}
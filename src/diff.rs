@@ -0,0 +1,173 @@
+//! Reading unified diffs to find the lines a change touched.
+//!
+//! `blazelint --diff` lints whole files as usual and then keeps only the
+//! diagnostics whose spans touch a line the diff added or modified. A modified
+//! line appears in a diff as a removal followed by an addition, so both cases
+//! are the `+` lines of each hunk, numbered as in the new file. Lines that
+//! were only removed have nothing left to lint.
+//!
+//! Both `git diff` output (with its `a/` and `b/` prefixes) and plain
+//! `diff -u` output are accepted. Deleted files are skipped.
+
+use crate::discovery::normalize_file_name;
+use crate::errors::Diagnostic;
+use crate::report::{byte_to_line_col, compute_line_starts};
+use std::collections::{BTreeSet, HashMap};
+
+/// Added and modified lines per file, taken from a unified diff.
+#[derive(Debug, Clone, Default)]
+pub struct ChangedLines {
+    /// 1-based line numbers in the new version of each file.
+    files: HashMap<String, BTreeSet<usize>>,
+}
+
+impl ChangedLines {
+    /// Parses a unified diff. Text outside file headers and hunks, such as
+    /// `git diff`'s `diff --git` and `index` lines, is ignored.
+    pub fn parse(diff: &str) -> Result<Self, String> {
+        let mut changed = ChangedLines::default();
+        let mut lines = diff.lines().enumerate().peekable();
+        // The file the current hunks belong to; `None` while it is deleted.
+        let mut file: Option<String> = None;
+        let mut seen_header = false;
+
+        while let Some((index, line)) = lines.next() {
+            if let Some(path) = line.strip_prefix("+++ ") {
+                file = new_file_name(path);
+                seen_header = true;
+                if let Some(file) = &file {
+                    changed.files.entry(file.clone()).or_default();
+                }
+                continue;
+            }
+            let Some(header) = line.strip_prefix("@@ ") else {
+                continue;
+            };
+            let (mut old_remaining, mut new_remaining, mut line_number) = parse_hunk_header(header)
+                .ok_or_else(|| format!("line {}: malformed hunk header '{line}'", index + 1))?;
+            if !seen_header {
+                return Err(format!("line {}: hunk before any '+++' header", index + 1));
+            }
+
+            while old_remaining + new_remaining > 0 {
+                let Some((index, line)) = lines.next() else {
+                    return Err("diff ends in the middle of a hunk".to_string());
+                };
+                match line.as_bytes().first() {
+                    Some(b'+') if new_remaining > 0 => {
+                        if let Some(file) = &file {
+                            changed
+                                .files
+                                .get_mut(file)
+                                .expect("file registered at its header")
+                                .insert(line_number);
+                        }
+                        line_number += 1;
+                        new_remaining -= 1;
+                    }
+                    Some(b'-') if old_remaining > 0 => old_remaining -= 1,
+                    // Some tools drop the space that marks an empty context line.
+                    Some(b' ') | None if old_remaining > 0 && new_remaining > 0 => {
+                        line_number += 1;
+                        old_remaining -= 1;
+                        new_remaining -= 1;
+                    }
+                    Some(b'\\') => {}
+                    _ => {
+                        return Err(format!(
+                            "line {}: hunk does not match the line counts in its header",
+                            index + 1
+                        ))
+                    }
+                }
+            }
+            // "\ No newline at end of file" may follow the last hunk line.
+            while lines.next_if(|(_, line)| line.starts_with('\\')).is_some() {}
+        }
+        Ok(changed)
+    }
+
+    /// Whether any of the 1-based lines `first..=last` of `file` was added or
+    /// modified.
+    pub fn touches(&self, file: &str, first: usize, last: usize) -> bool {
+        self.lines_of(file)
+            .is_some_and(|lines| lines.range(first..=last).next().is_some())
+    }
+
+    /// Splits the diagnostics found in `source` of `file` into those that
+    /// touch a changed line and those that do not.
+    pub fn partition(
+        &self,
+        file: &str,
+        source: &str,
+        diagnostics: Vec<Diagnostic>,
+    ) -> (Vec<Diagnostic>, Vec<Diagnostic>) {
+        let line_starts = compute_line_starts(source);
+        diagnostics.into_iter().partition(|diagnostic| {
+            let span = &diagnostic.span;
+            let (first, _) = byte_to_line_col(&line_starts, span.start);
+            let end = if span.end > span.start {
+                span.end - 1
+            } else {
+                span.start
+            };
+            let (last, _) = byte_to_line_col(&line_starts, end);
+            self.touches(file, first, last)
+        })
+    }
+
+    /// The changed lines of `file`, named relative to the directory the diff
+    /// was taken in. Names are compared after normalizing separators; failing
+    /// an exact match, a name matches the one diff path it is a suffix of at a
+    /// `/`, for a diff taken above the directory names are relative to. A name
+    /// that is a suffix of several diff paths matches none of them, and a
+    /// diff path never matches a longer name.
+    fn lines_of(&self, file: &str) -> Option<&BTreeSet<usize>> {
+        let file = normalize_file_name(file);
+        if let Some(lines) = self.files.get(&file) {
+            return Some(lines);
+        }
+        let mut matches = self.files.iter().filter(|(path, _)| {
+            path.strip_suffix(file.as_str())
+                .is_some_and(|prefix| prefix.ends_with('/'))
+        });
+        match (matches.next(), matches.next()) {
+            (Some((_, lines)), None) => Some(lines),
+            _ => None,
+        }
+    }
+}
+
+/// The file named by a `+++` header, or `None` for a deleted file.
+fn new_file_name(header: &str) -> Option<String> {
+    // `diff -u` follows the name with a tab and a timestamp.
+    let path = header.split('\t').next().unwrap_or(header).trim_end();
+    let path = path
+        .strip_prefix('"')
+        .and_then(|path| path.strip_suffix('"'))
+        .unwrap_or(path);
+    if path == "/dev/null" {
+        return None;
+    }
+    let path = path.strip_prefix("b/").unwrap_or(path);
+    Some(normalize_file_name(path))
+}
+
+/// Reads `-l,s +l,s @@` into the old and new line counts and the first new
+/// line. Counts default to 1 when omitted.
+fn parse_hunk_header(header: &str) -> Option<(usize, usize, usize)> {
+    let mut parts = header.split_whitespace();
+    let (_, old_count) = parse_range(parts.next()?.strip_prefix('-')?)?;
+    let (new_start, new_count) = parse_range(parts.next()?.strip_prefix('+')?)?;
+    if parts.next() != Some("@@") {
+        return None;
+    }
+    Some((old_count, new_count, new_start))
+}
+
+fn parse_range(range: &str) -> Option<(usize, usize)> {
+    match range.split_once(',') {
+        Some((start, count)) => Some((start.parse().ok()?, count.parse().ok()?)),
+        None => Some((range.parse().ok()?, 1)),
+    }
+}
@@ -0,0 +1,190 @@
+//! Discovery of Ballerina source files under user-supplied paths.
+//!
+//! Paths may name individual files or directories. Directories are walked
+//! recursively for `.bal` files, skipping hidden entries and Ballerina build
+//! output (`target/`), and every candidate is checked against the ignore globs.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// File extension used by Ballerina sources.
+pub const BALLERINA_EXTENSION: &str = "bal";
+
+/// Directory names that are never descended into while walking a directory.
+const SKIPPED_DIRS: &[&str] = &["target"];
+
+/// A compiled set of ignore globs.
+///
+/// Supported syntax: `*` matches any run of characters within one path
+/// segment, `?` matches a single character, and `**` matches any number of
+/// whole segments. Patterns without a `/` are matched against each path
+/// component (so `generated` or `*_test.bal` match at any depth); patterns with
+/// a `/` are matched against the whole path, relative to the walked root.
+#[derive(Debug, Clone, Default)]
+pub struct IgnoreSet {
+    patterns: Vec<String>,
+}
+
+impl IgnoreSet {
+    /// Builds an ignore set from raw glob patterns.
+    pub fn new<I, S>(patterns: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            patterns: patterns
+                .into_iter()
+                .map(|p| normalize(&p.into()).trim_end_matches('/').to_string())
+                .filter(|p| !p.is_empty())
+                .collect(),
+        }
+    }
+
+    /// Returns true when `path` matches any of the ignore patterns.
+    pub fn is_ignored(&self, path: &Path) -> bool {
+        if self.patterns.is_empty() {
+            return false;
+        }
+        let normalized = normalize(&path.to_string_lossy());
+        let normalized = normalized.trim_start_matches("./");
+        let components: Vec<&str> = normalized.split('/').filter(|c| !c.is_empty()).collect();
+
+        self.patterns.iter().any(|pattern| {
+            if pattern.contains('/') {
+                glob_match(pattern.trim_start_matches("./"), normalized)
+            } else {
+                components
+                    .iter()
+                    .any(|component| segment_match(pattern, component))
+            }
+        })
+    }
+}
+
+/// Expands `paths` into the sorted, de-duplicated list of `.bal` files to lint.
+///
+/// Explicit file paths are accepted regardless of their extension; directories
+/// contribute only `.bal` files. Returns an error for paths that do not exist
+/// or cannot be read.
+pub fn collect_files<P: AsRef<Path>>(paths: &[P], ignore: &IgnoreSet) -> io::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for path in paths {
+        let path = path.as_ref();
+        let metadata = fs::metadata(path).map_err(|err| {
+            io::Error::new(
+                err.kind(),
+                format!("cannot access {}: {err}", path.display()),
+            )
+        })?;
+
+        if metadata.is_dir() {
+            walk_dir(path, path, ignore, &mut files)?;
+        } else if !ignore.is_ignored(path) {
+            files.push(path.to_path_buf());
+        }
+    }
+    files.sort();
+    files.dedup();
+    Ok(files)
+}
+
+/// Recursively collects `.bal` files beneath `dir`.
+fn walk_dir(
+    root: &Path,
+    dir: &Path,
+    ignore: &IgnoreSet,
+    files: &mut Vec<PathBuf>,
+) -> io::Result<()> {
+    let mut entries = fs::read_dir(dir)
+        .map_err(|err| io::Error::new(err.kind(), format!("cannot read {}: {err}", dir.display())))?
+        .collect::<Result<Vec<_>, _>>()?;
+    entries.sort_by_key(|entry| entry.file_name());
+
+    for entry in entries {
+        let path = entry.path();
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        let relative = path.strip_prefix(root).unwrap_or(&path);
+        if name.starts_with('.') || ignore.is_ignored(relative) {
+            continue;
+        }
+
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            if !SKIPPED_DIRS.contains(&name.as_ref()) {
+                walk_dir(root, &path, ignore, files)?;
+            }
+        } else if path
+            .extension()
+            .is_some_and(|ext| ext == BALLERINA_EXTENSION)
+        {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Converts separators to `/` and drops any leading `./`, so a file has the
+/// same name however its path was spelled on the command line.
+pub fn normalize_file_name(name: &str) -> String {
+    let name = normalize(name);
+    let mut rest = name.as_str();
+    while let Some(stripped) = rest.strip_prefix("./") {
+        rest = stripped;
+    }
+    rest.to_string()
+}
+
+/// Converts platform separators to `/` so globs behave the same everywhere.
+fn normalize(path: &str) -> String {
+    path.replace('\\', "/")
+}
+
+/// Matches a `/`-separated glob against a `/`-separated path.
+fn glob_match(pattern: &str, path: &str) -> bool {
+    let pattern: Vec<&str> = pattern.split('/').collect();
+    let path: Vec<&str> = path.split('/').filter(|c| !c.is_empty()).collect();
+    match_segments(&pattern, &path)
+}
+
+/// Matches glob segments against path segments, expanding `**` greedily.
+fn match_segments(pattern: &[&str], path: &[&str]) -> bool {
+    match pattern.split_first() {
+        None => path.is_empty(),
+        Some((&"**", rest)) => (0..=path.len()).any(|skip| match_segments(rest, &path[skip..])),
+        Some((first, rest)) => match path.split_first() {
+            Some((segment, path_rest)) => {
+                segment_match(first, segment) && match_segments(rest, path_rest)
+            }
+            None => false,
+        },
+    }
+}
+
+/// Matches a single path segment against a glob segment (`*` and `?` only).
+fn segment_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, t));
+            p += 1;
+        } else if let Some((star_p, star_t)) = backtrack {
+            p = star_p + 1;
+            t = star_t + 1;
+            backtrack = Some((star_p, star_t + 1));
+        } else {
+            return false;
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
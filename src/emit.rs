@@ -0,0 +1,187 @@
+//! Debug dumps of the frontend's intermediate results, selected with `--emit`.
+//!
+//! Each request names what to dump and optionally a file to write it to;
+//! without a file the dump goes to stdout next to the file's diagnostics.
+
+use blazelint::ast::Stmt;
+use blazelint::cst;
+use blazelint::json::JsonValue;
+use blazelint::report::{byte_to_line_col, compute_line_starts};
+use blazelint::LintReport;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::PathBuf;
+
+/// An intermediate result that can be dumped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EmitKind {
+    /// The token stream with byte ranges.
+    Tokens,
+    /// The parsed statements in Rust debug notation.
+    Ast,
+    /// The parsed statements as one JSON document covering every file.
+    AstJson,
+    /// Declarations bound by semantic analysis, indented by scope depth.
+    Symbols,
+    /// The lossless concrete syntax tree, trivia included.
+    Cst,
+}
+
+impl EmitKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            EmitKind::Tokens => "Tokens",
+            EmitKind::Ast => "AST",
+            EmitKind::AstJson => "AST JSON",
+            EmitKind::Symbols => "Symbols",
+            EmitKind::Cst => "CST",
+        }
+    }
+}
+
+/// One `--emit KIND[=FILE]` option.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EmitRequest {
+    pub kind: EmitKind,
+    /// Destination file; stdout when absent.
+    pub path: Option<PathBuf>,
+}
+
+impl EmitRequest {
+    /// Parses the value given to `--emit`.
+    pub fn parse(value: &str) -> Result<Self, String> {
+        let (kind, path) = match value.split_once('=') {
+            Some((kind, path)) if !path.is_empty() => (kind, Some(PathBuf::from(path))),
+            Some(_) => {
+                return Err(format!(
+                    "option '--emit' is missing a file name in '{value}'"
+                ))
+            }
+            None => (value, None),
+        };
+        let kind = match kind {
+            "tokens" => EmitKind::Tokens,
+            "ast" => EmitKind::Ast,
+            "ast-json" => EmitKind::AstJson,
+            "symbols" => EmitKind::Symbols,
+            "cst" => EmitKind::Cst,
+            other => {
+                return Err(format!(
+                    "option '--emit' expects 'tokens', 'ast', 'ast-json', 'symbols', or 'cst', found '{other}'"
+                ))
+            }
+        };
+        Ok(Self { kind, path })
+    }
+
+    /// Whether output is written once at the end rather than per file.
+    fn is_buffered(&self) -> bool {
+        self.path.is_some() || self.kind == EmitKind::AstJson
+    }
+}
+
+/// Produces the dumps requested on the command line as files are linted.
+pub struct Emitter {
+    requests: Vec<EmitRequest>,
+    /// Pending text for buffered requests, parallel to `requests`.
+    buffers: Vec<String>,
+    /// Per-file entries for `ast-json`.
+    ast_files: Vec<JsonValue>,
+}
+
+impl Emitter {
+    pub fn new(requests: &[EmitRequest]) -> Self {
+        Self {
+            requests: requests.to_vec(),
+            buffers: vec![String::new(); requests.len()],
+            ast_files: Vec::new(),
+        }
+    }
+
+    /// Dumps the intermediate results of one linted file.
+    pub fn record(&mut self, report: &LintReport, source: &str) {
+        if self
+            .requests
+            .iter()
+            .any(|request| request.kind == EmitKind::AstJson)
+        {
+            self.ast_files.push(JsonValue::object([
+                ("file", report.file_name.as_str().into()),
+                (
+                    "ast",
+                    JsonValue::Array(report.ast.iter().map(Stmt::to_json).collect()),
+                ),
+            ]));
+        }
+
+        for (request, buffer) in self.requests.iter().zip(&mut self.buffers) {
+            if request.kind == EmitKind::AstJson {
+                continue;
+            }
+            let dump = render(request.kind, report, source);
+            if request.is_buffered() {
+                buffer.push_str(&dump);
+            } else {
+                print!("{dump}");
+            }
+        }
+    }
+
+    /// Writes buffered dumps to their destinations.
+    pub fn finish(self) -> Result<(), String> {
+        let ast_json = JsonValue::Array(self.ast_files).to_pretty_string() + "\n";
+        for (request, buffer) in self.requests.iter().zip(self.buffers) {
+            if !request.is_buffered() {
+                continue;
+            }
+            let contents = match request.kind {
+                EmitKind::AstJson => ast_json.as_str(),
+                _ => buffer.as_str(),
+            };
+            match &request.path {
+                Some(path) => fs::write(path, contents)
+                    .map_err(|err| format!("cannot write {}: {err}", path.display()))?,
+                None => print!("{contents}"),
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Renders a text dump of one file, starting with a header naming it.
+fn render(kind: EmitKind, report: &LintReport, source: &str) -> String {
+    let mut out = format!("--- {} ({}) ---\n", kind.as_str(), report.file_name);
+    match kind {
+        EmitKind::Tokens => {
+            for (start, token, end) in &report.tokens {
+                let _ = writeln!(out, "Token: {token:?} ({start}..{end})");
+            }
+        }
+        EmitKind::Ast => {
+            for stmt in &report.ast {
+                let _ = writeln!(out, "{stmt:#?}");
+            }
+        }
+        EmitKind::Symbols => {
+            let line_starts = compute_line_starts(source);
+            for symbol in report.semantic.symbols() {
+                let (line, column) = byte_to_line_col(&line_starts, symbol.span.start);
+                let _ = writeln!(
+                    out,
+                    "{}{} {}: {} @ {line}:{column}",
+                    "  ".repeat(symbol.scope_depth),
+                    symbol.kind.as_str(),
+                    symbol.name,
+                    symbol.ty,
+                );
+            }
+        }
+        EmitKind::Cst => {
+            let tree = cst::build(source, &report.tokens, &report.trivia, &report.ast);
+            let _ = write!(out, "{tree}");
+        }
+        EmitKind::AstJson => unreachable!("ast-json is rendered as a single document"),
+    }
+    out.push('\n');
+    out
+}
@@ -4,7 +4,9 @@
 //! types in this module allow each stage of the pipeline to report failures in
 //! a structured way, retaining byte spans and auxiliary notes.
 
+use std::fmt;
 use std::ops::Range;
+use std::str::FromStr;
 
 /// Byte range within the original source file.
 pub type Span = Range<usize>;
@@ -16,6 +18,70 @@ pub enum DiagnosticKind {
     Parse,
     Semantic,
     Linter,
+    /// Problems in a `.blazerc` configuration file.
+    Config,
+    /// Unused or malformed `blazelint-disable` comments.
+    Suppression,
+    /// Files that `blazelint fmt --check` would reformat.
+    Format,
+}
+
+impl DiagnosticKind {
+    /// Short lowercase label naming the stage that produced the diagnostic.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DiagnosticKind::Lex => "lexer",
+            DiagnosticKind::Parse => "parser",
+            DiagnosticKind::Semantic => "semantic",
+            DiagnosticKind::Linter => "linter",
+            DiagnosticKind::Config => "config",
+            DiagnosticKind::Suppression => "suppression",
+            DiagnosticKind::Format => "format",
+        }
+    }
+}
+
+/// How serious a diagnostic is, ordered from least to most severe.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    Hint,
+    Info,
+    Warning,
+    Error,
+}
+
+impl Severity {
+    /// Lowercase name used in output and configuration files.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Severity::Hint => "hint",
+            Severity::Info => "info",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        }
+    }
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Severity {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "hint" => Ok(Severity::Hint),
+            "info" => Ok(Severity::Info),
+            "warning" | "warn" => Ok(Severity::Warning),
+            "error" => Ok(Severity::Error),
+            other => Err(format!(
+                "unknown severity '{other}' (expected error, warning, info, or hint)"
+            )),
+        }
+    }
 }
 
 /// Structured diagnostic message produced by either the lexer or parser.
@@ -23,40 +89,161 @@ pub enum DiagnosticKind {
 #[derive(Debug, Clone)]
 pub struct Diagnostic {
     pub kind: DiagnosticKind,
+    pub severity: Severity,
+    /// Stable identifier such as `BL3001`, documented by `blazelint --explain`.
+    pub code: Option<&'static str>,
+    /// Name of the lint rule that produced the diagnostic, if any.
+    pub rule: Option<String>,
     pub message: String,
     pub span: Span,
+    /// Short text shown under the primary span, such as "redeclared here".
+    pub label: Option<String>,
+    /// Related locations shown alongside the primary span.
+    pub secondary: Vec<Label>,
     pub notes: Vec<String>,
+    /// Suggestions for resolving the problem, shown as `help:` lines.
+    pub help: Vec<String>,
+    /// Set when the diagnostic was silenced rather than reported.
+    pub suppression: Option<Suppression>,
+    /// Suggested edits that resolve the diagnostic, if the rule offers any.
+    pub fix: Option<Box<Fix>>,
+}
+
+/// A related location with a short explanation, such as the earlier
+/// declaration of a redeclared name.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Label {
+    pub span: Span,
+    pub message: String,
+}
+
+/// How confident a rule is that its suggested fix is correct.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Applicability {
+    /// Safe to apply without review; `--fix` applies these.
+    MachineApplicable,
+    /// Probably right, but may change behaviour; shown for manual review only.
+    MaybeIncorrect,
+}
+
+impl Applicability {
+    /// Name used in machine-readable output.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Applicability::MachineApplicable => "machine-applicable",
+            Applicability::MaybeIncorrect => "maybe-incorrect",
+        }
+    }
+}
+
+/// Replacement of the text in `span` with `replacement`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextEdit {
+    pub span: Span,
+    pub replacement: String,
+}
+
+/// A suggested change made of one or more non-overlapping edits.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fix {
+    /// Short description of the change, such as "Rename to `fooBar`".
+    pub message: String,
+    pub applicability: Applicability,
+    /// Edits in source order; they are applied together or not at all.
+    pub edits: Vec<TextEdit>,
+}
+
+/// Records why a diagnostic was silenced.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Suppression {
+    /// Reason given after `--` in the suppression comment, if any.
+    pub justification: Option<String>,
 }
 
 impl Diagnostic {
-    /// Creates a new diagnostic with the provided message and span.
+    /// Creates a new error-level diagnostic with the provided message and span.
     pub fn new(kind: DiagnosticKind, message: impl Into<String>, span: Span) -> Self {
         Self {
             kind,
+            severity: Severity::Error,
+            code: None,
+            rule: None,
             message: message.into(),
             span,
+            label: None,
+            secondary: Vec::new(),
             notes: Vec::new(),
+            help: Vec::new(),
+            suppression: None,
+            fix: None,
         }
     }
 
+    /// Overrides the severity of the diagnostic, returning the mutated value.
+    pub fn with_severity(mut self, severity: Severity) -> Self {
+        self.severity = severity;
+        self
+    }
+
+    /// Records the stable code of the diagnostic, returning the mutated value.
+    pub fn with_code(mut self, code: &'static str) -> Self {
+        self.code = Some(code);
+        self
+    }
+
+    /// Records the lint rule responsible for the diagnostic, returning the mutated value.
+    pub fn with_rule(mut self, rule: impl Into<String>) -> Self {
+        self.rule = Some(rule.into());
+        self
+    }
+
+    /// Attaches a suggested fix to the diagnostic, returning the mutated value.
+    pub fn with_fix(mut self, fix: Fix) -> Self {
+        self.fix = Some(Box::new(fix));
+        self
+    }
+
     /// Attaches an additional note to the diagnostic, returning the mutated value.
     pub fn with_note(mut self, note: impl Into<String>) -> Self {
         self.notes.push(note.into());
         self
     }
+
+    /// Labels the primary span, returning the mutated value.
+    pub fn with_label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    /// Adds a related location, returning the mutated value.
+    pub fn with_secondary(mut self, span: Span, message: impl Into<String>) -> Self {
+        self.secondary.push(Label {
+            span,
+            message: message.into(),
+        });
+        self
+    }
+
+    /// Adds a suggestion for resolving the problem, returning the mutated value.
+    pub fn with_help(mut self, help: impl Into<String>) -> Self {
+        self.help.push(help.into());
+        self
+    }
 }
 
 /// Error emitted when the lexer fails to tokenise the input stream.
 #[derive(Debug, Clone)]
 pub struct LexError {
+    pub code: &'static str,
     pub message: String,
     pub span: Span,
 }
 
 impl LexError {
-    /// Creates a new lexical error for the given span.
-    pub fn new(message: impl Into<String>, span: Span) -> Self {
+    /// Creates a new lexical error with the given code for the given span.
+    pub fn new(code: &'static str, message: impl Into<String>, span: Span) -> Self {
         Self {
+            code,
             message: message.into(),
             span,
         }
@@ -65,13 +252,14 @@ impl LexError {
 
 impl From<LexError> for Diagnostic {
     fn from(err: LexError) -> Self {
-        Diagnostic::new(DiagnosticKind::Lex, err.message, err.span)
+        Diagnostic::new(DiagnosticKind::Lex, err.message, err.span).with_code(err.code)
     }
 }
 
 /// Error produced while parsing the token stream into an AST.
 #[derive(Debug, Clone)]
 pub struct ParseError {
+    pub code: &'static str,
     pub message: String,
     pub span: Span,
     pub expected: Option<&'static str>,
@@ -79,9 +267,16 @@ pub struct ParseError {
 
 #[allow(dead_code)]
 impl ParseError {
-    /// Creates a new parse error with an optional expectation hint.
-    pub fn new(message: impl Into<String>, span: Span, expected: Option<&'static str>) -> Self {
+    /// Creates a new parse error with the given code and an optional
+    /// expectation hint.
+    pub fn new(
+        code: &'static str,
+        message: impl Into<String>,
+        span: Span,
+        expected: Option<&'static str>,
+    ) -> Self {
         Self {
+            code,
             message: message.into(),
             span,
             expected,
@@ -91,7 +286,8 @@ impl ParseError {
 
 impl From<ParseError> for Diagnostic {
     fn from(err: ParseError) -> Self {
-        let mut diagnostic = Diagnostic::new(DiagnosticKind::Parse, err.message, err.span);
+        let mut diagnostic =
+            Diagnostic::new(DiagnosticKind::Parse, err.message, err.span).with_code(err.code);
         if let Some(expected) = err.expected {
             diagnostic = diagnostic.with_note(format!("expected: {expected}"));
         }
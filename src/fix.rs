@@ -0,0 +1,297 @@
+//! Applying suggested fixes and describing the result as a diff.
+//!
+//! Rules attach a [`Fix`] to their diagnostics; [`apply_fixes`] applies every
+//! machine-applicable fix whose edits do not overlap an earlier one, and
+//! [`unified_diff`] renders the change for review.
+
+use crate::errors::{Applicability, Diagnostic, Fix, Span, TextEdit};
+use crate::semantic::{ScopeId, SemanticModel};
+
+/// Lines of unchanged context shown around each change in a diff.
+const DIFF_CONTEXT: usize = 3;
+
+/// Builds a fix renaming the declaration whose name is at `span` to
+/// `new_name`.
+///
+/// The declared name and every identifier `semantic` resolves to it are
+/// renamed; other uses of the same text, such as record or class fields and
+/// map keys, are left alone. The fix is machine-applicable only when no
+/// declaration named `new_name` is bound in a scope enclosing, or enclosed
+/// by, the renamed one's. Returns `None` when `span` does not name a
+/// declaration.
+pub fn rename_identifier(
+    source: &str,
+    semantic: &SemanticModel,
+    span: &Span,
+    new_name: &str,
+) -> Option<Fix> {
+    let old_name = source.get(span.clone())?;
+    let symbol = semantic
+        .symbols()
+        .iter()
+        .find(|symbol| symbol.name_span.as_ref() == Some(span))?;
+
+    let mut spans = semantic.references(symbol.id);
+    spans.push(span.clone());
+    spans.sort_by_key(|span| (span.start, span.end));
+    spans.dedup();
+    let edits = spans
+        .into_iter()
+        .map(|span| TextEdit {
+            span,
+            replacement: new_name.to_string(),
+        })
+        .collect();
+
+    let collides = semantic.symbols().iter().any(|other| {
+        other.name == new_name
+            && (encloses(semantic, other.scope, symbol.scope)
+                || encloses(semantic, symbol.scope, other.scope))
+    });
+    Some(Fix {
+        message: format!("Rename `{old_name}` to `{new_name}`"),
+        applicability: if collides {
+            Applicability::MaybeIncorrect
+        } else {
+            Applicability::MachineApplicable
+        },
+        edits,
+    })
+}
+
+/// Whether `outer` is `inner` or one of the scopes it is nested in.
+fn encloses(semantic: &SemanticModel, outer: ScopeId, inner: ScopeId) -> bool {
+    let mut scope = Some(inner);
+    while let Some(id) = scope {
+        if id == outer {
+            return true;
+        }
+        scope = semantic.scope(id).parent;
+    }
+    false
+}
+
+/// The outcome of [`apply_fixes`].
+#[derive(Debug, Clone)]
+pub struct FixResult {
+    /// The source with the accepted fixes applied.
+    pub source: String,
+    /// Number of fixes applied.
+    pub applied: usize,
+}
+
+/// Applies the machine-applicable fixes attached to `diagnostics`.
+///
+/// Fixes are considered in source order; a fix is skipped when any of its
+/// edits overlaps an edit already accepted, or falls outside `source`.
+pub fn apply_fixes(source: &str, diagnostics: &[Diagnostic]) -> FixResult {
+    let mut fixes: Vec<&Fix> = diagnostics
+        .iter()
+        .filter_map(|diagnostic| diagnostic.fix.as_deref())
+        .filter(|fix| fix.applicability == Applicability::MachineApplicable)
+        .filter(|fix| !fix.edits.is_empty())
+        .collect();
+    fixes.sort_by_key(|fix| fix.edits.iter().map(|edit| edit.span.start).min());
+
+    let mut accepted: Vec<&TextEdit> = Vec::new();
+    let mut applied = 0;
+    for fix in fixes {
+        let usable = fix.edits.iter().all(|edit| {
+            source.get(edit.span.clone()).is_some()
+                && accepted
+                    .iter()
+                    .all(|other| !overlaps(&edit.span, &other.span))
+        });
+        let self_overlapping = fix.edits.iter().enumerate().any(|(i, edit)| {
+            fix.edits[i + 1..]
+                .iter()
+                .any(|other| overlaps(&edit.span, &other.span))
+        });
+        if usable && !self_overlapping {
+            accepted.extend(&fix.edits);
+            applied += 1;
+        }
+    }
+
+    accepted.sort_by_key(|edit| edit.span.start);
+    let mut output = String::with_capacity(source.len());
+    let mut cursor = 0;
+    for edit in accepted {
+        output.push_str(&source[cursor..edit.span.start]);
+        output.push_str(&edit.replacement);
+        cursor = edit.span.end;
+    }
+    output.push_str(&source[cursor..]);
+
+    FixResult {
+        source: output,
+        applied,
+    }
+}
+
+/// Two edits conflict when their ranges intersect or insert at the same point.
+fn overlaps(a: &Span, b: &Span) -> bool {
+    (a.start < b.end && b.start < a.end) || a.start == b.start
+}
+
+/// A line-level change between two texts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LineOp {
+    Equal,
+    Delete,
+    Insert,
+}
+
+/// Renders the change from `old` to `new` as a unified diff of `path`.
+///
+/// Returns an empty string when the texts are identical.
+pub fn unified_diff(path: &str, old: &str, new: &str) -> String {
+    let old_lines: Vec<&str> = old.split_inclusive('\n').collect();
+    let new_lines: Vec<&str> = new.split_inclusive('\n').collect();
+    let ops = diff_lines(&old_lines, &new_lines);
+    if ops.iter().all(|(op, _, _)| *op == LineOp::Equal) {
+        return String::new();
+    }
+
+    let mut out = format!("--- a/{path}\n+++ b/{path}\n");
+    let mut index = 0;
+    while index < ops.len() {
+        if ops[index].0 == LineOp::Equal {
+            index += 1;
+            continue;
+        }
+
+        // Grow the hunk while the next change is close enough to share context.
+        let start = index.saturating_sub(DIFF_CONTEXT);
+        let mut last_change = index;
+        let mut probe = index;
+        while probe < ops.len() {
+            if ops[probe].0 != LineOp::Equal {
+                last_change = probe;
+            } else if probe - last_change > 2 * DIFF_CONTEXT {
+                break;
+            }
+            probe += 1;
+        }
+        let stop = (last_change + 1 + DIFF_CONTEXT).min(ops.len());
+        let hunk = &ops[start..stop];
+
+        let old_count = hunk
+            .iter()
+            .filter(|(op, _, _)| *op != LineOp::Insert)
+            .count();
+        let new_count = hunk
+            .iter()
+            .filter(|(op, _, _)| *op != LineOp::Delete)
+            .count();
+        let (_, old_start, new_start) = hunk[0];
+        let header_start = |start: usize, count: usize| if count == 0 { start } else { start + 1 };
+        out.push_str(&format!(
+            "@@ -{},{old_count} +{},{new_count} @@\n",
+            header_start(old_start, old_count),
+            header_start(new_start, new_count),
+        ));
+
+        for &(op, old_index, new_index) in hunk {
+            let (marker, line) = match op {
+                LineOp::Equal => (' ', old_lines[old_index]),
+                LineOp::Delete => ('-', old_lines[old_index]),
+                LineOp::Insert => ('+', new_lines[new_index]),
+            };
+            out.push(marker);
+            out.push_str(line);
+            if !line.ends_with('\n') {
+                out.push_str("\n\\ No newline at end of file\n");
+            }
+        }
+        index = stop;
+    }
+    out
+}
+
+/// Computes a shortest line edit script with Myers' algorithm.
+///
+/// Each entry records the operation and the old/new line indices at which it
+/// applies.
+fn diff_lines(old: &[&str], new: &[&str]) -> Vec<(LineOp, usize, usize)> {
+    // Unchanged leading and trailing lines need no search.
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let a = &old[prefix..old.len() - suffix];
+    let b = &new[prefix..new.len() - suffix];
+
+    let n = a.len() as isize;
+    let m = b.len() as isize;
+    let max = n + m;
+    let offset = max as usize + 1;
+    let mut v = vec![0isize; 2 * offset + 1];
+    let mut trace = Vec::new();
+
+    'search: for d in 0..=max {
+        trace.push(v.clone());
+        let mut k = -d;
+        while k <= d {
+            let idx = (k + offset as isize) as usize;
+            let mut x = if k == -d || (k != d && v[idx - 1] < v[idx + 1]) {
+                v[idx + 1]
+            } else {
+                v[idx - 1] + 1
+            };
+            let mut y = x - k;
+            while x < n && y < m && a[x as usize] == b[y as usize] {
+                x += 1;
+                y += 1;
+            }
+            v[idx] = x;
+            if x >= n && y >= m {
+                break 'search;
+            }
+            k += 2;
+        }
+    }
+
+    let mut middle = Vec::new();
+    let (mut x, mut y) = (n, m);
+    for d in (0..trace.len() as isize).rev() {
+        let v = &trace[d as usize];
+        let k = x - y;
+        let idx = (k + offset as isize) as usize;
+        let prev_k = if k == -d || (k != d && v[idx - 1] < v[idx + 1]) {
+            k + 1
+        } else {
+            k - 1
+        };
+        let prev_x = v[(prev_k + offset as isize) as usize];
+        let prev_y = prev_x - prev_k;
+        while x > prev_x && y > prev_y {
+            x -= 1;
+            y -= 1;
+            middle.push((LineOp::Equal, x as usize, y as usize));
+        }
+        if d > 0 {
+            if x == prev_x {
+                middle.push((LineOp::Insert, x as usize, prev_y as usize));
+            } else {
+                middle.push((LineOp::Delete, prev_x as usize, y as usize));
+            }
+        }
+        x = prev_x;
+        y = prev_y;
+    }
+    middle.reverse();
+
+    let mut ops: Vec<_> = (0..prefix).map(|i| (LineOp::Equal, i, i)).collect();
+    ops.extend(
+        middle
+            .into_iter()
+            .map(|(op, i, j)| (op, i + prefix, j + prefix)),
+    );
+    let (old_end, new_end) = (old.len() - suffix, new.len() - suffix);
+    ops.extend((0..suffix).map(|i| (LineOp::Equal, old_end + i, new_end + i)));
+    ops
+}
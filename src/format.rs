@@ -0,0 +1,548 @@
+//! Opinionated source formatter behind `blazelint fmt`.
+//!
+//! The formatter reprints the [concrete syntax tree](crate::cst) token by
+//! token, so comments survive and nothing is reordered. Everything else is
+//! normalized:
+//!
+//! * one statement per line, indented four spaces per block;
+//! * `{` ends its line, `}` starts one, and `} else {` stays together; record
+//!   types, classes, object types, and services are laid out the same way, one
+//!   member per line;
+//! * single spaces around binary and assignment operators, after commas and
+//!   keywords, and none inside brackets or around `.`, `:` in qualified names,
+//!   type parameters (`map<int>`), unions, casts (`<float>x`), and the `/` of
+//!   resource paths (`/api`, `users/[string id]`);
+//! * at most one blank line between statements, and none at the start or end
+//!   of a block;
+//! * a line longer than the configured width is broken at the first bracketed
+//!   list on it, one element per line, repeating for elements still too long.
+//!
+//! Comments keep their placement: a comment that ended a line of code still
+//! does, and one on its own line stays there.
+
+use crate::ast::NodeKind;
+use crate::codes;
+use crate::config::{Config, ConfigValue};
+use crate::cst::{SyntaxElement, SyntaxKind, SyntaxNode, SyntaxToken, SyntaxTokenKind};
+use crate::errors::{Diagnostic, DiagnosticKind};
+use crate::lexer::{CommentKind, Token, TriviaKind};
+
+/// Line width used when the configuration does not set one.
+pub const DEFAULT_MAX_WIDTH: usize = crate::linter::rules::line_length::DEFAULT_MAX_LINE_LENGTH;
+
+/// Spaces per indentation level.
+const INDENT_WIDTH: usize = 4;
+
+/// Settings for [`format_source`].
+#[derive(Debug, Clone)]
+pub struct FormatOptions {
+    /// Lines longer than this are broken where possible.
+    pub max_width: usize,
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        Self {
+            max_width: DEFAULT_MAX_WIDTH,
+        }
+    }
+}
+
+impl FormatOptions {
+    /// Uses the `line_length` rule's `max_length` as the width, so the
+    /// formatter and the linter agree on what is too long.
+    pub fn from_config(config: &Config) -> Self {
+        let max_width = config
+            .rules
+            .get("line_length")
+            .and_then(|settings| settings.options.get("max_length"))
+            .and_then(|option| match option.value {
+                ConfigValue::Integer(width) if width > 0 => Some(width as usize),
+                _ => None,
+            })
+            .unwrap_or(DEFAULT_MAX_WIDTH);
+        Self { max_width }
+    }
+}
+
+/// Formats `source`, or returns the lexer and parser errors that prevent it.
+pub fn format_source(source: &str, options: &FormatOptions) -> Result<String, Vec<Diagnostic>> {
+    let (tree, diagnostics) = crate::syntax_tree(source)?;
+    if !diagnostics.is_empty() {
+        return Err(diagnostics);
+    }
+
+    let mut items = Vec::new();
+    let mut newlines = 0;
+    collect_items(&tree, &mut items, &mut newlines);
+
+    let mut printer = Printer {
+        options,
+        lines: Vec::new(),
+        line: Line::new(0),
+        depth: 0,
+        statement_start: true,
+    };
+    for (index, item) in items.iter().enumerate() {
+        let previous = index.checked_sub(1).map(|index| &items[index]);
+        printer.push(previous, item);
+    }
+    Ok(printer.finish())
+}
+
+/// Builds the `--check` diagnostic for a file whose formatted text differs,
+/// spanning the first line that would change.
+pub fn unformatted_diagnostic(source: &str, formatted: &str) -> Diagnostic {
+    let mut start = 0;
+    let mut end = source.len();
+    for (original, expected) in source
+        .split_inclusive('\n')
+        .zip(formatted.split_inclusive('\n'))
+    {
+        if original != expected {
+            end = start + original.trim_end_matches(['\n', '\r']).len();
+            break;
+        }
+        start += original.len();
+    }
+    if start >= source.len() {
+        // Only lines were added or removed at the end.
+        start = source.len();
+        end = source.len();
+    }
+    Diagnostic::new(DiagnosticKind::Format, "File is not formatted", start..end)
+        .with_code(codes::UNFORMATTED_FILE)
+        .with_note("run `blazelint fmt` to format it")
+}
+
+/// A token or comment to print, with what the tree says about it.
+struct Item<'t> {
+    token: &'t SyntaxToken,
+    /// The node the token belongs to.
+    parent: SyntaxKind,
+    /// Line breaks between the previous item and this one in the source.
+    newlines_before: usize,
+    /// The accessor of a resource function, `get` in
+    /// `resource function get users()`.
+    is_accessor: bool,
+}
+
+impl Item<'_> {
+    fn token(&self) -> Option<&Token> {
+        match &self.token.kind {
+            SyntaxTokenKind::Token(token) => Some(token),
+            SyntaxTokenKind::Trivia(_) => None,
+        }
+    }
+
+    fn comment(&self) -> Option<CommentKind> {
+        match self.token.kind {
+            SyntaxTokenKind::Trivia(TriviaKind::Comment(kind)) => Some(kind),
+            _ => None,
+        }
+    }
+
+    fn is(&self, token: Token) -> bool {
+        self.token() == Some(&token)
+    }
+
+    fn parent_is(&self, kind: NodeKind) -> bool {
+        self.parent == SyntaxKind::Node(kind)
+    }
+
+    /// A comment that runs to the end of its line.
+    fn ends_line(&self) -> bool {
+        matches!(self.comment(), Some(CommentKind::Line | CommentKind::Doc))
+    }
+
+    /// A `{` opening a block or a record type's fields, or a `{|`.
+    fn is_block_open(&self) -> bool {
+        self.is(Token::LBrace) && !self.parent_is(NodeKind::MapLiteral)
+            || self.is(Token::LBracePipe)
+    }
+
+    fn is_block_close(&self) -> bool {
+        self.is(Token::RBrace) && !self.parent_is(NodeKind::MapLiteral)
+            || self.is(Token::PipeRBrace)
+    }
+}
+
+/// Flattens the tree into tokens and comments, dropping whitespace but
+/// counting the line breaks it held.
+fn collect_items<'t>(node: &'t SyntaxNode, items: &mut Vec<Item<'t>>, newlines: &mut usize) {
+    for child in &node.children {
+        match child {
+            SyntaxElement::Node(child) => collect_items(child, items, newlines),
+            SyntaxElement::Token(token) => match token.kind {
+                SyntaxTokenKind::Trivia(TriviaKind::Newline) => *newlines += 1,
+                SyntaxTokenKind::Trivia(TriviaKind::Whitespace) => {}
+                _ => {
+                    let is_accessor = node.kind == SyntaxKind::Node(NodeKind::ResourceFunction)
+                        && matches!(token.kind, SyntaxTokenKind::Token(Token::Identifier(_)))
+                        && items.last().is_some_and(|previous: &Item| {
+                            previous.is(Token::Function) && previous.parent == node.kind
+                        });
+                    items.push(Item {
+                        token,
+                        parent: node.kind,
+                        newlines_before: *newlines,
+                        is_accessor,
+                    });
+                    *newlines = 0;
+                }
+            },
+        }
+    }
+}
+
+/// How two consecutive items are separated.
+enum Separator {
+    Tight,
+    Space,
+    Newline { blank: bool },
+}
+
+fn separator(previous: &Item, current: &Item) -> Separator {
+    let newline = Separator::Newline {
+        blank: current.newlines_before > 1
+            && !previous.is_block_open()
+            && !current.is_block_close(),
+    };
+    if previous.ends_line() {
+        return newline;
+    }
+    if current.comment().is_some() || previous.comment().is_some() {
+        return if current.newlines_before > 0 {
+            newline
+        } else {
+            Separator::Space
+        };
+    }
+    if previous.is_block_open() {
+        return if current.is_block_close() {
+            Separator::Tight
+        } else {
+            newline
+        };
+    }
+    if current.is_block_close() || previous.is(Token::Semicolon) {
+        return newline;
+    }
+    if previous.is_block_close() {
+        return if current.is(Token::Else) {
+            Separator::Space
+        } else if current.is(Token::Semicolon) {
+            // The end of a type definition: `type T record { ... };`
+            Separator::Tight
+        } else {
+            newline
+        };
+    }
+    if spaced(previous, current) {
+        Separator::Space
+    } else {
+        Separator::Tight
+    }
+}
+
+/// Whether two tokens on the same line are separated by a space.
+fn spaced(previous: &Item, current: &Item) -> bool {
+    let (Some(before), Some(after)) = (previous.token(), current.token()) else {
+        return true;
+    };
+    // Operators that are not binary here: type parameters, unions, casts,
+    // import paths, and unary operators.
+    let infix = |item: &Item| item.parent_is(NodeKind::Binary);
+
+    // A resource function's path after its accessor, `get .()` or
+    // `get [string id]()`, and a rest parameter, `[string... ids]`.
+    if previous.is_accessor
+        || previous.parent_is(NodeKind::ResourceFunction) && matches!(before, Token::DotDotDot)
+    {
+        return true;
+    }
+    if matches!(
+        after,
+        Token::Comma
+            | Token::Semicolon
+            | Token::RParen
+            | Token::RBracket
+            | Token::Dot
+            | Token::DotDotDot
+    ) || matches!(
+        before,
+        Token::LParen | Token::LBracket | Token::Dot | Token::DotDotDot
+    ) {
+        return false;
+    }
+    if previous.parent_is(NodeKind::Unary)
+        && matches!(
+            before,
+            Token::Minus | Token::Plus | Token::Bang | Token::Tilde
+        )
+    {
+        return false;
+    }
+    if previous.parent_is(NodeKind::MapLiteral) && matches!(before, Token::LBrace)
+        || current.parent_is(NodeKind::MapLiteral) && matches!(after, Token::RBrace)
+    {
+        return false;
+    }
+
+    match after {
+        Token::LParen => !matches!(
+            before,
+            Token::Identifier(_) | Token::RParen | Token::RBracket
+        ),
+        Token::LBracket => {
+            !(matches!(
+                before,
+                Token::Identifier(_) | Token::RParen | Token::RBracket
+            ) || is_type_keyword(before)
+                || matches!(before, Token::Gt | Token::Question) && !infix(previous)
+                // A path parameter: `users/[string id]`.
+                || matches!(before, Token::Slash) && !infix(previous))
+        }
+        Token::Colon => current.parent_is(NodeKind::Ternary),
+        Token::Question => current.parent_is(NodeKind::Ternary),
+        Token::Lt if current.parent_is(NodeKind::Cast) => true,
+        // A service's attach point: `service /api on ep`.
+        Token::Slash if current.parent_is(NodeKind::Service) => matches!(before, Token::Service),
+        Token::Lt | Token::Gt | Token::Pipe | Token::Slash => infix(current),
+        _ => match before {
+            Token::Colon => {
+                previous.parent_is(NodeKind::MapLiteral) || previous.parent_is(NodeKind::Ternary)
+            }
+            Token::Gt if previous.parent_is(NodeKind::Cast) => false,
+            Token::Slash if previous.parent_is(NodeKind::Service) => matches!(after, Token::On),
+            // Also the `*` of an object type inclusion, `*Shape;`.
+            Token::Lt | Token::Pipe | Token::Slash | Token::Star => infix(previous),
+            _ => true,
+        },
+    }
+}
+
+fn is_type_keyword(token: &Token) -> bool {
+    matches!(
+        token,
+        Token::Int
+            | Token::String
+            | Token::Boolean
+            | Token::Float
+            | Token::Decimal
+            | Token::Byte
+            | Token::Anydata
+            | Token::Map
+    )
+}
+
+/// The role a piece plays when a line is broken.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Role {
+    Open,
+    Close,
+    Comma,
+    Other,
+}
+
+/// One token or comment as placed on an output line.
+#[derive(Debug, Clone)]
+struct Piece {
+    text: String,
+    space_before: bool,
+    role: Role,
+}
+
+/// An output line before width limits are applied.
+#[derive(Debug, Clone)]
+struct Line {
+    indent: usize,
+    pieces: Vec<Piece>,
+}
+
+impl Line {
+    fn new(indent: usize) -> Self {
+        Self {
+            indent,
+            pieces: Vec::new(),
+        }
+    }
+
+    fn render(&self) -> String {
+        let mut text = " ".repeat(self.indent * INDENT_WIDTH);
+        for (index, piece) in self.pieces.iter().enumerate() {
+            if index > 0 && piece.space_before {
+                text.push(' ');
+            }
+            text.push_str(&piece.text);
+        }
+        text
+    }
+}
+
+struct Printer<'o> {
+    options: &'o FormatOptions,
+    /// Finished lines; `None` is a blank line.
+    lines: Vec<Option<Line>>,
+    line: Line,
+    /// Current block nesting.
+    depth: usize,
+    /// Whether the next token starts a statement rather than continuing one.
+    statement_start: bool,
+}
+
+impl Printer<'_> {
+    fn push(&mut self, previous: Option<&Item>, item: &Item) {
+        if item.is_block_close() {
+            self.depth = self.depth.saturating_sub(1);
+        }
+
+        let space_before = match previous.map(|previous| separator(previous, item)) {
+            None | Some(Separator::Tight) => false,
+            Some(Separator::Space) => true,
+            Some(Separator::Newline { blank }) => {
+                let indent = if self.statement_start {
+                    self.depth
+                } else {
+                    self.depth + 1
+                };
+                let line = std::mem::replace(&mut self.line, Line::new(indent));
+                self.lines.push(Some(line));
+                if blank {
+                    self.lines.push(None);
+                }
+                false
+            }
+        };
+        if previous.is_none() {
+            self.line.indent = self.depth;
+        }
+
+        let role = match item.token() {
+            Some(Token::LParen | Token::LBracket) => Role::Open,
+            Some(Token::RParen | Token::RBracket) => Role::Close,
+            Some(Token::LBrace) if !item.is_block_open() => Role::Open,
+            Some(Token::RBrace) if !item.is_block_close() => Role::Close,
+            Some(Token::Comma) => Role::Comma,
+            _ => Role::Other,
+        };
+        self.line.pieces.push(Piece {
+            text: item.token.text.clone(),
+            space_before,
+            role,
+        });
+
+        if item.comment().is_none() {
+            self.statement_start =
+                item.is(Token::Semicolon) || item.is_block_open() || item.is_block_close();
+        }
+        if item.is_block_open() {
+            self.depth += 1;
+        }
+    }
+
+    fn finish(mut self) -> String {
+        if !self.line.pieces.is_empty() {
+            self.lines.push(Some(self.line));
+        }
+        let mut out = String::new();
+        for line in &self.lines {
+            match line {
+                Some(line) => {
+                    for text in break_line(line, self.options.max_width) {
+                        out.push_str(&text);
+                        out.push('\n');
+                    }
+                }
+                None => out.push('\n'),
+            }
+        }
+        out
+    }
+}
+
+/// Renders `line`, splitting bracketed lists one element per line while the
+/// line is wider than `max_width`.
+fn break_line(line: &Line, max_width: usize) -> Vec<String> {
+    let rendered = line.render();
+    if rendered.chars().count() <= max_width || rendered.contains('\n') {
+        return vec![rendered];
+    }
+    let Some((open, close)) = breakable_group(&line.pieces) else {
+        return vec![rendered];
+    };
+
+    let mut out = vec![Line {
+        indent: line.indent,
+        pieces: line.pieces[..=open].to_vec(),
+    }
+    .render()];
+
+    let mut element = Line::new(line.indent + 1);
+    let mut depth = 0;
+    for piece in &line.pieces[open + 1..close] {
+        let mut piece = piece.clone();
+        if element.pieces.is_empty() {
+            piece.space_before = false;
+        }
+        match piece.role {
+            Role::Open => depth += 1,
+            Role::Close => depth -= 1,
+            Role::Comma if depth == 0 => {
+                element.pieces.push(piece);
+                out.extend(break_line(&element, max_width));
+                element = Line::new(line.indent + 1);
+                continue;
+            }
+            _ => {}
+        }
+        element.pieces.push(piece);
+    }
+    if !element.pieces.is_empty() {
+        out.extend(break_line(&element, max_width));
+    }
+
+    let mut tail = line.pieces[close..].to_vec();
+    tail[0].space_before = false;
+    out.extend(break_line(
+        &Line {
+            indent: line.indent,
+            pieces: tail,
+        },
+        max_width,
+    ));
+    out
+}
+
+/// Finds the first bracket pair on the line, outside any other, that holds a
+/// comma-separated list.
+fn breakable_group(pieces: &[Piece]) -> Option<(usize, usize)> {
+    let mut index = 0;
+    while index < pieces.len() {
+        if pieces[index].role != Role::Open {
+            index += 1;
+            continue;
+        }
+        let mut depth = 0;
+        let mut has_comma = false;
+        let mut close = None;
+        for (offset, piece) in pieces[index + 1..].iter().enumerate() {
+            match piece.role {
+                Role::Open => depth += 1,
+                Role::Close if depth == 0 => {
+                    close = Some(index + 1 + offset);
+                    break;
+                }
+                Role::Close => depth -= 1,
+                Role::Comma if depth == 0 => has_comma = true,
+                _ => {}
+            }
+        }
+        match close {
+            Some(close) if has_comma => return Some((index, close)),
+            Some(close) => index = close + 1,
+            None => return None,
+        }
+    }
+    None
+}
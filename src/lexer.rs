@@ -3,7 +3,97 @@
 //! The lexer converts raw source text into a stream of token triples annotated
 //! with byte offsets. Subsequent stages use these spans to highlight precise
 //! error locations and to reconstruct lexemes as needed.
-use crate::errors::LexError;
+//!
+//! Everything between tokens is kept as [`Trivia`]: runs of spaces and tabs,
+//! line breaks, and comments. Tokens and trivia together cover every byte of
+//! a source that lexes cleanly.
+use crate::codes;
+use crate::errors::{LexError, Span};
+
+/// A token annotated with its `[start, end)` byte offsets in the source.
+pub type SpannedToken = (usize, Token, usize);
+
+/// Whether a comment was written with `//`, `/* ... */`, or as a `#`
+/// documentation line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommentKind {
+    Line,
+    Block,
+    Doc,
+}
+
+/// A comment skipped by the lexer, kept so later stages can read directives.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Comment {
+    pub kind: CommentKind,
+    /// Covers the delimiters; a line comment's span stops before the newline.
+    pub span: Span,
+}
+
+impl Comment {
+    /// Returns the comment's text without its `//`, `/*`, `*/`, or `#` delimiters.
+    pub fn text<'a>(&self, source: &'a str) -> &'a str {
+        let raw = &source[self.span.clone()];
+        match self.kind {
+            CommentKind::Line => &raw[2..],
+            CommentKind::Block => &raw[2..raw.len() - 2],
+            CommentKind::Doc => &raw[1..],
+        }
+    }
+}
+
+/// What a piece of trivia consists of.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TriviaKind {
+    /// A run of spaces and tabs.
+    Whitespace,
+    /// One line break: `\n`, `\r\n`, or a lone `\r`.
+    Newline,
+    Comment(CommentKind),
+}
+
+/// Source text between tokens that carries no meaning for the parser.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Trivia {
+    pub kind: TriviaKind,
+    pub span: Span,
+}
+
+/// A token or a piece of trivia, as produced by [`merge_trivia`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Element<'a> {
+    Token(&'a SpannedToken),
+    Trivia(&'a Trivia),
+}
+
+impl Element<'_> {
+    /// Byte range the element covers.
+    pub fn span(&self) -> Span {
+        match self {
+            Element::Token((start, _, end)) => *start..*end,
+            Element::Trivia(trivia) => trivia.span.clone(),
+        }
+    }
+}
+
+/// Interleaves `tokens` and `trivia`, both in source order, into one stream
+/// ordered by position.
+pub fn merge_trivia<'a>(tokens: &'a [SpannedToken], trivia: &'a [Trivia]) -> Vec<Element<'a>> {
+    let mut elements = Vec::with_capacity(tokens.len() + trivia.len());
+    let mut tokens = tokens.iter().peekable();
+    let mut trivia = trivia.iter().peekable();
+    loop {
+        let element = match (tokens.peek(), trivia.peek()) {
+            (Some(token), Some(piece)) if token.0 < piece.span.start => {
+                Element::Token(tokens.next().expect("peeked"))
+            }
+            (_, Some(_)) => Element::Trivia(trivia.next().expect("peeked")),
+            (Some(_), None) => Element::Token(tokens.next().expect("peeked")),
+            (None, None) => return elements,
+        };
+        elements.push(element);
+    }
+}
 
 /// Tokens recognised by the Ballerina subset Blazelint currently supports.
 #[derive(Debug, PartialEq, Clone)]
@@ -22,6 +112,7 @@ pub enum Token {
     Return,
     Panic,
     Check,
+    CheckPanic,
     Returns,
     Int,
     String,
@@ -37,6 +128,21 @@ pub enum Token {
     Break,
     Continue,
     Is,
+    Type,
+    Record,
+    Class,
+    Object,
+    New,
+    Isolated,
+    Readonly,
+    Private,
+    Distinct,
+    Client,
+    Remote,
+    Listener,
+    Service,
+    Resource,
+    On,
 
     // Operators
     Plus,
@@ -74,6 +180,10 @@ pub enum Token {
     RParen,
     LBrace,
     RBrace,
+    /// `{|`, opening a closed record type descriptor.
+    LBracePipe,
+    /// `|}`, closing a closed record type descriptor.
+    PipeRBrace,
     LBracket,
     RBracket,
     Colon,
@@ -98,6 +208,10 @@ pub struct Lexer<'input> {
     start: usize,
     /// Cursor pointing at the next character to process.
     current: usize,
+    /// Comments skipped so far, in source order.
+    comments: Vec<Comment>,
+    /// Trivia skipped so far, comments included, in source order.
+    trivia: Vec<Trivia>,
 }
 
 impl<'input> Lexer<'input> {
@@ -108,6 +222,34 @@ impl<'input> Lexer<'input> {
             chars: input.chars().peekable(),
             start: 0,
             current: 0,
+            comments: Vec::new(),
+            trivia: Vec::new(),
+        }
+    }
+
+    /// Takes the comments skipped so far, leaving none behind.
+    pub fn take_comments(&mut self) -> Vec<Comment> {
+        std::mem::take(&mut self.comments)
+    }
+
+    /// Takes the trivia skipped so far, leaving none behind.
+    pub fn take_trivia(&mut self) -> Vec<Trivia> {
+        std::mem::take(&mut self.trivia)
+    }
+
+    /// Records a comment both as a comment and as trivia.
+    fn push_comment(&mut self, kind: CommentKind, span: Span) {
+        self.trivia.push(Trivia {
+            kind: TriviaKind::Comment(kind),
+            span: span.clone(),
+        });
+        self.comments.push(Comment { kind, span });
+    }
+
+    /// Consumes the rest of the line, stopping before any line break.
+    fn skip_to_line_end(&mut self) {
+        while !matches!(self.peek(), Some('\n' | '\r') | None) {
+            self.advance();
         }
     }
 
@@ -123,22 +265,38 @@ impl<'input> Lexer<'input> {
                 None => return Ok(()),
             };
 
+            let trivia_start = self.current;
             match c {
-                ' ' | '\r' | '\t' | '\n' => {
+                ' ' | '\t' => {
+                    while matches!(self.peek(), Some(' ' | '\t')) {
+                        self.advance();
+                    }
+                    self.trivia.push(Trivia {
+                        kind: TriviaKind::Whitespace,
+                        span: trivia_start..self.current,
+                    });
+                }
+                '\n' | '\r' => {
                     self.advance();
+                    if c == '\r' && self.peek() == Some(&'\n') {
+                        self.advance();
+                    }
+                    self.trivia.push(Trivia {
+                        kind: TriviaKind::Newline,
+                        span: trivia_start..self.current,
+                    });
+                }
+                '#' => {
+                    // Documentation line # ...
+                    self.skip_to_line_end();
+                    self.push_comment(CommentKind::Doc, trivia_start..self.current);
                 }
                 '/' => {
                     let comment_start = self.current;
                     if self.peek_next() == Some('/') {
                         // Single-line comment //
-                        self.advance(); // Consume '/'
-                        self.advance(); // Consume second '/'
-                        while self.peek() != Some(&'\n') && !self.is_at_end() {
-                            self.advance();
-                        }
-                        if self.peek() == Some(&'\n') {
-                            self.advance();
-                        }
+                        self.skip_to_line_end();
+                        self.push_comment(CommentKind::Line, comment_start..self.current);
                     } else if self.peek_next() == Some('*') {
                         // Multi-line comment /* ... */
                         self.advance(); // consume '/'
@@ -155,10 +313,12 @@ impl<'input> Lexer<'input> {
                         }
                         if !found_end_comment {
                             return Err(LexError::new(
+                                codes::UNTERMINATED_COMMENT,
                                 "Unterminated block comment",
                                 comment_start..self.current,
                             ));
                         }
+                        self.push_comment(CommentKind::Block, comment_start..self.current);
                     } else {
                         return Ok(());
                     }
@@ -175,6 +335,7 @@ impl<'input> Lexer<'input> {
                 self.advance();
                 if self.is_at_end() {
                     return Err(LexError::new(
+                        codes::UNTERMINATED_STRING,
                         "Unterminated escape sequence",
                         self.start..self.current,
                     ));
@@ -185,6 +346,7 @@ impl<'input> Lexer<'input> {
 
         if self.is_at_end() {
             return Err(LexError::new(
+                codes::UNTERMINATED_STRING,
                 "Unterminated string literal",
                 self.start..self.current,
             ));
@@ -237,6 +399,7 @@ impl<'input> Lexer<'input> {
 
         if self.is_at_end() {
             return Err(LexError::new(
+                codes::UNTERMINATED_STRING,
                 "Unterminated string template",
                 self.start..self.current,
             ));
@@ -272,6 +435,7 @@ impl<'input> Lexer<'input> {
                 }
             } else {
                 return Err(LexError::new(
+                    codes::INVALID_NUMBER,
                     "Malformed exponent in number literal",
                     self.start..self.current,
                 ));
@@ -281,6 +445,7 @@ impl<'input> Lexer<'input> {
         let value_str = &self.input[self.start..self.current];
         value_str.parse::<f64>().map(Token::Number).map_err(|e| {
             LexError::new(
+                codes::INVALID_NUMBER,
                 format!("Invalid number literal '{value_str}': {e}"),
                 self.start..self.current,
             )
@@ -311,6 +476,7 @@ impl<'input> Lexer<'input> {
             "return" => Token::Return,
             "panic" => Token::Panic,
             "check" => Token::Check,
+            "checkpanic" => Token::CheckPanic,
             "returns" => Token::Returns,
             "int" => Token::Int,
             "string" => Token::String,
@@ -326,6 +492,21 @@ impl<'input> Lexer<'input> {
             "break" => Token::Break,
             "continue" => Token::Continue,
             "is" => Token::Is,
+            "type" => Token::Type,
+            "record" => Token::Record,
+            "class" => Token::Class,
+            "object" => Token::Object,
+            "new" => Token::New,
+            "isolated" => Token::Isolated,
+            "readonly" => Token::Readonly,
+            "private" => Token::Private,
+            "distinct" => Token::Distinct,
+            "client" => Token::Client,
+            "remote" => Token::Remote,
+            "listener" => Token::Listener,
+            "service" => Token::Service,
+            "resource" => Token::Resource,
+            "on" => Token::On,
             _ => Token::Identifier(text.to_string()),
         }
     }
@@ -333,7 +514,7 @@ impl<'input> Lexer<'input> {
     //-------------- Helpers ---------------------------
 
     /// Creates a token triple `[start, token, end)` covering the current lexeme.
-    fn create_token(&self, token_type: Token) -> (usize, Token, usize) {
+    fn create_token(&self, token_type: Token) -> SpannedToken {
         (self.start, token_type, self.current)
     }
 
@@ -380,7 +561,7 @@ impl<'input> Lexer<'input> {
 
 /// Implements `Iterator` so the lexer can be used directly in `for` loops.
 impl Iterator for Lexer<'_> {
-    type Item = Result<(usize, Token, usize), LexError>;
+    type Item = Result<SpannedToken, LexError>;
 
     fn next(&mut self) -> Option<Self::Item> {
         // Skip whitespace and comments before finding the next token
@@ -397,7 +578,13 @@ impl Iterator for Lexer<'_> {
         let result = match c {
             '(' => Ok(self.create_token(Token::LParen)),
             ')' => Ok(self.create_token(Token::RParen)),
-            '{' => Ok(self.create_token(Token::LBrace)),
+            '{' => {
+                if self.match_char('|') {
+                    Ok(self.create_token(Token::LBracePipe))
+                } else {
+                    Ok(self.create_token(Token::LBrace))
+                }
+            }
             '}' => Ok(self.create_token(Token::RBrace)),
             '[' => Ok(self.create_token(Token::LBracket)),
             ']' => Ok(self.create_token(Token::RBracket)),
@@ -493,6 +680,8 @@ impl Iterator for Lexer<'_> {
             '|' => {
                 if self.match_char('|') {
                     Ok(self.create_token(Token::PipePipe))
+                } else if self.match_char('}') {
+                    Ok(self.create_token(Token::PipeRBrace))
                 } else {
                     Ok(self.create_token(Token::Pipe))
                 }
@@ -507,6 +696,7 @@ impl Iterator for Lexer<'_> {
                 Ok(self.create_token(id_token))
             }
             _ => Err(LexError::new(
+                codes::UNEXPECTED_CHARACTER,
                 format!("Unexpected character: '{c}'"),
                 self.start..self.current,
             )),
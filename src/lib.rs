@@ -0,0 +1,230 @@
+//! Blazelint: a linter for the Ballerina programming language.
+//!
+//! The crate exposes the complete front-end pipeline (lexer, parser, semantic
+//! analyzer, and lint rules) so that tools can lint Ballerina sources in-process
+//! instead of shelling out to the `blazelint` binary. The main entry point is
+//! [`lint_source`], which runs every stage over a source string and returns the
+//! token stream, AST, and collected diagnostics. [`syntax_tree`] produces the
+//! lossless [`cst`] instead, for tools that rewrite source.
+//!
+//! ```
+//! use blazelint::{lint_source, LintOptions};
+//!
+//! let report = lint_source("int a_b = 1;", "example.bal", &LintOptions::default());
+//! assert_eq!(report.diagnostics.len(), 1);
+//! assert!(report.diagnostics[0].message.contains("camelCase"));
+//! ```
+
+pub mod ast;
+pub mod baseline;
+pub mod codes;
+pub mod config;
+pub mod cst;
+pub mod diff;
+pub mod discovery;
+pub mod errors;
+pub mod fix;
+pub mod format;
+pub mod json;
+pub mod lexer;
+pub mod linter;
+pub mod lsp;
+pub mod parser;
+pub mod report;
+pub mod semantic;
+pub mod suppression;
+
+use ast::Stmt;
+use config::Config;
+use errors::{Diagnostic, Severity};
+use lexer::{Comment, Lexer, SpannedToken, Trivia};
+use linter::TokenContext;
+use parser::Parser;
+use semantic::SemanticModel;
+
+/// Knobs controlling which stages of the pipeline [`lint_source`] runs.
+///
+/// Construct with [`LintOptions::default`] and adjust individual fields; new
+/// options may be added in future releases.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct LintOptions {
+    /// Run semantic analysis (scopes, types, returns) after parsing.
+    pub semantic: bool,
+    /// Run the registered lint rules over the parsed AST.
+    pub lint: bool,
+    /// Rule configuration, usually loaded from a `.blazerc` file.
+    pub config: Config,
+}
+
+impl Default for LintOptions {
+    fn default() -> Self {
+        Self {
+            semantic: true,
+            lint: true,
+            config: Config::default(),
+        }
+    }
+}
+
+/// Everything produced while linting a single source file.
+#[derive(Debug)]
+pub struct LintReport {
+    /// Name of the linted file, as passed to [`lint_source`].
+    pub file_name: String,
+    /// Tokens produced by the lexer. Empty when lexing failed.
+    pub tokens: Vec<SpannedToken>,
+    /// Comments skipped by the lexer, in source order.
+    pub comments: Vec<Comment>,
+    /// Whitespace, line breaks, and comments between tokens, in source order.
+    pub trivia: Vec<Trivia>,
+    /// Top-level statements recovered by the parser.
+    pub ast: Vec<Stmt>,
+    /// What semantic analysis learned about the AST. Built whenever the parser
+    /// recovered any statements, even if `options.semantic` is off.
+    pub semantic: SemanticModel,
+    /// Diagnostics from every stage, in pipeline order.
+    pub diagnostics: Vec<Diagnostic>,
+    /// Rule diagnostics silenced by suppression comments.
+    pub suppressed: Vec<Diagnostic>,
+}
+
+impl LintReport {
+    /// Returns true when no stage reported a diagnostic.
+    pub fn is_clean(&self) -> bool {
+        self.diagnostics.is_empty()
+    }
+
+    /// Counts the diagnostics reported at exactly `severity`.
+    pub fn count(&self, severity: Severity) -> usize {
+        self.diagnostics
+            .iter()
+            .filter(|diagnostic| diagnostic.severity == severity)
+            .count()
+    }
+
+    /// Returns true when any diagnostic is error-level.
+    pub fn has_errors(&self) -> bool {
+        self.count(Severity::Error) > 0
+    }
+}
+
+/// Lints `source` and returns the tokens, AST, and diagnostics it produced.
+///
+/// Problems in `options.config` are not reported here; validate a loaded
+/// configuration with [`linter::RuleSet::from_config`] first.
+///
+/// Lexical errors stop the pipeline, since the parser cannot work on a partial
+/// token stream. Parse errors do not: the parser recovers at statement
+/// boundaries and the remaining stages still run over whatever it recovered.
+pub fn lint_source(source: &str, file_name: &str, options: &LintOptions) -> LintReport {
+    let mut report = LintReport {
+        file_name: file_name.to_string(),
+        tokens: Vec::new(),
+        comments: Vec::new(),
+        trivia: Vec::new(),
+        ast: Vec::new(),
+        semantic: SemanticModel::default(),
+        diagnostics: Vec::new(),
+        suppressed: Vec::new(),
+    };
+
+    let lexed = lex(source);
+    if !lexed.diagnostics.is_empty() {
+        report.diagnostics = lexed.diagnostics;
+        return report;
+    }
+    report.tokens = lexed.tokens;
+    report.comments = lexed.comments;
+    report.trivia = lexed.trivia;
+
+    let (ast, parse_diagnostics) = parse(&report.tokens);
+    report.ast = ast;
+    report.diagnostics.extend(parse_diagnostics);
+
+    if !report.ast.is_empty() {
+        let (model, semantic_diagnostics) = semantic::analyze_program(&report.ast);
+        report.semantic = model;
+        if options.semantic {
+            report.diagnostics.extend(semantic_diagnostics);
+        }
+    }
+
+    // Run linter rules even if there are errors (to catch style issues)
+    if options.lint {
+        let (rules, _) = linter::RuleSet::from_config(&options.config);
+        report
+            .diagnostics
+            .extend(rules.run(&report.ast, source, &report.semantic));
+        report.diagnostics.extend(rules.run_tokens(&TokenContext {
+            source,
+            tokens: &report.tokens,
+            trivia: &report.trivia,
+        }));
+        report.suppressed = suppression::apply(
+            source,
+            &report.comments,
+            &mut report.diagnostics,
+            &rules.names(),
+        );
+    }
+
+    report
+}
+
+/// Tokenises `source`, returning every lexical error when any occur.
+pub fn tokenize(source: &str) -> Result<Vec<SpannedToken>, Vec<Diagnostic>> {
+    let lexed = lex(source);
+    if lexed.diagnostics.is_empty() {
+        Ok(lexed.tokens)
+    } else {
+        Err(lexed.diagnostics)
+    }
+}
+
+/// Parses `source` into a lossless concrete syntax tree, returned with the
+/// parse diagnostics. Lexical errors are returned instead, since the tree could
+/// not cover the source.
+pub fn syntax_tree(source: &str) -> Result<(cst::SyntaxNode, Vec<Diagnostic>), Vec<Diagnostic>> {
+    let lexed = lex(source);
+    if !lexed.diagnostics.is_empty() {
+        return Err(lexed.diagnostics);
+    }
+    let (ast, diagnostics) = parse(&lexed.tokens);
+    let tree = cst::build(source, &lexed.tokens, &lexed.trivia, &ast);
+    Ok((tree, diagnostics))
+}
+
+/// Everything the lexer produced for one source.
+struct Lexed {
+    tokens: Vec<SpannedToken>,
+    comments: Vec<Comment>,
+    trivia: Vec<Trivia>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+/// Runs the lexer to completion, keeping the comments and trivia it skipped.
+fn lex(source: &str) -> Lexed {
+    let mut lexer = Lexer::new(source);
+    let mut tokens = Vec::new();
+    let mut diagnostics = Vec::new();
+
+    for result in lexer.by_ref() {
+        match result {
+            Ok(token) => tokens.push(token),
+            Err(err) => diagnostics.push(err.into()),
+        }
+    }
+
+    Lexed {
+        tokens,
+        comments: lexer.take_comments(),
+        trivia: lexer.take_trivia(),
+        diagnostics,
+    }
+}
+
+/// Parses a token stream into top-level statements plus any parse diagnostics.
+pub fn parse(tokens: &[SpannedToken]) -> (Vec<Stmt>, Vec<Diagnostic>) {
+    Parser::new(tokens.to_vec()).parse()
+}
@@ -1,16 +1,358 @@
 pub mod rules;
 
-use crate::{ast::Stmt, errors::Diagnostic};
+use crate::{
+    ast::{walk, Expr, Node, NodeKind, Stmt, VisitContext, Visitor},
+    config::{config_error, Config, ConfigValue},
+    errors::{Diagnostic, Severity},
+    lexer::{merge_trivia, Element, SpannedToken, Trivia},
+    semantic::{self, SemanticModel},
+};
+use rules::{camel_case::CamelCase, constant_case::ConstantCase, line_length::LineLength};
 
 /// A blueprint for creating new linting rules.
-#[allow(dead_code)]
+///
+/// Node rules subscribe to the node kinds they inspect with
+/// [`Rule::node_kinds`]; the linter walks the whole AST once, nested
+/// statements and expressions included, and calls [`Rule::check_node`] for
+/// every matching node. Token rules override [`Rule::check_tokens`] instead,
+/// which sees the file's tokens together with the whitespace and comments
+/// between them. A rule may do both.
 pub trait Rule {
     /// Returns the name of the rule.
     fn name(&self) -> &'static str;
 
+    /// Former names of the rule, still accepted in the configuration and in
+    /// suppression comments with a deprecation warning.
+    fn deprecated_names(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    /// Returns the stable diagnostic code of the rule, such as `BL4001`.
+    ///
+    /// Each rule needs its own entry in [`crate::codes`].
+    fn code(&self) -> &'static str;
+
     /// Returns a description of the rule.
     fn description(&self) -> &'static str;
 
-    /// Validates a given statement.
-    fn validate(&self, statement: &Stmt, source: &str) -> Vec<Diagnostic>;
+    /// The kind of problem the rule looks for.
+    fn category(&self) -> RuleCategory;
+
+    /// Whether the rule's diagnostics can come with a suggested fix.
+    fn fixable(&self) -> bool {
+        false
+    }
+
+    /// Options accepted by [`Rule::configure`]; none by default.
+    fn options(&self) -> &'static [RuleOption] {
+        &[]
+    }
+
+    /// Severity of the rule's diagnostics unless the configuration overrides it.
+    ///
+    /// Style rules default to warnings so they do not fail a build on their own.
+    fn default_severity(&self) -> Severity {
+        Severity::Warning
+    }
+
+    /// Applies a rule-specific option from the configuration file.
+    ///
+    /// Rules without options keep the default, which rejects every key.
+    fn configure(&mut self, _option: &str, _value: &ConfigValue) -> Result<(), OptionError> {
+        Err(OptionError::Unknown)
+    }
+
+    /// The kinds of node passed to [`Rule::check_node`]; none by default.
+    fn node_kinds(&self) -> &'static [NodeKind] {
+        &[]
+    }
+
+    /// Checks one node of a subscribed kind.
+    fn check_node(&self, _node: Node<'_>, _cx: &RuleContext<'_>) -> Vec<Diagnostic> {
+        Vec::new()
+    }
+
+    /// Checks the token stream of a file that lexed cleanly. Called once per
+    /// file, even when parsing failed.
+    fn check_tokens(&self, _cx: &TokenContext<'_>) -> Vec<Diagnostic> {
+        Vec::new()
+    }
+}
+
+/// The kind of problem a rule looks for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RuleCategory {
+    /// How declarations are named.
+    Naming,
+    /// Layout and presentation of the source text.
+    Style,
+}
+
+impl RuleCategory {
+    /// Lowercase name used in output.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RuleCategory::Naming => "naming",
+            RuleCategory::Style => "style",
+        }
+    }
+}
+
+/// A rule option that can be set in the configuration file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RuleOption {
+    pub name: &'static str,
+    pub description: &'static str,
+    /// The value used when the option is not set, as written in `.blazerc`.
+    pub default: &'static str,
+}
+
+/// What a rule can see besides the node it is checking.
+pub struct RuleContext<'a> {
+    /// Source text of the file being linted.
+    pub source: &'a str,
+    /// The node's position in the tree: its ancestors and enclosing scopes.
+    pub tree: &'a VisitContext<'a>,
+    /// Symbols, scopes, resolved references, and expression types for the
+    /// whole file.
+    pub semantic: &'a SemanticModel,
+}
+
+/// What a token rule sees: the whole file, lexed.
+pub struct TokenContext<'a> {
+    /// Source text of the file being linted.
+    pub source: &'a str,
+    /// Every token, in source order.
+    pub tokens: &'a [SpannedToken],
+    /// Whitespace, line breaks, and comments between tokens, in source order.
+    pub trivia: &'a [Trivia],
+}
+
+impl<'a> TokenContext<'a> {
+    /// Tokens and trivia interleaved in source order. Together they cover
+    /// every byte of the source.
+    pub fn elements(&self) -> Vec<Element<'a>> {
+        merge_trivia(self.tokens, self.trivia)
+    }
+
+    /// The source split into lines, each paired with the byte offset where
+    /// it starts. Line terminators are not included.
+    pub fn lines(&self) -> impl Iterator<Item = (usize, &'a str)> {
+        let source = self.source;
+        source.split_inclusive('\n').scan(0, move |offset, line| {
+            let start = *offset;
+            *offset += line.len();
+            Some((start, line.trim_end_matches(['\n', '\r'])))
+        })
+    }
+}
+
+/// Reason a rule rejected an option from the configuration file.
+#[derive(Debug, Clone, PartialEq)]
+pub enum OptionError {
+    /// The rule has no option with that name.
+    Unknown,
+    /// The option exists but the value is unusable; the message explains why.
+    Invalid(String),
+}
+
+/// Returns every built-in rule, in the order they are applied.
+///
+/// This is the rule registry: configuration files, suppression comments,
+/// `--list-rules`, and SARIF output all name rules as listed here.
+pub fn default_rules() -> Vec<Box<dyn Rule>> {
+    vec![
+        Box::new(CamelCase),
+        Box::new(ConstantCase),
+        Box::new(LineLength::default()),
+    ]
+}
+
+/// A rule that survived configuration, with its effective severity.
+struct ActiveRule {
+    rule: Box<dyn Rule>,
+    severity: Severity,
+}
+
+/// The set of enabled, configured rules applied to each file.
+pub struct RuleSet {
+    rules: Vec<ActiveRule>,
+}
+
+impl Default for RuleSet {
+    fn default() -> Self {
+        Self::from_config(&Config::default()).0
+    }
+}
+
+impl RuleSet {
+    /// Builds the rule set described by `config`.
+    ///
+    /// Unknown rule names and rejected options are returned as `Config`
+    /// diagnostics spanning the offending text in the configuration file; the
+    /// rule set is still built from every setting that could be applied.
+    pub fn from_config(config: &Config) -> (RuleSet, Vec<Diagnostic>) {
+        let mut diagnostics = Vec::new();
+        let mut rules = Vec::new();
+
+        for mut rule in default_rules() {
+            let settings = config.rules.get(rule.name()).or_else(|| {
+                rule.deprecated_names()
+                    .iter()
+                    .find_map(|name| config.rules.get(*name))
+            });
+            let Some(settings) = settings else {
+                let severity = rule.default_severity();
+                rules.push(ActiveRule { rule, severity });
+                continue;
+            };
+
+            for (option, entry) in &settings.options {
+                match rule.configure(option, &entry.value) {
+                    Ok(()) => {}
+                    Err(OptionError::Unknown) => diagnostics.push(config_error(
+                        format!("Unknown option '{option}' for rule '{}'", rule.name()),
+                        entry.key_span.clone(),
+                    )),
+                    Err(OptionError::Invalid(message)) => {
+                        diagnostics.push(config_error(message, entry.value_span.clone()))
+                    }
+                }
+            }
+
+            if settings.enabled {
+                let severity = settings.severity.unwrap_or_else(|| rule.default_severity());
+                rules.push(ActiveRule { rule, severity });
+            }
+        }
+
+        let known = rule_names();
+        for (name, settings) in &config.rules {
+            if let Some(current) = renamed_rule(name) {
+                diagnostics.push(
+                    config_error(
+                        format!("Rule '{name}' has been renamed to '{current}'"),
+                        settings.name_span.clone(),
+                    )
+                    .with_severity(Severity::Warning)
+                    .with_help(format!("use '{current}' instead")),
+                );
+            } else if !known.contains(&name.as_str()) {
+                diagnostics.push(
+                    config_error(format!("Unknown rule '{name}'"), settings.name_span.clone())
+                        .with_note(format!("available rules: {}", known.join(", "))),
+                );
+            }
+        }
+
+        (RuleSet { rules }, diagnostics)
+    }
+
+    /// Returns the names of the enabled rules, in application order.
+    pub fn names(&self) -> Vec<&'static str> {
+        self.rules.iter().map(|active| active.rule.name()).collect()
+    }
+
+    /// Runs the linting rules over the provided AST.
+    ///
+    /// This function walks every statement and expression in the AST, nested
+    /// ones included, and hands each node to the enabled rules subscribed to
+    /// its kind, collecting every rule violation with the configured severity.
+    ///
+    /// # Args
+    ///
+    /// * `ast` - A slice of `Stmt` representing the AST to be linted.
+    /// * `source` - The source code string the AST was parsed from.
+    /// * `semantic` - The semantic model built from the same AST.
+    pub fn run(&self, ast: &[Stmt], source: &str, semantic: &SemanticModel) -> Vec<Diagnostic> {
+        let mut visitor = RuleVisitor {
+            rules: self,
+            source,
+            semantic,
+            diagnostics: Vec::new(),
+        };
+        walk(ast, &mut visitor);
+        visitor.diagnostics
+    }
+
+    /// Runs every rule's token check over one file.
+    pub fn run_tokens(&self, cx: &TokenContext<'_>) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+        for active in &self.rules {
+            let found = active.rule.check_tokens(cx);
+            diagnostics.extend(found.into_iter().map(|diagnostic| {
+                diagnostic
+                    .with_severity(active.severity)
+                    .with_code(active.rule.code())
+                    .with_rule(active.rule.name())
+            }));
+        }
+        diagnostics
+    }
+
+    /// Applies the rules subscribed to `node`'s kind.
+    fn check(&self, node: Node<'_>, cx: &RuleContext<'_>, diagnostics: &mut Vec<Diagnostic>) {
+        let kind = node.kind();
+        for active in &self.rules {
+            if !active.rule.node_kinds().contains(&kind) {
+                continue;
+            }
+            let found = active.rule.check_node(node, cx);
+            diagnostics.extend(found.into_iter().map(|diagnostic| {
+                diagnostic
+                    .with_severity(active.severity)
+                    .with_code(active.rule.code())
+                    .with_rule(active.rule.name())
+            }));
+        }
+    }
+}
+
+/// Feeds every node of a walk to a [`RuleSet`].
+struct RuleVisitor<'r> {
+    rules: &'r RuleSet,
+    source: &'r str,
+    semantic: &'r SemanticModel,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl<'a> Visitor<'a> for RuleVisitor<'_> {
+    fn enter_stmt(&mut self, stmt: &'a Stmt, tree: &VisitContext<'a>) {
+        let cx = RuleContext {
+            source: self.source,
+            tree,
+            semantic: self.semantic,
+        };
+        self.rules
+            .check(Node::Stmt(stmt), &cx, &mut self.diagnostics);
+    }
+
+    fn enter_expr(&mut self, expr: &'a Expr, tree: &VisitContext<'a>) {
+        let cx = RuleContext {
+            source: self.source,
+            tree,
+            semantic: self.semantic,
+        };
+        self.rules
+            .check(Node::Expr(expr), &cx, &mut self.diagnostics);
+    }
+}
+
+/// Names of every built-in rule.
+pub(crate) fn rule_names() -> Vec<&'static str> {
+    default_rules().iter().map(|rule| rule.name()).collect()
+}
+
+/// The current name of the built-in rule formerly called `name`.
+pub(crate) fn renamed_rule(name: &str) -> Option<&'static str> {
+    default_rules()
+        .iter()
+        .find(|rule| rule.deprecated_names().contains(&name))
+        .map(|rule| rule.name())
+}
+
+/// Runs the built-in rules, with default settings, over the provided AST.
+pub fn run_linter(ast: &[Stmt], source: &str) -> Vec<Diagnostic> {
+    let (model, _) = semantic::analyze_program(ast);
+    RuleSet::default().run(ast, source, &model)
 }
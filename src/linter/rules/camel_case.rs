@@ -1,15 +1,18 @@
 //! Rule to enforce camelCase for variable names.
 
 use crate::{
-    ast::Stmt,
+    ast::{Node, NodeKind, Stmt},
+    codes,
     errors::{Diagnostic, DiagnosticKind},
-    linter::Rule,
+    fix::rename_identifier,
+    linter::{Rule, RuleCategory, RuleContext},
 };
 
 /// A rule that enforces variable names to be in camelCase.
 ///
-/// This rule checks for variable declarations and reports a diagnostic
-/// if the variable name is not in camelCase.
+/// This rule checks variable declarations at any depth, including those in
+/// function bodies, branches, and loops, and reports a diagnostic if the
+/// variable name is not in camelCase.
 pub struct CamelCase;
 
 impl Rule for CamelCase {
@@ -18,36 +21,61 @@ impl Rule for CamelCase {
         "camel_case"
     }
 
+    fn code(&self) -> &'static str {
+        codes::CAMEL_CASE
+    }
+
     /// Returns a description of the rule.
     fn description(&self) -> &'static str {
         "Variables should be in camelCase."
     }
 
-    /// Validates the given statement against the rule.
+    fn category(&self) -> RuleCategory {
+        RuleCategory::Naming
+    }
+
+    /// Offers a rename to the converted name.
+    fn fixable(&self) -> bool {
+        true
+    }
+
+    /// Subscribes to variable declarations.
+    fn node_kinds(&self) -> &'static [NodeKind] {
+        &[NodeKind::VarDecl]
+    }
+
+    /// Validates the given variable declaration against the rule.
     ///
-    /// This function checks if the statement is a variable declaration and
-    /// if the variable name is in camelCase.
+    /// This function checks if the variable name is in camelCase. When the
+    /// name can be converted, the diagnostic offers a rename as its fix.
     ///
     /// # Arguments
     ///
-    /// * `statement` - The statement to validate
+    /// * `node` - The declaration to validate
+    /// * `cx` - The source and tree context of the declaration
     ///
     /// # Returns
     ///
-    /// A vector of diagnostics found in the statement
-    fn validate(&self, statement: &Stmt, _source: &str) -> Vec<Diagnostic> {
+    /// A vector of diagnostics found in the declaration
+    fn check_node(&self, node: Node<'_>, cx: &RuleContext<'_>) -> Vec<Diagnostic> {
         let mut diagnostics = Vec::new();
 
-        if let Stmt::VarDecl {
+        if let Node::Stmt(Stmt::VarDecl {
             name, name_span, ..
-        } = statement
+        }) = node
         {
             if !is_camel_case(name) {
-                diagnostics.push(Diagnostic::new(
+                let mut diagnostic = Diagnostic::new(
                     DiagnosticKind::Linter,
                     format!("Variable \"{}\" is not in camelCase.", name),
                     name_span.clone(),
-                ));
+                );
+                if let Some(fix) = to_camel_case(name).and_then(|new_name| {
+                    rename_identifier(cx.source, cx.semantic, name_span, &new_name)
+                }) {
+                    diagnostic = diagnostic.with_fix(fix);
+                }
+                diagnostics.push(diagnostic);
             }
         }
 
@@ -77,3 +105,26 @@ fn is_camel_case(s: &str) -> bool {
     }
     s.chars().all(|c| c.is_ascii_alphanumeric()) && !s.contains('_')
 }
+
+/// Converts a name such as `snake_case`, `PascalCase`, or `SCREAMING_CASE` to
+/// camelCase, or returns `None` when the result would still be invalid.
+fn to_camel_case(name: &str) -> Option<String> {
+    let mut result = String::new();
+    for word in name.split('_').filter(|word| !word.is_empty()) {
+        let all_upper = !word.chars().any(|c| c.is_ascii_lowercase());
+        let mut chars = word.chars();
+        let first = chars.next()?;
+        let rest: String = if all_upper {
+            chars.as_str().to_ascii_lowercase()
+        } else {
+            chars.as_str().to_string()
+        };
+        if result.is_empty() {
+            result.push(first.to_ascii_lowercase());
+        } else {
+            result.push(first.to_ascii_uppercase());
+        }
+        result.push_str(&rest);
+    }
+    is_camel_case(&result).then_some(result)
+}
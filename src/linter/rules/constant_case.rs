@@ -1,6 +1,8 @@
-use crate::ast::Stmt;
+use crate::ast::{Node, NodeKind, Stmt};
+use crate::codes;
 use crate::errors::{Diagnostic, DiagnosticKind};
-use crate::linter::Rule;
+use crate::fix::rename_identifier;
+use crate::linter::{Rule, RuleCategory, RuleContext};
 
 /// A linting rule to enforce that constant variable names are in SCREAMING_SNAKE_CASE.
 pub struct ConstantCase;
@@ -8,7 +10,17 @@ pub struct ConstantCase;
 impl Rule for ConstantCase {
     /// Returns the name of the rule.
     fn name(&self) -> &'static str {
-        "constant-case"
+        "constant_case"
+    }
+
+    /// The rule was called `constant-case` before rule names settled on
+    /// snake case.
+    fn deprecated_names(&self) -> &'static [&'static str] {
+        &["constant-case"]
+    }
+
+    fn code(&self) -> &'static str {
+        codes::CONSTANT_CASE
     }
 
     /// Returns a description of the rule.
@@ -16,28 +28,50 @@ impl Rule for ConstantCase {
         "Constant variable names should be in SCREAMING_SNAKE_CASE."
     }
 
-    /// Validates a given statement to ensure that constant variable names are in SCREAMING_SNAKE_CASE.
+    fn category(&self) -> RuleCategory {
+        RuleCategory::Naming
+    }
+
+    /// Offers a rename to the converted name.
+    fn fixable(&self) -> bool {
+        true
+    }
+
+    /// Subscribes to constant declarations.
+    fn node_kinds(&self) -> &'static [NodeKind] {
+        &[NodeKind::ConstDecl]
+    }
+
+    /// Validates a constant declaration to ensure that its name is in SCREAMING_SNAKE_CASE.
     ///
     /// # Arguments
     ///
-    /// * `statement` - The statement to validate.
+    /// * `node` - The declaration to validate.
+    /// * `cx` - The source and tree context of the declaration.
     ///
     /// # Returns
     ///
-    /// A vector of diagnostics if the constant variable name is not in SCREAMING_SNAKE_CASE.
-    fn validate(&self, statement: &Stmt, _source: &str) -> Vec<Diagnostic> {
+    /// A vector of diagnostics if the constant variable name is not in SCREAMING_SNAKE_CASE,
+    /// each offering a rename when the name can be converted.
+    fn check_node(&self, node: Node<'_>, cx: &RuleContext<'_>) -> Vec<Diagnostic> {
         let mut diagnostics = Vec::new();
 
-        if let Stmt::ConstDecl {
+        if let Node::Stmt(Stmt::ConstDecl {
             name, name_span, ..
-        } = statement
+        }) = node
         {
             if !is_screaming_snake_case(name) {
-                diagnostics.push(Diagnostic::new(
+                let mut diagnostic = Diagnostic::new(
                     DiagnosticKind::Linter,
                     "Constant variable names should be in SCREAMING_SNAKE_CASE.".to_string(),
                     name_span.clone(),
-                ));
+                );
+                if let Some(fix) = to_screaming_snake_case(name).and_then(|new_name| {
+                    rename_identifier(cx.source, cx.semantic, name_span, &new_name)
+                }) {
+                    diagnostic = diagnostic.with_fix(fix);
+                }
+                diagnostics.push(diagnostic);
             }
         }
 
@@ -57,3 +91,18 @@ impl Rule for ConstantCase {
 fn is_screaming_snake_case(name: &str) -> bool {
     name.chars().all(|c| c.is_ascii_uppercase() || c == '_')
 }
+
+/// Converts a name such as `maxSize` or `max_size` to SCREAMING_SNAKE_CASE, or
+/// returns `None` when the result would still be invalid.
+fn to_screaming_snake_case(name: &str) -> Option<String> {
+    let mut result = String::new();
+    let mut previous_lower = false;
+    for c in name.chars() {
+        if c.is_ascii_uppercase() && previous_lower {
+            result.push('_');
+        }
+        previous_lower = c.is_ascii_lowercase();
+        result.push(c.to_ascii_uppercase());
+    }
+    (is_screaming_snake_case(&result) && result != name).then_some(result)
+}
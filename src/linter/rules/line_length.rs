@@ -1,14 +1,34 @@
 use crate::{
-    ast::Stmt,
+    codes,
+    config::ConfigValue,
     errors::{Diagnostic, DiagnosticKind},
-    linter::Rule,
+    linter::{OptionError, Rule, RuleCategory, RuleOption, TokenContext},
 };
 
-const MAX_LINE_LENGTH: usize = 120;
+/// Line length allowed when the configuration does not set `max_length`.
+pub(crate) const DEFAULT_MAX_LINE_LENGTH: usize = 120;
 
 /// A linting rule to enforce that lines do not exceed a maximum length.
+///
+/// The limit defaults to [`DEFAULT_MAX_LINE_LENGTH`] characters and can be
+/// changed with the `max_length` option.
 #[derive(Debug, Clone)]
-pub struct LineLength;
+pub struct LineLength {
+    max_length: usize,
+}
+
+impl LineLength {
+    /// Creates the rule with a custom maximum line length.
+    pub fn new(max_length: usize) -> Self {
+        Self { max_length }
+    }
+}
+
+impl Default for LineLength {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_LINE_LENGTH)
+    }
+}
 
 impl Rule for LineLength {
     /// Returns the name of the rule.
@@ -16,39 +36,62 @@ impl Rule for LineLength {
         "line_length"
     }
 
+    fn code(&self) -> &'static str {
+        codes::LINE_LENGTH
+    }
+
     /// Returns a description of the rule.
     fn description(&self) -> &'static str {
-        "Lines should not exceed 120 characters."
+        // tests/cli_output.rs checks this default and the one in `options`
+        // against DEFAULT_MAX_LINE_LENGTH.
+        "Lines should not exceed the maximum line length (120 characters by default)."
+    }
+
+    fn category(&self) -> RuleCategory {
+        RuleCategory::Style
     }
 
-    /// Validates a given statement to ensure that it does not exceed the maximum line length.
+    fn options(&self) -> &'static [RuleOption] {
+        &[RuleOption {
+            name: "max_length",
+            description: "Longest allowed line, in characters",
+            default: "120",
+        }]
+    }
+
+    /// Accepts `max_length`, a positive integer.
+    fn configure(&mut self, option: &str, value: &ConfigValue) -> Result<(), OptionError> {
+        match (option, value) {
+            ("max_length", ConfigValue::Integer(n)) if *n > 0 => {
+                self.max_length = *n as usize;
+                Ok(())
+            }
+            ("max_length", other) => Err(OptionError::Invalid(format!(
+                "Invalid value {other} for 'max_length': expected a positive integer"
+            ))),
+            _ => Err(OptionError::Unknown),
+        }
+    }
+
+    /// Checks every line of the file, comments and blank lines included.
     ///
     /// # Arguments
     ///
-    /// * `statement` - The statement to validate.
-    /// * `source` - The source code of the file being linted.
+    /// * `cx` - The source code of the file being linted, with its tokens.
     ///
     /// # Returns
     ///
-    /// A vector of diagnostics if the statement exceeds the maximum line length.
-    fn validate(&self, statement: &Stmt, source: &str) -> Vec<Diagnostic> {
-        let mut diagnostics = Vec::new();
-        let span = statement.span();
-
-        let statement_source = &source[span.start..span.end];
-
-        for line in statement_source.lines() {
-            if line.len() > MAX_LINE_LENGTH {
-                diagnostics.push(Diagnostic::new(
+    /// A diagnostic spanning each line longer than the maximum line length.
+    fn check_tokens(&self, cx: &TokenContext<'_>) -> Vec<Diagnostic> {
+        cx.lines()
+            .filter(|(_, line)| line.chars().count() > self.max_length)
+            .map(|(start, line)| {
+                Diagnostic::new(
                     DiagnosticKind::Linter,
-                    self.description().to_string(),
-                    span.clone(),
-                ));
-                // We only want to report the error once per statement
-                break;
-            }
-        }
-
-        diagnostics
+                    format!("Lines should not exceed {} characters.", self.max_length),
+                    start..start + line.len(),
+                )
+            })
+            .collect()
     }
 }
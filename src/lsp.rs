@@ -0,0 +1,573 @@
+//! Language server mode, started with `blazelint lsp`.
+//!
+//! The server speaks the Language Server Protocol over a byte stream framed
+//! with `Content-Length` headers, normally stdin and stdout. Open documents
+//! are kept in memory, updated incrementally from `textDocument/didChange`,
+//! and relinted after every change; diagnostics are published with positions
+//! in UTF-16 code units, the protocol's default encoding.
+//!
+//! [`Server`] handles one decoded message at a time and returns the messages
+//! to send back, so editors can be scripted in-process; [`serve`] adds the
+//! framing.
+
+use crate::config::Config;
+use crate::errors::{Diagnostic, Severity, Span};
+use crate::json::JsonValue;
+use crate::linter::RuleSet;
+use crate::report::artifact_uri;
+use crate::{lint_source, LintOptions};
+use std::collections::HashMap;
+use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
+
+/// JSON-RPC error codes used in responses.
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const SERVER_NOT_INITIALIZED: i64 = -32002;
+
+/// `TextDocumentSyncKind.Incremental`.
+const SYNC_INCREMENTAL: usize = 2;
+
+/// `MessageType` values for `window/logMessage`.
+const LOG_WARNING: usize = 2;
+
+/// An open document.
+struct Document {
+    text: String,
+    version: Option<JsonValue>,
+    /// Name passed to the linter: the file path for `file:` URIs.
+    file_name: String,
+    options: LintOptions,
+}
+
+/// Protocol state for one client connection.
+#[derive(Default)]
+pub struct Server {
+    documents: HashMap<String, Document>,
+    initialized: bool,
+    shutdown_requested: bool,
+    exit_code: Option<i32>,
+}
+
+impl Server {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The process exit status, once the client has sent `exit`: 0 after a
+    /// `shutdown` request and 1 otherwise.
+    pub fn exit_code(&self) -> Option<i32> {
+        self.exit_code
+    }
+
+    /// Handles one message from the client and returns the messages to send
+    /// back: the response to a request, and any notifications it triggered.
+    pub fn handle(&mut self, message: &JsonValue) -> Vec<JsonValue> {
+        let method = message.get("method").and_then(JsonValue::as_str);
+        let id = message.get("id");
+        let params = message.get("params").unwrap_or(&JsonValue::Null);
+
+        match (method, id) {
+            (Some(method), Some(id)) => self.request(method, id.clone()),
+            (Some(method), None) => self.notification(method, params),
+            // A response to a request; the server never sends any.
+            (None, Some(_))
+                if message.get("result").is_some() || message.get("error").is_some() =>
+            {
+                Vec::new()
+            }
+            _ => vec![error_response(
+                id.cloned().unwrap_or(JsonValue::Null),
+                INVALID_REQUEST,
+                "expected a request or notification object",
+            )],
+        }
+    }
+
+    fn request(&mut self, method: &str, id: JsonValue) -> Vec<JsonValue> {
+        if self.shutdown_requested {
+            return vec![error_response(
+                id,
+                INVALID_REQUEST,
+                "the server is shutting down",
+            )];
+        }
+        if !self.initialized && method != "initialize" {
+            return vec![error_response(
+                id,
+                SERVER_NOT_INITIALIZED,
+                "expected 'initialize' first",
+            )];
+        }
+
+        let result = match method {
+            "initialize" if self.initialized => {
+                return vec![error_response(
+                    id,
+                    INVALID_REQUEST,
+                    "the server is already initialized",
+                )]
+            }
+            "initialize" => {
+                self.initialized = true;
+                initialize_result()
+            }
+            "shutdown" => {
+                self.shutdown_requested = true;
+                JsonValue::Null
+            }
+            other => {
+                return vec![error_response(
+                    id,
+                    METHOD_NOT_FOUND,
+                    &format!("unsupported method '{other}'"),
+                )]
+            }
+        };
+        vec![JsonValue::object([
+            ("jsonrpc", "2.0".into()),
+            ("id", id),
+            ("result", result),
+        ])]
+    }
+
+    fn notification(&mut self, method: &str, params: &JsonValue) -> Vec<JsonValue> {
+        if method == "exit" {
+            self.exit_code = Some(if self.shutdown_requested { 0 } else { 1 });
+            return Vec::new();
+        }
+        if !self.initialized {
+            return Vec::new();
+        }
+
+        let outcome = match method {
+            "textDocument/didOpen" => self.did_open(params),
+            "textDocument/didChange" => self.did_change(params),
+            "textDocument/didClose" => self.did_close(params),
+            _ => Ok(Vec::new()),
+        };
+        outcome.unwrap_or_else(|message| {
+            vec![log_message(
+                LOG_WARNING,
+                &format!("Ignoring {method}: {message}"),
+            )]
+        })
+    }
+
+    fn did_open(&mut self, params: &JsonValue) -> Result<Vec<JsonValue>, String> {
+        let item = field(params, "textDocument")?;
+        let uri = string_field(item, "uri")?;
+        let text = string_field(item, "text")?;
+
+        let path = uri_to_path(uri);
+        let (options, mut messages) = load_options(path.as_deref());
+        let document = Document {
+            text: text.to_string(),
+            version: item.get("version").cloned(),
+            file_name: path.map_or_else(|| uri.to_string(), |path| path.display().to_string()),
+            options,
+        };
+        messages.push(publish(uri, &document));
+        self.documents.insert(uri.to_string(), document);
+        Ok(messages)
+    }
+
+    fn did_change(&mut self, params: &JsonValue) -> Result<Vec<JsonValue>, String> {
+        let identifier = field(params, "textDocument")?;
+        let uri = string_field(identifier, "uri")?;
+        let changes = field(params, "contentChanges")?
+            .as_array()
+            .ok_or("'contentChanges' is not an array")?;
+        let document = self
+            .documents
+            .get_mut(uri)
+            .ok_or_else(|| format!("'{uri}' is not open"))?;
+
+        // Changes apply in order, each to the result of the previous one.
+        for change in changes {
+            let text = string_field(change, "text")?;
+            match change.get("range") {
+                Some(range) => {
+                    let index = LineIndex::new(&document.text);
+                    let start = index.offset(field(range, "start")?)?;
+                    let end = index.offset(field(range, "end")?)?;
+                    if end < start {
+                        return Err("change range ends before it starts".to_string());
+                    }
+                    document.text.replace_range(start..end, text);
+                }
+                None => document.text = text.to_string(),
+            }
+        }
+        document.version = identifier.get("version").cloned();
+        Ok(vec![publish(uri, document)])
+    }
+
+    fn did_close(&mut self, params: &JsonValue) -> Result<Vec<JsonValue>, String> {
+        let uri = string_field(field(params, "textDocument")?, "uri")?;
+        if self.documents.remove(uri).is_none() {
+            return Err(format!("'{uri}' is not open"));
+        }
+        // Clear the closed document's diagnostics from the editor.
+        Ok(vec![publish_diagnostics(uri, None, Vec::new())])
+    }
+}
+
+/// Runs a server reading framed messages from `input` and writing replies to
+/// `output` until the client sends `exit` or closes the stream.
+///
+/// Returns the process exit status; malformed framing is an I/O error.
+pub fn serve(mut input: impl BufRead, mut output: impl Write) -> io::Result<i32> {
+    let mut server = Server::new();
+    while let Some(body) = read_message(&mut input)? {
+        let replies = match JsonValue::parse(&body) {
+            Ok(message) => server.handle(&message),
+            Err(err) => vec![error_response(JsonValue::Null, PARSE_ERROR, &err)],
+        };
+        for reply in &replies {
+            write_message(&mut output, reply)?;
+        }
+        if let Some(code) = server.exit_code() {
+            return Ok(code);
+        }
+    }
+    Ok(if server.shutdown_requested { 0 } else { 1 })
+}
+
+/// Reads one `Content-Length` framed message body, or `None` at end of input.
+pub fn read_message(input: &mut impl BufRead) -> io::Result<Option<String>> {
+    let mut length = None;
+    let mut line = String::new();
+    loop {
+        line.clear();
+        if input.read_line(&mut line)? == 0 {
+            if length.is_none() {
+                return Ok(None);
+            }
+            return Err(invalid_data("end of input inside a message header"));
+        }
+        let header = line.trim_end_matches(['\r', '\n']);
+        if header.is_empty() {
+            break;
+        }
+        let Some((name, value)) = header.split_once(':') else {
+            return Err(invalid_data(&format!("malformed header '{header}'")));
+        };
+        if name.trim().eq_ignore_ascii_case("content-length") {
+            let value = value.trim();
+            length = Some(
+                value
+                    .parse::<usize>()
+                    .map_err(|_| invalid_data(&format!("invalid Content-Length '{value}'")))?,
+            );
+        }
+    }
+
+    let length = length.ok_or_else(|| invalid_data("message without a Content-Length header"))?;
+    let mut body = vec![0; length];
+    input.read_exact(&mut body)?;
+    String::from_utf8(body)
+        .map(Some)
+        .map_err(|_| invalid_data("message body is not UTF-8"))
+}
+
+/// Writes `message` with a `Content-Length` header and flushes `output`.
+pub fn write_message(output: &mut impl Write, message: &JsonValue) -> io::Result<()> {
+    let body = message.to_string();
+    write!(output, "Content-Length: {}\r\n\r\n{body}", body.len())?;
+    output.flush()
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
+
+fn initialize_result() -> JsonValue {
+    JsonValue::object([
+        (
+            "capabilities",
+            JsonValue::object([
+                ("positionEncoding", "utf-16".into()),
+                (
+                    "textDocumentSync",
+                    JsonValue::object([
+                        ("openClose", true.into()),
+                        ("change", SYNC_INCREMENTAL.into()),
+                    ]),
+                ),
+            ]),
+        ),
+        (
+            "serverInfo",
+            JsonValue::object([
+                ("name", "blazelint".into()),
+                ("version", env!("CARGO_PKG_VERSION").into()),
+            ]),
+        ),
+    ])
+}
+
+fn error_response(id: JsonValue, code: i64, message: &str) -> JsonValue {
+    JsonValue::object([
+        ("jsonrpc", "2.0".into()),
+        ("id", id),
+        (
+            "error",
+            JsonValue::object([("code", code.into()), ("message", message.into())]),
+        ),
+    ])
+}
+
+fn notification(method: &str, params: JsonValue) -> JsonValue {
+    JsonValue::object([
+        ("jsonrpc", "2.0".into()),
+        ("method", method.into()),
+        ("params", params),
+    ])
+}
+
+fn log_message(kind: usize, message: &str) -> JsonValue {
+    notification(
+        "window/logMessage",
+        JsonValue::object([("type", kind.into()), ("message", message.into())]),
+    )
+}
+
+fn publish_diagnostics(
+    uri: &str,
+    version: Option<JsonValue>,
+    diagnostics: Vec<JsonValue>,
+) -> JsonValue {
+    let mut params = vec![("uri".to_string(), uri.into())];
+    if let Some(version) = version {
+        params.push(("version".to_string(), version));
+    }
+    params.push(("diagnostics".to_string(), JsonValue::Array(diagnostics)));
+    notification("textDocument/publishDiagnostics", JsonValue::Object(params))
+}
+
+/// Lints `document` and builds the notification publishing its diagnostics.
+fn publish(uri: &str, document: &Document) -> JsonValue {
+    let report = lint_source(&document.text, &document.file_name, &document.options);
+    let index = LineIndex::new(&document.text);
+    let diagnostics = report
+        .diagnostics
+        .iter()
+        .map(|diagnostic| diagnostic_json(uri, &index, diagnostic))
+        .collect();
+    publish_diagnostics(uri, document.version.clone(), diagnostics)
+}
+
+/// Converts a diagnostic in the document at `uri` to an LSP `Diagnostic`,
+/// folding notes, help, and the fix description into the message. Related
+/// spans become `relatedInformation`.
+fn diagnostic_json(uri: &str, index: &LineIndex<'_>, diagnostic: &Diagnostic) -> JsonValue {
+    let mut message = diagnostic.message.clone();
+    for note in &diagnostic.notes {
+        message.push_str("\nnote: ");
+        message.push_str(note);
+    }
+    for help in &diagnostic.help {
+        message.push_str("\nhelp: ");
+        message.push_str(help);
+    }
+    if let Some(fix) = &diagnostic.fix {
+        message.push_str("\nhelp: ");
+        message.push_str(&fix.message);
+    }
+    let code = diagnostic
+        .rule
+        .as_deref()
+        .or(diagnostic.code)
+        .unwrap_or(diagnostic.kind.as_str());
+
+    let range = |span: &Span| {
+        JsonValue::object([
+            ("start", index.position(span.start)),
+            ("end", index.position(span.end)),
+        ])
+    };
+    let mut fields = vec![
+        ("range", range(&diagnostic.span)),
+        ("severity", lsp_severity(diagnostic).into()),
+        ("code", code.into()),
+        ("source", "blazelint".into()),
+        ("message", message.into()),
+    ];
+    if !diagnostic.secondary.is_empty() {
+        let related = diagnostic
+            .secondary
+            .iter()
+            .map(|label| {
+                JsonValue::object([
+                    (
+                        "location",
+                        JsonValue::object([("uri", uri.into()), ("range", range(&label.span))]),
+                    ),
+                    ("message", label.message.as_str().into()),
+                ])
+            })
+            .collect();
+        fields.push(("relatedInformation", JsonValue::Array(related)));
+    }
+    JsonValue::object(fields)
+}
+
+/// `DiagnosticSeverity` values: 1 error, 2 warning, 3 information, 4 hint.
+fn lsp_severity(diagnostic: &Diagnostic) -> usize {
+    match diagnostic.severity {
+        Severity::Error => 1,
+        Severity::Warning => 2,
+        Severity::Info => 3,
+        Severity::Hint => 4,
+    }
+}
+
+/// Finds and loads the `.blazerc` governing `path`.
+///
+/// Problems in the configuration are published as diagnostics on the
+/// configuration file itself; an empty list clears earlier ones once fixed.
+fn load_options(path: Option<&Path>) -> (LintOptions, Vec<JsonValue>) {
+    let mut options = LintOptions::default();
+    let Some(config_path) = path.and_then(Path::parent).and_then(Config::discover) else {
+        return (options, Vec::new());
+    };
+
+    let config_uri = artifact_uri(&config_path.display().to_string());
+    let (config, source, mut diagnostics) = match Config::load(&config_path) {
+        Ok(loaded) => loaded,
+        Err(err) => {
+            let message = format!("Cannot read {}: {err}", config_path.display());
+            return (options, vec![log_message(LOG_WARNING, &message)]);
+        }
+    };
+    diagnostics.extend(RuleSet::from_config(&config).1);
+    options.config = config;
+
+    let index = LineIndex::new(&source);
+    let diagnostics = diagnostics
+        .iter()
+        .map(|diagnostic| diagnostic_json(&config_uri, &index, diagnostic))
+        .collect();
+    (
+        options,
+        vec![publish_diagnostics(&config_uri, None, diagnostics)],
+    )
+}
+
+fn field<'a>(value: &'a JsonValue, key: &str) -> Result<&'a JsonValue, String> {
+    value.get(key).ok_or_else(|| format!("missing '{key}'"))
+}
+
+fn string_field<'a>(value: &'a JsonValue, key: &str) -> Result<&'a str, String> {
+    field(value, key)?
+        .as_str()
+        .ok_or_else(|| format!("'{key}' is not a string"))
+}
+
+/// Maps a `file:` URI to a local path; `None` for other schemes.
+fn uri_to_path(uri: &str) -> Option<PathBuf> {
+    let rest = uri.strip_prefix("file://")?;
+    let rest = rest.strip_prefix("localhost").unwrap_or(rest);
+    if !rest.starts_with('/') {
+        return None;
+    }
+
+    let bytes = rest.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let byte = rest
+                .get(i + 1..i + 3)
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())?;
+            decoded.push(byte);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+    let path = String::from_utf8(decoded).ok()?;
+
+    // `file:///C:/src/main.bal` names a Windows drive.
+    let drive = path.as_bytes().get(2) == Some(&b':')
+        && path.as_bytes().get(1).is_some_and(u8::is_ascii_alphabetic);
+    Some(PathBuf::from(if drive { &path[1..] } else { &path[..] }))
+}
+
+/// Converts between byte offsets and LSP positions in one text.
+///
+/// Lines end at `\n`, `\r\n`, or a lone `\r`, as the protocol specifies;
+/// columns count UTF-16 code units.
+struct LineIndex<'a> {
+    text: &'a str,
+    line_starts: Vec<usize>,
+}
+
+impl<'a> LineIndex<'a> {
+    fn new(text: &'a str) -> Self {
+        let bytes = text.as_bytes();
+        let mut line_starts = vec![0];
+        for (i, &byte) in bytes.iter().enumerate() {
+            let ends_line = byte == b'\n' || (byte == b'\r' && bytes.get(i + 1) != Some(&b'\n'));
+            if ends_line {
+                line_starts.push(i + 1);
+            }
+        }
+        Self { text, line_starts }
+    }
+
+    /// The position of byte `offset`, clamped to the text.
+    fn position(&self, offset: usize) -> JsonValue {
+        let mut offset = offset.min(self.text.len());
+        while !self.text.is_char_boundary(offset) {
+            offset -= 1;
+        }
+        let line = self.line_starts.partition_point(|&start| start <= offset) - 1;
+        let start = self.line_starts[line];
+        let character = self.text[start..offset].encode_utf16().count();
+        JsonValue::object([("line", line.into()), ("character", character.into())])
+    }
+
+    /// The byte offset of an LSP `Position`.
+    ///
+    /// Positions past the end of a line clamp to the line's end and lines past
+    /// the end of the text clamp to its end. A position inside a surrogate
+    /// pair moves back to the start of the character.
+    fn offset(&self, position: &JsonValue) -> Result<usize, String> {
+        let line = field(position, "line")?
+            .as_usize()
+            .ok_or("'line' is not a non-negative integer")?;
+        let character = field(position, "character")?
+            .as_usize()
+            .ok_or("'character' is not a non-negative integer")?;
+
+        let Some(&start) = self.line_starts.get(line) else {
+            return Ok(self.text.len());
+        };
+        let end = self.line_content_end(line);
+        let mut units = 0;
+        for (index, c) in self.text[start..end].char_indices() {
+            units += c.len_utf16();
+            if units > character {
+                return Ok(start + index);
+            }
+        }
+        Ok(end)
+    }
+
+    /// The end of `line`, excluding its line break.
+    fn line_content_end(&self, line: usize) -> usize {
+        let Some(&next) = self.line_starts.get(line + 1) else {
+            return self.text.len();
+        };
+        let before = &self.text[..next];
+        if before.ends_with("\r\n") {
+            next - 2
+        } else {
+            next - 1
+        }
+    }
+}
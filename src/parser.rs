@@ -5,6 +5,7 @@
 //! grammar specification so that follow-up stages can rely on predictable AST
 //! shapes and accurate byte ranges for diagnostics.
 use crate::ast::*;
+use crate::codes;
 use crate::errors::{Diagnostic, ParseError, Span};
 use crate::lexer::Token;
 
@@ -68,7 +69,11 @@ impl Parser {
                 | Some(Token::While)
                 | Some(Token::Foreach)
                 | Some(Token::Return)
-                | Some(Token::Const) => return,
+                | Some(Token::Const)
+                | Some(Token::Type)
+                | Some(Token::Class)
+                | Some(Token::Listener)
+                | Some(Token::Service) => return,
                 _ => {
                     self.advance().ok();
                 }
@@ -76,13 +81,27 @@ impl Parser {
         }
     }
 
-    /// Parses a top-level declaration (variable, function, or statement).
+    /// Parses a top-level declaration (variable, function, type, class,
+    /// listener, service, or statement).
     fn declaration(&mut self) -> ParseResult<Stmt> {
         if self.match_token(&[Token::Import])? {
             self.import_declaration()
+        } else if self.check(&Token::Type)
+            || matches!(self.peek(), Some(Token::Public))
+                && matches!(self.peek_n(1), Some(Token::Type))
+        {
+            self.type_definition()
         } else if self.starts_var_decl() || matches!(self.peek(), Some(Token::Const)) {
             self.var_decl()
-        } else if matches!(self.peek(), Some(Token::Public | Token::Function)) {
+        } else if matches!(self.peek_past_qualifiers(), Some(Token::Class)) {
+            self.class_definition()
+        } else if matches!(self.peek_past_qualifiers(), Some(Token::Listener)) {
+            self.listener_declaration()
+        } else if matches!(self.peek_past_qualifiers(), Some(Token::Service)) {
+            self.service_declaration()
+        } else if matches!(self.peek(), Some(Token::Public | Token::Function))
+            || matches!(self.peek_past_qualifiers(), Some(Token::Function))
+        {
             self.function()
         } else {
             self.statement()
@@ -126,15 +145,437 @@ impl Parser {
         })
     }
 
+    /// Parses a type definition (`[public] type Name <type-descriptor>;`).
+    fn type_definition(&mut self) -> ParseResult<Stmt> {
+        let span_start = self.current_span().start;
+        let is_public = self.match_token(&[Token::Public])?;
+        self.consume(Token::Type, "Expected 'type'", Some("'type'"))?;
+
+        let name_token = self.advance_owned()?;
+        let name = match name_token {
+            Token::Identifier(name) => name,
+            _ => {
+                return Err(
+                    self.error_previous("Expected type name after 'type'", Some("identifier"))
+                )
+            }
+        };
+        let name_span = self.previous_span();
+
+        let type_desc = self.parse_type_descriptor()?;
+        self.consume(
+            Token::Semicolon,
+            "Expected ';' after type definition",
+            Some("';'"),
+        )?;
+
+        Ok(Stmt::TypeDefinition {
+            is_public,
+            name,
+            name_span,
+            type_desc,
+            span: span_start..self.previous_span().end,
+        })
+    }
+
+    /// Parses a class definition:
+    /// `[public] [isolated|readonly|distinct|client] class Name { ... }`.
+    ///
+    /// Members are fields, methods, and `*T;` type inclusions, in any order.
+    fn class_definition(&mut self) -> ParseResult<Stmt> {
+        let span_start = self.current_span().start;
+        let (is_public, qualifiers) = self.qualifiers(&[
+            Qualifier::Isolated,
+            Qualifier::Readonly,
+            Qualifier::Distinct,
+            Qualifier::Client,
+        ])?;
+        self.consume(Token::Class, "Expected 'class'", Some("'class'"))?;
+
+        let name_token = self.advance_owned()?;
+        let name = match name_token {
+            Token::Identifier(name) => name,
+            _ => {
+                return Err(
+                    self.error_previous("Expected class name after 'class'", Some("identifier"))
+                )
+            }
+        };
+        let name_span = self.previous_span();
+        self.consume(Token::LBrace, "Expected '{' after class name", Some("'{'"))?;
+        let members = self.class_members()?;
+        self.consume(
+            Token::RBrace,
+            "Expected '}' at end of class body",
+            Some("'}'"),
+        )?;
+
+        Ok(Stmt::Class {
+            is_public,
+            qualifiers,
+            name,
+            name_span,
+            members,
+            span: span_start..self.previous_span().end,
+        })
+    }
+
+    /// Parses the members of a class or service body up to its closing `}`:
+    /// fields, methods, resource methods, and `*T;` type inclusions, in any
+    /// order.
+    fn class_members(&mut self) -> ParseResult<Vec<ClassMember>> {
+        let mut members = Vec::new();
+        while !self.check(&Token::RBrace) && !self.is_at_end() {
+            if self.check(&Token::Star) {
+                let (type_name, span) = self.type_inclusion()?;
+                members.push(ClassMember::Inclusion { type_name, span });
+            } else if matches!(self.peek_past_qualifiers(), Some(Token::Function)) {
+                members.push(ClassMember::Method(self.function()?));
+            } else if matches!(self.peek_past_qualifiers(), Some(Token::Resource)) {
+                members.push(ClassMember::Method(self.resource_function()?));
+            } else {
+                members.push(ClassMember::Field(self.object_field()?));
+            }
+        }
+        Ok(members)
+    }
+
+    /// Parses a listener declaration:
+    /// `[public] listener [T] name = initializer;`.
+    fn listener_declaration(&mut self) -> ParseResult<Stmt> {
+        let span_start = self.current_span().start;
+        let is_public = self.match_token(&[Token::Public])?;
+        self.consume(Token::Listener, "Expected 'listener'", Some("'listener'"))?;
+
+        // Without a type, the name comes straight before `=`.
+        let type_annotation = if matches!(self.peek(), Some(Token::Identifier(_)))
+            && matches!(self.peek_n(1), Some(Token::Eq))
+        {
+            None
+        } else {
+            Some(self.parse_type_descriptor()?)
+        };
+
+        let name_token = self.advance_owned()?;
+        let name = match name_token {
+            Token::Identifier(name) => name,
+            _ => return Err(self.error_previous("Expected listener name", Some("identifier"))),
+        };
+        let name_span = self.previous_span();
+        self.consume(Token::Eq, "Expected '=' after listener name", Some("'='"))?;
+        let initializer = self.expression()?;
+        self.consume(
+            Token::Semicolon,
+            "Expected ';' after listener declaration",
+            Some("';'"),
+        )?;
+
+        Ok(Stmt::Listener {
+            is_public,
+            name,
+            name_span,
+            type_annotation,
+            initializer,
+            span: span_start..self.previous_span().end,
+        })
+    }
+
+    /// Parses a service declaration:
+    /// `[isolated] service [T] [attach-point] on listener, ... { ... }`.
+    ///
+    /// The attach point is an absolute resource path (`/api/v1`, or `/`) or
+    /// a string literal naming the service.
+    fn service_declaration(&mut self) -> ParseResult<Stmt> {
+        let span_start = self.current_span().start;
+        let (_, qualifiers) = self.qualifiers(&[Qualifier::Isolated])?;
+        self.consume(Token::Service, "Expected 'service'", Some("'service'"))?;
+
+        let type_annotation = if matches!(self.peek(), Some(Token::Identifier(_))) {
+            Some(self.parse_type_descriptor()?)
+        } else {
+            None
+        };
+
+        let attach_point = if self.match_token(&[Token::Slash])? {
+            let path_start = self.previous_span().start;
+            let mut segments = Vec::new();
+            if let Some(Token::Identifier(_)) = self.peek() {
+                loop {
+                    match self.advance_owned()? {
+                        Token::Identifier(name) => segments.push(name),
+                        _ => {
+                            return Err(self.error_previous(
+                                "Expected path segment after '/'",
+                                Some("identifier"),
+                            ))
+                        }
+                    }
+                    if !self.match_token(&[Token::Slash])? {
+                        break;
+                    }
+                }
+            }
+            Some(AttachPoint::Path {
+                segments,
+                span: path_start..self.previous_span().end,
+            })
+        } else if let Some(Token::StringLiteral(name)) = self.peek().cloned() {
+            self.advance()?;
+            Some(AttachPoint::Name {
+                name,
+                span: self.previous_span(),
+            })
+        } else {
+            None
+        };
+
+        self.consume(
+            Token::On,
+            "Expected 'on' before the service's listeners",
+            Some("'on'"),
+        )?;
+        let mut listeners = vec![self.expression()?];
+        while self.match_token(&[Token::Comma])? {
+            listeners.push(self.expression()?);
+        }
+
+        self.consume(
+            Token::LBrace,
+            "Expected '{' before service body",
+            Some("'{'"),
+        )?;
+        let members = self.class_members()?;
+        self.consume(
+            Token::RBrace,
+            "Expected '}' at end of service body",
+            Some("'}'"),
+        )?;
+
+        Ok(Stmt::Service {
+            qualifiers,
+            type_annotation,
+            attach_point,
+            listeners,
+            members,
+            span: span_start..self.previous_span().end,
+        })
+    }
+
+    /// Parses a resource method:
+    /// `[isolated] resource function accessor path(params) [returns T] { ... }`.
+    ///
+    /// The path is `.` for the root, or segments separated by `/`: names,
+    /// `[T name]` parameters, and a final `[T... name]` rest parameter.
+    fn resource_function(&mut self) -> ParseResult<Stmt> {
+        let span_start = self.current_span().start;
+        let (_, qualifiers) = self.qualifiers(&[Qualifier::Isolated])?;
+        self.consume(Token::Resource, "Expected 'resource'", Some("'resource'"))?;
+        self.consume(
+            Token::Function,
+            "Expected 'function' after 'resource'",
+            Some("'function'"),
+        )?;
+
+        let accessor = match self.advance_owned()? {
+            Token::Identifier(name) => name,
+            _ => {
+                return Err(self.error_previous(
+                    "Expected resource accessor, such as 'get'",
+                    Some("accessor"),
+                ))
+            }
+        };
+        let accessor_span = self.previous_span();
+
+        let mut path = Vec::new();
+        if !self.match_token(&[Token::Dot])? {
+            loop {
+                let segment = self.path_segment()?;
+                let is_rest = matches!(segment, PathSegment::Rest { .. });
+                path.push(segment);
+                // Nothing follows a rest parameter.
+                if is_rest || !self.match_token(&[Token::Slash])? {
+                    break;
+                }
+            }
+        }
+
+        let params = self.parameters("resource path")?;
+        let return_type = if self.match_token(&[Token::Returns])? {
+            Some(self.parse_type_descriptor()?)
+        } else {
+            None
+        };
+        self.consume(
+            Token::LBrace,
+            "Expected '{' before function body",
+            Some("'{'"),
+        )?;
+        let body = self.block()?;
+
+        Ok(Stmt::ResourceFunction {
+            qualifiers,
+            accessor,
+            accessor_span,
+            path,
+            params,
+            return_type,
+            body,
+            span: span_start..self.previous_span().end,
+        })
+    }
+
+    /// Parses one segment of a resource path: `name`, `[T name]`, or
+    /// `[T... name]`.
+    fn path_segment(&mut self) -> ParseResult<PathSegment> {
+        let span_start = self.current_span().start;
+        if !self.match_token(&[Token::LBracket])? {
+            return match self.advance_owned()? {
+                Token::Identifier(name) => Ok(PathSegment::Name {
+                    name,
+                    span: self.previous_span(),
+                }),
+                _ => Err(self.error_previous("Expected resource path segment", Some("identifier"))),
+            };
+        }
+
+        let type_desc = self.parse_type_descriptor()?;
+        let is_rest = self.match_token(&[Token::DotDotDot])?;
+        let name = match self.advance_owned()? {
+            Token::Identifier(name) => name,
+            _ => {
+                return Err(self.error_previous("Expected path parameter name", Some("identifier")))
+            }
+        };
+        let name_span = self.previous_span();
+        self.consume(
+            Token::RBracket,
+            "Expected ']' after path parameter",
+            Some("']'"),
+        )?;
+        let span = span_start..self.previous_span().end;
+        Ok(if is_rest {
+            PathSegment::Rest {
+                type_desc,
+                name,
+                name_span,
+                span,
+            }
+        } else {
+            PathSegment::Param {
+                type_desc,
+                name,
+                name_span,
+                span,
+            }
+        })
+    }
+
+    /// Parses a field of a class or object type:
+    /// `[public|private] [final] T name [= default];`.
+    fn object_field(&mut self) -> ParseResult<ObjectField> {
+        let span_start = self.current_span().start;
+        let (is_public, qualifiers) = self.qualifiers(&[Qualifier::Private, Qualifier::Final])?;
+        let type_desc = self.parse_type_descriptor()?;
+
+        let name_token = self.advance_owned()?;
+        let name = match name_token {
+            Token::Identifier(name) => name,
+            _ => {
+                return Err(
+                    self.error_previous("Expected field name after field type", Some("identifier"))
+                )
+            }
+        };
+        let name_span = self.previous_span();
+
+        let default = if self.match_token(&[Token::Eq])? {
+            Some(self.expression()?)
+        } else {
+            None
+        };
+        self.consume(Token::Semicolon, "Expected ';' after field", Some("';'"))?;
+
+        Ok(ObjectField {
+            is_public,
+            qualifiers,
+            name,
+            name_span,
+            type_desc,
+            default,
+            span: span_start..self.previous_span().end,
+        })
+    }
+
+    /// Parses an object type inclusion, `*T;` or `*module:T;`, returning the
+    /// type name and the inclusion's span.
+    fn type_inclusion(&mut self) -> ParseResult<(String, Span)> {
+        let span_start = self.current_span().start;
+        self.consume(Token::Star, "Expected '*'", Some("'*'"))?;
+        let mut type_name = match self.advance_owned()? {
+            Token::Identifier(name) => name,
+            _ => return Err(self.error_previous("Expected type name after '*'", Some("type"))),
+        };
+        if self.check(&Token::Colon) && matches!(self.peek_n(1), Some(Token::Identifier(_))) {
+            self.advance()?;
+            if let Token::Identifier(name) = self.advance_owned()? {
+                type_name = format!("{type_name}:{name}");
+            }
+        }
+        self.consume(
+            Token::Semicolon,
+            "Expected ';' after type inclusion",
+            Some("';'"),
+        )?;
+        Ok((type_name, span_start..self.previous_span().end))
+    }
+
+    /// Consumes leading `public` and `allowed` qualifier keywords, in any
+    /// order, stopping at the first other token.
+    fn qualifiers(&mut self, allowed: &[Qualifier]) -> ParseResult<(bool, Vec<Qualifier>)> {
+        let mut is_public = false;
+        let mut qualifiers = Vec::new();
+        loop {
+            if self.match_token(&[Token::Public])? {
+                is_public = true;
+                continue;
+            }
+            match self.peek().and_then(Self::qualifier) {
+                Some(qualifier) if allowed.contains(&qualifier) => {
+                    self.advance()?;
+                    qualifiers.push(qualifier);
+                }
+                _ => return Ok((is_public, qualifiers)),
+            }
+        }
+    }
+
+    /// The qualifier a keyword token stands for, if any.
+    fn qualifier(token: &Token) -> Option<Qualifier> {
+        match token {
+            Token::Private => Some(Qualifier::Private),
+            Token::Final => Some(Qualifier::Final),
+            Token::Isolated => Some(Qualifier::Isolated),
+            Token::Readonly => Some(Qualifier::Readonly),
+            Token::Distinct => Some(Qualifier::Distinct),
+            Token::Client => Some(Qualifier::Client),
+            Token::Remote => Some(Qualifier::Remote),
+            _ => None,
+        }
+    }
+
     /// Parses a `var` declaration and optional type/initializer pair.
     fn var_decl(&mut self) -> ParseResult<Stmt> {
         let mut span_start = self.current_span().start;
 
         if self.match_token(&[Token::Const])? {
             if Self::is_type_start(self.peek().unwrap()) {
-                return Err(
-                    self.error_previous("const declarations cannot have a type annotation", None)
-                );
+                return Err(ParseError::new(
+                    codes::CONST_TYPE_ANNOTATION,
+                    "const declarations cannot have a type annotation",
+                    self.previous_span(),
+                    None,
+                ));
             }
 
             let name_token = self.advance_owned()?;
@@ -414,6 +855,21 @@ impl Parser {
     fn block(&mut self) -> ParseResult<Vec<Stmt>> {
         let mut stmts = Vec::new();
         while !self.check(&Token::RBrace) && !self.is_at_end() {
+            let module_level = match self.peek_past_qualifiers() {
+                Some(Token::Type) => Some("Type definitions"),
+                Some(Token::Class) => Some("Class definitions"),
+                Some(Token::Listener) => Some("Listener declarations"),
+                Some(Token::Service) => Some("Service declarations"),
+                _ => None,
+            };
+            if let Some(declarations) = module_level {
+                return Err(ParseError::new(
+                    codes::UNEXPECTED_TOKEN,
+                    format!("{declarations} are only allowed at module level"),
+                    self.current_span(),
+                    None,
+                ));
+            }
             stmts.push(self.declaration()?);
         }
         self.consume(Token::RBrace, "Expected '}' at end of block", Some("'}'"))?;
@@ -422,9 +878,33 @@ impl Parser {
 
     /// Parses a `function` declaration including parameters, optional return type, and body.
     fn function(&mut self) -> ParseResult<Stmt> {
-        let is_public = self.match_token(&[Token::Public])?;
+        let signature = self.function_signature()?;
+        self.consume(
+            Token::LBrace,
+            "Expected '{' before function body",
+            Some("'{'"),
+        )?;
+        let body = self.block()?;
+        let body_end_span = self.previous_span();
+        Ok(Stmt::Function {
+            is_public: signature.is_public,
+            qualifiers: signature.qualifiers,
+            name: signature.name,
+            name_span: signature.name_span,
+            params: signature.params,
+            return_type: signature.return_type,
+            body,
+            span: signature.span.start..body_end_span.end,
+        })
+    }
+
+    /// Parses a function's qualifiers, name, parameters, and optional return
+    /// type, up to its body. The span starts at the `function` keyword.
+    fn function_signature(&mut self) -> ParseResult<MethodDecl> {
+        let (is_public, qualifiers) =
+            self.qualifiers(&[Qualifier::Private, Qualifier::Isolated, Qualifier::Remote])?;
 
-        self.advance()?; // consume 'function'
+        self.consume(Token::Function, "Expected 'function'", Some("'function'"))?;
         let keyword_span = self.previous_span();
         let name_token = self.advance_owned()?;
         let name_span = self.previous_span();
@@ -433,9 +913,30 @@ impl Parser {
             _ => return Err(self.error_previous("Expected function name", Some("identifier"))),
         };
 
+        let params = self.parameters("function name")?;
+        let return_type = if self.match_token(&[Token::Returns])? {
+            Some(self.parse_type_descriptor()?)
+        } else {
+            None
+        };
+
+        Ok(MethodDecl {
+            is_public,
+            qualifiers,
+            name,
+            name_span,
+            params,
+            return_type,
+            span: keyword_span.start..self.previous_span().end,
+        })
+    }
+
+    /// Parses a parenthesized parameter list, which follows `after` (named in
+    /// the error for a missing `(`).
+    fn parameters(&mut self, after: &str) -> ParseResult<Vec<(String, TypeDescriptor)>> {
         self.consume(
             Token::LParen,
-            "Expected '(' after function name",
+            &format!("Expected '(' after {after}"),
             Some("'('"),
         )?;
         let mut params = Vec::new();
@@ -453,29 +954,7 @@ impl Parser {
             }
         }
         self.consume(Token::RParen, "Expected ')' after parameters", Some("')'"))?;
-
-        let return_type = if self.match_token(&[Token::Returns])? {
-            Some(self.parse_type_descriptor()?)
-        } else {
-            None
-        };
-
-        self.consume(
-            Token::LBrace,
-            "Expected '{' before function body",
-            Some("'{'"),
-        )?;
-        let body = self.block()?;
-        let body_end_span = self.previous_span();
-        Ok(Stmt::Function {
-            is_public,
-            name,
-            name_span,
-            params,
-            return_type,
-            body,
-            span: keyword_span.start..body_end_span.end,
-        })
+        Ok(params)
     }
 
     /// Parses an expression entry point.
@@ -530,7 +1009,31 @@ impl Parser {
                 });
             }
 
+            if let Expr::FieldAccess {
+                object,
+                field,
+                field_span,
+                span: target_span,
+            } = expr
+            {
+                let op = match op_token {
+                    Token::Eq => None,
+                    Token::PlusEq => Some(BinaryOp::PlusAssign),
+                    Token::MinusEq => Some(BinaryOp::MinusAssign),
+                    _ => unreachable!(),
+                };
+                return Ok(Expr::FieldAssign {
+                    object,
+                    field,
+                    field_span,
+                    op,
+                    value: Box::new(value),
+                    span: target_span.start..value_span_end.max(assign_span.end),
+                });
+            }
+
             return Err(ParseError::new(
+                codes::INVALID_ASSIGNMENT_TARGET,
                 "Invalid assignment target",
                 assign_span,
                 Some("identifier"),
@@ -731,9 +1234,17 @@ impl Parser {
         Ok(expr)
     }
 
-    /// Parses a unary expression (`!`, unary `-`, `+`, `~`).
+    /// Parses a unary expression (`!`, unary `-`, `+`, `~`, `check`,
+    /// `checkpanic`).
     fn unary(&mut self) -> ParseResult<Expr> {
-        if self.match_token(&[Token::Bang, Token::Minus, Token::Plus, Token::Tilde])? {
+        if self.match_token(&[
+            Token::Bang,
+            Token::Minus,
+            Token::Plus,
+            Token::Tilde,
+            Token::Check,
+            Token::CheckPanic,
+        ])? {
             let op_token = self.previous().cloned().expect("operator token");
             let op_span = self.previous_span();
             let op = match op_token {
@@ -741,6 +1252,8 @@ impl Parser {
                 Token::Minus => UnaryOp::Minus,
                 Token::Plus => UnaryOp::Plus,
                 Token::Tilde => UnaryOp::BitwiseNot,
+                Token::Check => UnaryOp::Check,
+                Token::CheckPanic => UnaryOp::CheckPanic,
                 _ => unreachable!(),
             };
             let right = self.unary()?;
@@ -762,10 +1275,13 @@ impl Parser {
                 let method_name = match method_token {
                     Token::Identifier(name) => name,
                     _ => {
-                        return Err(self
-                            .error_previous("Expected method name after '.'", Some("identifier")))
+                        return Err(self.error_previous(
+                            "Expected field or method name after '.'",
+                            Some("identifier"),
+                        ))
                     }
                 };
+                let name_span = self.previous_span();
 
                 if self.match_token(&[Token::LParen])? {
                     // Method call: obj.method()
@@ -784,18 +1300,17 @@ impl Parser {
                     expr = Expr::MethodCall {
                         object: Box::new(expr),
                         method: method_name,
+                        method_span: name_span,
                         arguments,
                         span,
                     };
                 } else {
-                    // Field access: obj.field (treat as variable for now)
-                    let span = expr.span().start..self.previous_span().end;
-                    expr = Expr::MemberAccess {
+                    // Field access: obj.field
+                    let span = expr.span().start..name_span.end;
+                    expr = Expr::FieldAccess {
                         object: Box::new(expr),
-                        member: Box::new(Expr::Variable {
-                            name: method_name,
-                            span: self.previous_span(),
-                        }),
+                        field: method_name,
+                        field_span: name_span,
                         span,
                     };
                 }
@@ -936,6 +1451,40 @@ impl Parser {
                     })
                 }
             }
+            Token::New => {
+                // Object construction: new, new (args), new T(args)
+                let mut class_name = None;
+                if let Some(Token::Identifier(name)) = self.peek().cloned() {
+                    self.advance()?;
+                    let mut name = name;
+                    if self.check(&Token::Colon)
+                        && matches!(self.peek_n(1), Some(Token::Identifier(_)))
+                    {
+                        self.advance()?;
+                        if let Token::Identifier(type_name) = self.advance_owned()? {
+                            name = format!("{name}:{type_name}");
+                        }
+                    }
+                    class_name = Some(name);
+                }
+                let mut arguments = Vec::new();
+                if self.match_token(&[Token::LParen])? {
+                    if !self.check(&Token::RParen) {
+                        loop {
+                            arguments.push(self.expression()?);
+                            if !self.match_token(&[Token::Comma])? {
+                                break;
+                            }
+                        }
+                    }
+                    self.consume(Token::RParen, "Expected ')' after arguments", Some("')'"))?;
+                }
+                Ok(Expr::New {
+                    class_name,
+                    arguments,
+                    span: token_span.start..self.previous_span().end,
+                })
+            }
             Token::LParen => {
                 let open_span = token_span;
                 // Check for nil literal: ()
@@ -997,6 +1546,7 @@ impl Parser {
                 if !self.check(&Token::RBrace) {
                     loop {
                         let key_token = self.advance_owned()?;
+                        let key_span = self.previous_span();
                         let key = match key_token {
                             Token::StringLiteral(s) => s,
                             Token::Identifier(s) => s,
@@ -1010,7 +1560,11 @@ impl Parser {
 
                         self.consume(Token::Colon, "Expected ':' after map key", Some("':'"))?;
                         let value = self.expression()?;
-                        entries.push((key, value));
+                        entries.push(MapEntry {
+                            key,
+                            key_span,
+                            value,
+                        });
 
                         if !self.match_token(&[Token::Comma])? {
                             break;
@@ -1026,8 +1580,10 @@ impl Parser {
                     span: open_span.start..close_span.end,
                 })
             }
-            _ => Err(self.error_previous(
-                &format!("Unexpected token in expression: {:?}", token),
+            _ => Err(ParseError::new(
+                codes::UNEXPECTED_TOKEN,
+                format!("Unexpected token in expression: {:?}", token),
+                self.previous_span(),
                 None,
             )),
         }
@@ -1040,6 +1596,13 @@ impl Parser {
             let value_type = Box::new(self.parse_type_descriptor()?);
             self.consume(Token::Gt, "Expected '>' after map value type", Some("'>'"))?;
             TypeDescriptor::Map { value_type }
+        } else if self.match_token(&[Token::Record])? {
+            self.record_type_descriptor()?
+        } else if matches!(
+            self.peek(),
+            Some(Token::Object | Token::Isolated | Token::Client | Token::Distinct)
+        ) {
+            self.object_type_descriptor()?
         } else {
             let token = self.advance_owned()?;
             let base_type = match token {
@@ -1050,6 +1613,18 @@ impl Parser {
                 Token::Decimal => "decimal".to_string(),
                 Token::Byte => "byte".to_string(),
                 Token::Anydata => "anydata".to_string(),
+                Token::Readonly => "readonly".to_string(),
+                // A type of another module: `http:Listener`.
+                Token::Identifier(module)
+                    if self.check(&Token::Colon)
+                        && matches!(self.peek_n(1), Some(Token::Identifier(_))) =>
+                {
+                    self.advance()?;
+                    match self.advance_owned()? {
+                        Token::Identifier(name) => format!("{module}:{name}"),
+                        _ => unreachable!(),
+                    }
+                }
                 Token::Identifier(s) => s,
                 t => {
                     return Err(
@@ -1101,6 +1676,148 @@ impl Parser {
         Ok(type_desc)
     }
 
+    /// Parses the fields of a record type descriptor, after `record`.
+    ///
+    /// `{ ... }` is an open record and `{| ... |}` a closed one. Fields are
+    /// `T name;`, `T name?;`, or `T name = default;`, and a final `T...;`
+    /// gives the type of any further fields.
+    fn record_type_descriptor(&mut self) -> ParseResult<TypeDescriptor> {
+        let closed = self.match_token(&[Token::LBracePipe])?;
+        if !closed {
+            self.consume(
+                Token::LBrace,
+                "Expected '{' or '{|' after 'record'",
+                Some("'{'"),
+            )?;
+        }
+        let close = if closed {
+            Token::PipeRBrace
+        } else {
+            Token::RBrace
+        };
+
+        let mut fields = Vec::new();
+        let mut rest = None;
+        while !self.check(&close) && !self.is_at_end() {
+            let field_start = self.current_span().start;
+            let type_desc = self.parse_type_descriptor()?;
+
+            if self.match_token(&[Token::DotDotDot])? {
+                self.consume(
+                    Token::Semicolon,
+                    "Expected ';' after rest descriptor",
+                    Some("';'"),
+                )?;
+                rest = Some(Box::new(type_desc));
+                // The rest descriptor comes last.
+                break;
+            }
+
+            let name_token = self.advance_owned()?;
+            let name = match name_token {
+                Token::Identifier(name) => name,
+                _ => {
+                    return Err(self.error_previous(
+                        "Expected field name after field type",
+                        Some("identifier"),
+                    ))
+                }
+            };
+            let name_span = self.previous_span();
+
+            let optional = self.match_token(&[Token::Question])?;
+            let default = if !optional && self.match_token(&[Token::Eq])? {
+                Some(self.expression()?)
+            } else {
+                None
+            };
+            self.consume(
+                Token::Semicolon,
+                "Expected ';' after record field",
+                Some("';'"),
+            )?;
+
+            fields.push(RecordField {
+                name,
+                name_span,
+                type_desc,
+                optional,
+                default,
+                span: field_start..self.previous_span().end,
+            });
+        }
+
+        if closed {
+            self.consume(
+                Token::PipeRBrace,
+                "Expected '|}' at end of record type",
+                Some("'|}'"),
+            )?;
+        } else {
+            self.consume(
+                Token::RBrace,
+                "Expected '}' at end of record type",
+                Some("'}'"),
+            )?;
+        }
+
+        Ok(TypeDescriptor::Record {
+            fields,
+            closed,
+            rest,
+        })
+    }
+
+    /// Parses an object type descriptor:
+    /// `[isolated|client|distinct] object { ... }`.
+    ///
+    /// Members are fields, method declarations without bodies, and `*T;`
+    /// type inclusions.
+    fn object_type_descriptor(&mut self) -> ParseResult<TypeDescriptor> {
+        let mut qualifiers = Vec::new();
+        while let Some(qualifier) = self.peek().and_then(Self::qualifier) {
+            if !matches!(
+                qualifier,
+                Qualifier::Isolated | Qualifier::Client | Qualifier::Distinct
+            ) {
+                break;
+            }
+            self.advance()?;
+            qualifiers.push(qualifier);
+        }
+        self.consume(Token::Object, "Expected 'object'", Some("'object'"))?;
+        self.consume(Token::LBrace, "Expected '{' after 'object'", Some("'{'"))?;
+
+        let mut members = Vec::new();
+        while !self.check(&Token::RBrace) && !self.is_at_end() {
+            if self.check(&Token::Star) {
+                let (type_name, span) = self.type_inclusion()?;
+                members.push(ObjectMember::Inclusion { type_name, span });
+            } else if matches!(self.peek_past_qualifiers(), Some(Token::Function)) {
+                let mut method = self.function_signature()?;
+                self.consume(
+                    Token::Semicolon,
+                    "Expected ';' after method declaration",
+                    Some("';'"),
+                )?;
+                method.span.end = self.previous_span().end;
+                members.push(ObjectMember::Method(method));
+            } else {
+                members.push(ObjectMember::Field(self.object_field()?));
+            }
+        }
+        self.consume(
+            Token::RBrace,
+            "Expected '}' at end of object type",
+            Some("'}'"),
+        )?;
+
+        Ok(TypeDescriptor::Object {
+            qualifiers,
+            members,
+        })
+    }
+
     /// Utility to build a span-aware binary expression node.
     fn make_binary_expr(&self, left: Expr, op: BinaryOp, op_span: Span, right: Expr) -> Expr {
         let span_start = left.span().start.min(op_span.start);
@@ -1261,19 +1978,35 @@ impl Parser {
         end..end
     }
 
-    /// Constructs a `ParseError` for the current token position.
+    /// Constructs a missing-syntax `ParseError` for the current token position.
     fn error_here(&self, message: &str, expected: Option<&'static str>) -> ParseError {
-        ParseError::new(message.to_string(), self.current_span(), expected)
+        ParseError::new(
+            codes::EXPECTED_SYNTAX,
+            message.to_string(),
+            self.current_span(),
+            expected,
+        )
     }
 
-    /// Constructs a `ParseError` for the previously consumed token position.
+    /// Constructs a missing-syntax `ParseError` for the previously consumed
+    /// token position.
     fn error_previous(&self, message: &str, expected: Option<&'static str>) -> ParseError {
-        ParseError::new(message.to_string(), self.previous_span(), expected)
+        ParseError::new(
+            codes::EXPECTED_SYNTAX,
+            message.to_string(),
+            self.previous_span(),
+            expected,
+        )
     }
 
     /// Constructs a `ParseError` representing an unexpected end of input.
     fn unexpected_eof(&self, expected: Option<&'static str>) -> ParseError {
-        ParseError::new("Unexpected end of input", self.end_span(), expected)
+        ParseError::new(
+            codes::UNEXPECTED_EOF,
+            "Unexpected end of input",
+            self.end_span(),
+            expected,
+        )
     }
 
     /// Peeks ahead by `offset` tokens without consuming them.
@@ -1283,63 +2016,114 @@ impl Parser {
             .map(|(_, token, _)| token)
     }
 
+    /// The first token after any `public` and qualifier keywords, which
+    /// tells classes and functions apart.
+    fn peek_past_qualifiers(&self) -> Option<&Token> {
+        let mut offset = 0;
+        while matches!(
+            self.peek_n(offset),
+            Some(
+                Token::Public
+                    | Token::Private
+                    | Token::Isolated
+                    | Token::Readonly
+                    | Token::Distinct
+                    | Token::Client
+                    | Token::Remote
+            )
+        ) {
+            offset += 1;
+        }
+        self.peek_n(offset)
+    }
+
     /// Determines whether the upcoming tokens form the start of a variable declaration.
     fn starts_var_decl(&self) -> bool {
         match self.peek() {
             Some(Token::Var) | Some(Token::Final) | Some(Token::Const) => true,
+            // Only a type descriptor starts with `record`.
+            Some(Token::Record) => true,
             Some(token) if Self::is_type_start(token) => {
                 // Could be: int x, int[] x, int[3] x, etc.
-                // Need to skip type suffixes to find identifier
-                let mut offset = 1;
+                matches!(
+                    self.type_suffixes_end(token),
+                    Some(offset) if matches!(self.peek_n(offset), Some(Token::Identifier(_)))
+                )
+            }
+            Some(token @ Token::Identifier(_)) => {
+                // A named type: Person p, Person? p, Person[] people. The name
+                // must be followed by `=` or `;`, so that expressions such as
+                // `a[i] = x` or `a ? b : c` are not taken for declarations.
+                matches!(
+                    self.type_suffixes_end(token),
+                    Some(offset) if matches!(self.peek_n(offset), Some(Token::Identifier(_)))
+                        && matches!(self.peek_n(offset + 1), Some(Token::Eq | Token::Semicolon))
+                )
+            }
+            _ => false,
+        }
+    }
 
-                // Skip array brackets and other type suffixes
-                loop {
+    /// Skips the suffixes of a type descriptor starting with `first`, the
+    /// current token, and returns the offset of the token after them; `None`
+    /// when they are malformed.
+    fn type_suffixes_end(&self, first: &Token) -> Option<usize> {
+        let mut offset = 1 + self.module_prefix_len(0);
+        loop {
+            match self.peek_n(offset) {
+                Some(Token::LBracket) => {
+                    // Skip [, maybe a number, identifier, or *, then ]
+                    offset += 1;
+                    if matches!(
+                        self.peek_n(offset),
+                        Some(Token::Number(_)) | Some(Token::Star) | Some(Token::Identifier(_))
+                    ) {
+                        offset += 1;
+                    }
+                    if matches!(self.peek_n(offset), Some(Token::RBracket)) {
+                        offset += 1;
+                    } else {
+                        return None;
+                    }
+                }
+                Some(Token::Question) => {
+                    offset += 1;
+                }
+                Some(Token::Pipe) => {
+                    // Skip the next member of the union.
+                    offset += 1;
                     match self.peek_n(offset) {
-                        Some(Token::LBracket) => {
-                            // Skip [, maybe a number, identifier, or *, then ]
-                            offset += 1;
-                            if matches!(
-                                self.peek_n(offset),
-                                Some(Token::Number(_))
-                                    | Some(Token::Star)
-                                    | Some(Token::Identifier(_))
-                            ) {
-                                offset += 1;
-                            }
-                            if matches!(self.peek_n(offset), Some(Token::RBracket)) {
-                                offset += 1;
-                            } else {
-                                return false; // Malformed
-                            }
-                        }
-                        Some(Token::Question) | Some(Token::Pipe) => {
-                            offset += 1;
-                        }
-                        Some(Token::Lt) if matches!(token, Token::Map) => {
-                            // map<T> type - skip until >
-                            offset += 1;
-                            // This is simplified - real impl would need to recursively parse type
-                            while !matches!(self.peek_n(offset), Some(Token::Gt) | None) {
-                                offset += 1;
-                            }
-                            if matches!(self.peek_n(offset), Some(Token::Gt)) {
-                                offset += 1;
-                            }
-                        }
-                        Some(Token::Identifier(_)) => {
-                            return true;
-                        }
-                        _ => return false,
+                        Some(token) if Self::is_type_start(token) => offset += 1,
+                        Some(Token::Identifier(_)) => offset += 1 + self.module_prefix_len(offset),
+                        _ => return None,
                     }
                 }
+                Some(Token::Lt) if offset == 1 && matches!(first, Token::Map) => {
+                    // map<T> type - skip until >
+                    offset += 1;
+                    // This is simplified - real impl would need to recursively parse type
+                    while !matches!(self.peek_n(offset), Some(Token::Gt) | None) {
+                        offset += 1;
+                    }
+                    if matches!(self.peek_n(offset), Some(Token::Gt)) {
+                        offset += 1;
+                    }
+                }
+                _ => return Some(offset),
             }
-            Some(Token::Map) => {
-                // map<T> identifier
-                // Simplified check
-                true
-            }
-            Some(Token::Identifier(_)) => matches!(self.peek_n(1), Some(Token::Identifier(_))),
-            _ => false,
+        }
+    }
+
+    /// 2 when the identifier at `offset` is followed by `:name`, as in the
+    /// qualified type name `http:Listener`; 0 otherwise.
+    fn module_prefix_len(&self, offset: usize) -> usize {
+        if matches!(self.peek_n(offset), Some(Token::Identifier(_)))
+            && matches!(self.peek_n(offset + 1), Some(Token::Colon))
+            && matches!(self.peek_n(offset + 2), Some(Token::Identifier(_)))
+        {
+            2
+        } else {
+            0
         }
     }
 
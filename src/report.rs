@@ -0,0 +1,936 @@
+//! Rendering of diagnostics for people and machines.
+//!
+//! These helpers translate byte spans into line/column positions and either
+//! print each diagnostic with the offending source line and a caret underline,
+//! or describe it in a stable JSON schema or as a SARIF log.
+
+use crate::errors::{Diagnostic, Fix, Severity, Span};
+use crate::json::JsonValue;
+use crate::linter::Rule;
+
+/// Version of the JSON output schema produced by [`json_report`].
+///
+/// Bumped whenever a field is removed or changes meaning; new fields may be
+/// added without a version change.
+pub const JSON_SCHEMA_VERSION: usize = 1;
+
+/// A file's diagnostics together with the text their spans point into.
+#[derive(Debug, Clone, Copy)]
+pub struct FileDiagnostics<'a> {
+    pub file_name: &'a str,
+    pub source: &'a str,
+    pub diagnostics: &'a [Diagnostic],
+}
+
+/// Computes the byte indices where each line in `source` begins, including a
+/// sentinel entry for the end of the file.
+pub fn compute_line_starts(source: &str) -> Vec<usize> {
+    let mut starts = vec![0];
+    for (idx, ch) in source.char_indices() {
+        if ch == '\n' {
+            starts.push(idx + ch.len_utf8());
+        }
+    }
+    starts.push(source.len());
+    starts
+}
+
+/// Converts a byte index into a 1-based (line, column) pair using the provided
+/// line-start table.
+pub fn byte_to_line_col(line_starts: &[usize], index: usize) -> (usize, usize) {
+    if line_starts.len() <= 1 {
+        return (1, index + 1);
+    }
+
+    for (line_idx, window) in line_starts.windows(2).enumerate() {
+        if index < window[1] {
+            let col = index.saturating_sub(window[0]) + 1;
+            return (line_idx + 1, col);
+        }
+    }
+
+    let last_idx = line_starts.len().saturating_sub(2);
+    let line_start = line_starts.get(last_idx).copied().unwrap_or(0);
+    (last_idx + 1, index.saturating_sub(line_start) + 1)
+}
+
+/// Retrieves the text of a specific 1-based line number, trimming trailing
+/// newline characters.
+pub fn line_text(source: &str, line_starts: &[usize], line: usize) -> String {
+    if line == 0 || line >= line_starts.len() {
+        return String::new();
+    }
+
+    let start = line_starts[line - 1].min(source.len());
+    let end = line_starts[line].min(source.len());
+    let mut text = source[start..end].to_string();
+    while text.ends_with('\n') || text.ends_with('\r') {
+        text.pop();
+    }
+    text
+}
+
+/// Columns a tab advances to when source lines are rendered.
+const TAB_WIDTH: usize = 4;
+
+/// ANSI styles used by [`render_diagnostic`] when colors are enabled.
+const STYLE_GUTTER: &str = "1;34";
+const STYLE_SECONDARY: &str = "1;34";
+const STYLE_EMPHASIS: &str = "1";
+
+/// ANSI style for a severity: its header and primary underline.
+fn severity_style(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Error => "1;31",
+        Severity::Warning => "1;33",
+        Severity::Info => "1;32",
+        Severity::Hint => "1;36",
+    }
+}
+
+/// Wraps `text` in an ANSI style when `color` is set.
+fn paint(text: &str, style: &str, color: bool) -> String {
+    if color && !text.is_empty() {
+        format!("\x1b[{style}m{text}\x1b[0m")
+    } else {
+        text.to_string()
+    }
+}
+
+/// A position inside the rendered source: 1-based line, 0-based display
+/// column with tabs expanded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+struct Position {
+    line: usize,
+    column: usize,
+}
+
+/// A span to underline, with its optional label.
+struct Annotation<'a> {
+    start: Position,
+    /// Exclusive for single-line annotations; the column after the last
+    /// character for multi-line ones.
+    end: Position,
+    primary: bool,
+    message: Option<&'a str>,
+    /// Margin column of a multi-line annotation.
+    slot: usize,
+}
+
+impl Annotation<'_> {
+    fn is_multiline(&self) -> bool {
+        self.start.line != self.end.line
+    }
+
+    fn mark(&self) -> char {
+        if self.primary {
+            '^'
+        } else {
+            '-'
+        }
+    }
+}
+
+/// One character of an annotation line, with its style.
+type Cell = (char, Option<&'static str>);
+
+/// Renders a diagnostic in the style of rustc: a header naming the stage,
+/// severity, and code; the location; the affected source lines with the
+/// primary span underlined with `^` and related spans with `-`, each with its
+/// label; then notes and help.
+///
+/// Spans over several lines are drawn with a connecting line in the left
+/// margin. Tabs are expanded to four columns so underlines line up. With
+/// `color`, the output uses ANSI escape codes.
+pub fn render_diagnostic(
+    file_name: &str,
+    source: &str,
+    line_starts: &[usize],
+    diag: &Diagnostic,
+    color: bool,
+) -> String {
+    let severity_style = severity_style(diag.severity);
+    let position = |index: usize| {
+        let index = floor_char_boundary(source, index.min(source.len()));
+        let (line, _) = byte_to_line_col(line_starts, index);
+        let line_start = line_starts[line - 1].min(index);
+        Position {
+            line,
+            column: display_width(&source[line_start..index]),
+        }
+    };
+
+    let mut annotations = Vec::new();
+    let labels = std::iter::once((&diag.span, diag.label.as_deref(), true)).chain(
+        diag.secondary
+            .iter()
+            .map(|label| (&label.span, Some(label.message.as_str()), false)),
+    );
+    for (span, message, primary) in labels {
+        let start = position(span.start);
+        // An end just past a line break belongs to the line it ends.
+        let end = if span.end > span.start {
+            let last = position(span.end - 1);
+            let end = position(span.end);
+            if end.line != last.line {
+                Position {
+                    line: last.line,
+                    column: display_width(&line_text(source, line_starts, last.line)),
+                }
+            } else {
+                end
+            }
+        } else {
+            start
+        };
+        annotations.push(Annotation {
+            start,
+            end,
+            primary,
+            message,
+            slot: 0,
+        });
+    }
+    let mut slots = 0;
+    for annotation in annotations.iter_mut().filter(|a| a.is_multiline()) {
+        annotation.slot = slots;
+        slots += 1;
+    }
+    let margin_width = if slots > 0 { slots + 1 } else { 0 };
+
+    let mut lines = Vec::new();
+    for annotation in &annotations {
+        let (first, last) = (annotation.start.line, annotation.end.line);
+        lines.extend([first, last]);
+        if last - first <= 4 {
+            lines.extend(first..last);
+        } else {
+            lines.extend([first + 1, last - 1]);
+        }
+    }
+    lines.sort_unstable();
+    lines.dedup();
+
+    let gutter_width = lines.last().copied().unwrap_or(1).to_string().len();
+    let pad = " ".repeat(gutter_width);
+    let bar = paint("|", STYLE_GUTTER, color);
+    let mut out = String::new();
+
+    let mut header = format!("{} {}", diag.kind.as_str(), diag.severity);
+    if let Some(code) = diag.code {
+        header.push_str(&format!("[{code}]"));
+    }
+    out.push_str(&format!(
+        "{}{} {}\n",
+        paint(&header, severity_style, color),
+        paint(":", STYLE_EMPHASIS, color),
+        paint(&diag.message, STYLE_EMPHASIS, color)
+    ));
+    let (start_line, start_col) = byte_to_line_col(line_starts, diag.span.start.min(source.len()));
+    let (end_line, end_col) = byte_to_line_col(line_starts, diag.span.end.min(source.len()));
+    out.push_str(&format!(
+        "{pad}{} {file_name}:{start_line}:{start_col}-{end_line}:{end_col}\n",
+        paint("-->", STYLE_GUTTER, color)
+    ));
+    out.push_str(&format!("{pad} {bar}\n"));
+
+    let render_cells = |cells: &[Cell]| -> String {
+        let mut text = String::new();
+        let mut run = String::new();
+        let mut run_style = None;
+        for &(ch, style) in cells {
+            if style != run_style {
+                text.push_str(&paint_run(&run, run_style, color));
+                run.clear();
+                run_style = style;
+            }
+            run.push(ch);
+        }
+        text.push_str(&paint_run(&run, run_style, color));
+        text.trim_end().to_string()
+    };
+    let style_of = |annotation: &Annotation<'_>| {
+        Some(if annotation.primary {
+            severity_style
+        } else {
+            STYLE_SECONDARY
+        })
+    };
+    // The margin of `line`: a bar for each multi-line annotation that is
+    // open across it.
+    let margin = |line: usize, skip: Option<usize>| -> Vec<Cell> {
+        let mut cells = vec![(' ', None); margin_width];
+        for annotation in annotations.iter().filter(|a| a.is_multiline()) {
+            if Some(annotation.slot) != skip
+                && annotation.start.line < line
+                && line <= annotation.end.line
+            {
+                cells[annotation.slot] = ('|', style_of(annotation));
+            }
+        }
+        cells
+    };
+    let annotation_line = |cells: Vec<Cell>| format!("{pad} {bar} {}\n", render_cells(&cells));
+
+    let mut previous = None;
+    for &line in &lines {
+        if previous.is_some_and(|previous| line > previous + 1) {
+            out.push_str(&format!("{}\n", paint("...", STYLE_GUTTER, color)));
+        }
+        previous = Some(line);
+
+        let text = line_text(source, line_starts, line).replace('\t', &" ".repeat(TAB_WIDTH));
+        let mut source_cells = margin(line, None);
+        source_cells.extend(text.chars().map(|ch| (ch, None)));
+        out.push_str(&format!(
+            "{} {bar} {}\n",
+            paint(&format!("{line:>gutter_width$}"), STYLE_GUTTER, color),
+            render_cells(&source_cells)
+        ));
+
+        // Single-line annotations share one underline line; the rightmost
+        // label follows it, the others hang below on connectors.
+        let mut singles: Vec<&Annotation<'_>> = annotations
+            .iter()
+            .filter(|a| !a.is_multiline() && a.start.line == line)
+            .collect();
+        singles.sort_by_key(|a| (a.start.column, !a.primary));
+        if !singles.is_empty() {
+            let mut cells = margin(line, None);
+            // The primary span is drawn last so its carets stay visible.
+            let mut drawing = singles.clone();
+            drawing.sort_by_key(|a| a.primary);
+            for annotation in &drawing {
+                let from = margin_width + annotation.start.column;
+                let to = margin_width + annotation.end.column.max(annotation.start.column + 1);
+                if cells.len() < to {
+                    cells.resize(to, (' ', None));
+                }
+                for cell in &mut cells[from..to] {
+                    *cell = (annotation.mark(), style_of(annotation));
+                }
+            }
+            let last = singles.last().expect("non-empty");
+            let hanging: Vec<&Annotation<'_>> = singles[..singles.len() - 1]
+                .iter()
+                .copied()
+                .filter(|a| a.message.is_some())
+                .collect();
+            if let Some(message) = last.message {
+                cells.push((' ', None));
+                cells.extend(message.chars().map(|ch| (ch, style_of(last))));
+            }
+            out.push_str(&annotation_line(cells));
+
+            if !hanging.is_empty() {
+                let connectors = |count: usize| {
+                    let mut cells = margin(line, None);
+                    for annotation in &hanging[..count] {
+                        let at = margin_width + annotation.start.column;
+                        if cells.len() <= at {
+                            cells.resize(at + 1, (' ', None));
+                        }
+                        cells[at] = ('|', style_of(annotation));
+                    }
+                    cells
+                };
+                out.push_str(&annotation_line(connectors(hanging.len())));
+                for index in (0..hanging.len()).rev() {
+                    let annotation = hanging[index];
+                    let mut cells = connectors(index);
+                    let at = margin_width + annotation.start.column;
+                    if cells.len() < at {
+                        cells.resize(at, (' ', None));
+                    }
+                    cells.truncate(at);
+                    let message = annotation.message.unwrap_or("");
+                    cells.extend(message.chars().map(|ch| (ch, style_of(annotation))));
+                    out.push_str(&annotation_line(cells));
+                }
+            }
+        }
+
+        for annotation in annotations.iter().filter(|a| a.is_multiline()) {
+            let style = style_of(annotation);
+            if annotation.start.line == line {
+                // ` ____^` from the margin to the first character.
+                let mut cells = margin(line, Some(annotation.slot));
+                let to = margin_width + annotation.start.column;
+                cells.resize(to, (' ', None));
+                for cell in &mut cells[annotation.slot + 1..to] {
+                    *cell = ('_', style);
+                }
+                cells.push((annotation.mark(), style));
+                out.push_str(&annotation_line(cells));
+            }
+            if annotation.end.line == line {
+                // `|____^ label` from the margin to the last character.
+                let mut cells = margin(line, None);
+                let last = margin_width + annotation.end.column.saturating_sub(1);
+                cells.resize(last, (' ', None));
+                for cell in &mut cells[annotation.slot + 1..last] {
+                    *cell = ('_', style);
+                }
+                cells.push((annotation.mark(), style));
+                if let Some(message) = annotation.message {
+                    cells.push((' ', None));
+                    cells.extend(message.chars().map(|ch| (ch, style)));
+                }
+                out.push_str(&annotation_line(cells));
+            }
+        }
+    }
+
+    let fix = diag.fix.as_ref().map(|fix| fix.message.as_str());
+    let trailers = diag
+        .notes
+        .iter()
+        .map(|note| ("note", note.as_str()))
+        .chain(diag.help.iter().map(|help| ("help", help.as_str())))
+        .chain(fix.map(|fix| ("help", fix)));
+    for (kind, text) in trailers {
+        out.push_str(&format!(
+            "{pad} {} {}: {text}\n",
+            paint("=", STYLE_GUTTER, color),
+            paint(kind, STYLE_EMPHASIS, color)
+        ));
+    }
+    out
+}
+
+/// Paints one run of equally styled cells.
+fn paint_run(text: &str, style: Option<&str>, color: bool) -> String {
+    match style {
+        Some(style) if !text.trim().is_empty() => paint(text, style, color),
+        _ => text.to_string(),
+    }
+}
+
+/// Width of `text` in columns, with tabs expanded to [`TAB_WIDTH`].
+fn display_width(text: &str) -> usize {
+    text.chars()
+        .map(|ch| if ch == '\t' { TAB_WIDTH } else { 1 })
+        .sum()
+}
+
+/// Prints diagnostics with line/column information and relevant source
+/// snippets, rendered by [`render_diagnostic`], each followed by a blank line.
+///
+/// `file_name` prefixes each location so output from several files can be told
+/// apart.
+pub fn print_diagnostics(
+    file_name: &str,
+    source: &str,
+    line_starts: &[usize],
+    diagnostics: &[Diagnostic],
+    color: bool,
+) {
+    for diag in diagnostics {
+        println!(
+            "{}",
+            render_diagnostic(file_name, source, line_starts, diag, color)
+        );
+    }
+}
+
+/// Builds the JSON document describing every diagnostic in `files`.
+///
+/// The schema is:
+///
+/// ```text
+/// {
+///   "version": 1,
+///   "diagnostics": [{
+///     "file", "rule" (string or null), "code" (string or null), "kind",
+///     "severity", "message",
+///     "span": { "start", "end" },                     // byte offsets
+///     "range": { "start": { "line", "column" },       // 1-based; columns
+///                "end": { "line", "column" } },       // count code points
+///     "label": string or null,                          // text under the span
+///     "related": [{ "message", "span", "range" }],      // secondary spans
+///     "notes": [string],
+///     "help": [string],
+///     "fix": null | { "message", "applicability",
+///                     "edits": [{ "span": { "start", "end" }, "replacement" }] }
+///   }],
+///   "summary": { "files", "errors", "warnings", "infos", "hints" }
+/// }
+/// ```
+///
+/// `files_checked` is reported in the summary; it may differ from `files.len()`
+/// when clean files are omitted or configuration files are included.
+pub fn json_report(files: &[FileDiagnostics<'_>], files_checked: usize) -> JsonValue {
+    let mut entries = Vec::new();
+    for file in files {
+        let line_starts = compute_line_starts(file.source);
+        for diag in file.diagnostics {
+            entries.push(diagnostic_json(file, &line_starts, diag));
+        }
+    }
+
+    let count = |severity: Severity| -> JsonValue {
+        files
+            .iter()
+            .flat_map(|file| file.diagnostics)
+            .filter(|diag| diag.severity == severity)
+            .count()
+            .into()
+    };
+
+    JsonValue::object([
+        ("version", JSON_SCHEMA_VERSION.into()),
+        ("diagnostics", JsonValue::Array(entries)),
+        (
+            "summary",
+            JsonValue::object([
+                ("files", files_checked.into()),
+                ("errors", count(Severity::Error)),
+                ("warnings", count(Severity::Warning)),
+                ("infos", count(Severity::Info)),
+                ("hints", count(Severity::Hint)),
+            ]),
+        ),
+    ])
+}
+
+/// Describes a single diagnostic as a JSON object.
+fn diagnostic_json(
+    file: &FileDiagnostics<'_>,
+    line_starts: &[usize],
+    diag: &Diagnostic,
+) -> JsonValue {
+    let start = diag.span.start.min(file.source.len());
+    let end = diag.span.end.min(file.source.len()).max(start);
+    let position = |index: usize| {
+        let (line, column) = char_line_col(file.source, line_starts, index);
+        JsonValue::object([("line", line.into()), ("column", column.into())])
+    };
+    let related = diag
+        .secondary
+        .iter()
+        .map(|label| {
+            let start = label.span.start.min(file.source.len());
+            let end = label.span.end.min(file.source.len()).max(start);
+            JsonValue::object([
+                ("message", label.message.as_str().into()),
+                (
+                    "span",
+                    JsonValue::object([("start", start.into()), ("end", end.into())]),
+                ),
+                (
+                    "range",
+                    JsonValue::object([("start", position(start)), ("end", position(end))]),
+                ),
+            ])
+        })
+        .collect();
+
+    JsonValue::object([
+        ("file", file.file_name.into()),
+        ("rule", diag.rule.clone().into()),
+        ("code", diag.code.into()),
+        ("kind", diag.kind.as_str().into()),
+        ("severity", diag.severity.as_str().into()),
+        ("message", diag.message.clone().into()),
+        (
+            "span",
+            JsonValue::object([("start", start.into()), ("end", end.into())]),
+        ),
+        (
+            "range",
+            JsonValue::object([("start", position(start)), ("end", position(end))]),
+        ),
+        ("label", diag.label.clone().into()),
+        ("related", JsonValue::Array(related)),
+        ("notes", diag.notes.clone().into()),
+        ("help", diag.help.clone().into()),
+        ("fix", diag.fix.as_deref().map(fix_json).into()),
+    ])
+}
+
+/// Describes a suggested fix as a JSON object.
+fn fix_json(fix: &Fix) -> JsonValue {
+    let edits = fix
+        .edits
+        .iter()
+        .map(|edit| {
+            JsonValue::object([
+                (
+                    "span",
+                    JsonValue::object([
+                        ("start", edit.span.start.into()),
+                        ("end", edit.span.end.into()),
+                    ]),
+                ),
+                ("replacement", edit.replacement.as_str().into()),
+            ])
+        })
+        .collect();
+    JsonValue::object([
+        ("message", fix.message.as_str().into()),
+        ("applicability", fix.applicability.as_str().into()),
+        ("edits", JsonValue::Array(edits)),
+    ])
+}
+
+/// Prints one row per rule: its name, code, category, default severity,
+/// whether it offers fixes, its options with their defaults, and its
+/// description.
+pub fn print_rules(rules: &[Box<dyn Rule>]) {
+    let header = [
+        "RULE",
+        "CODE",
+        "CATEGORY",
+        "SEVERITY",
+        "FIX",
+        "OPTIONS",
+        "DESCRIPTION",
+    ];
+    let mut rows = vec![header.map(str::to_string)];
+    for rule in rules {
+        let options = rule
+            .options()
+            .iter()
+            .map(|option| format!("{}={}", option.name, option.default))
+            .collect::<Vec<_>>();
+        rows.push([
+            rule.name().to_string(),
+            rule.code().to_string(),
+            rule.category().as_str().to_string(),
+            rule.default_severity().as_str().to_string(),
+            if rule.fixable() { "yes" } else { "no" }.to_string(),
+            if options.is_empty() {
+                "-".to_string()
+            } else {
+                options.join(", ")
+            },
+            rule.description().to_string(),
+        ]);
+    }
+
+    let mut widths = [0; 6];
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    for row in &rows {
+        let mut line = String::new();
+        for (width, cell) in widths.iter().zip(row) {
+            line.push_str(&format!("{cell:<width$}  "));
+        }
+        line.push_str(&row[6]);
+        println!("{line}");
+    }
+}
+
+/// Describes every rule for `--list-rules --format json`.
+///
+/// The schema is:
+///
+/// ```text
+/// {
+///   "version": 1,
+///   "rules": [{
+///     "name", "code", "category", "default_severity", "fixable" (bool),
+///     "description",
+///     "options": [{ "name", "description", "default" }]
+///   }]
+/// }
+/// ```
+pub fn rules_json(rules: &[Box<dyn Rule>]) -> JsonValue {
+    let rules = rules
+        .iter()
+        .map(|rule| {
+            let options = rule
+                .options()
+                .iter()
+                .map(|option| {
+                    JsonValue::object([
+                        ("name", option.name.into()),
+                        ("description", option.description.into()),
+                        ("default", option.default.into()),
+                    ])
+                })
+                .collect();
+            JsonValue::object([
+                ("name", rule.name().into()),
+                ("code", rule.code().into()),
+                ("category", rule.category().as_str().into()),
+                ("default_severity", rule.default_severity().as_str().into()),
+                ("fixable", rule.fixable().into()),
+                ("description", rule.description().into()),
+                ("options", JsonValue::Array(options)),
+            ])
+        })
+        .collect();
+    JsonValue::object([
+        ("version", JSON_SCHEMA_VERSION.into()),
+        ("rules", JsonValue::Array(rules)),
+    ])
+}
+
+/// Version of the SARIF specification produced by [`sarif_report`].
+pub const SARIF_VERSION: &str = "2.1.0";
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+/// Builds a SARIF 2.1.0 log describing every diagnostic in `files`.
+///
+/// `rules` populates `tool.driver.rules` and should list every registered
+/// rule, including disabled ones, so results can refer to them by index.
+/// Lexer, parser, semantic, and config diagnostics have no rule; their
+/// `ruleId` is the diagnostic kind (for example `parser`).
+///
+/// Regions carry 1-based lines and Unicode code point columns alongside the
+/// exact byte range. Diagnostics silenced by a suppression comment are listed
+/// with an `inSource` suppression; every other result has an empty
+/// `suppressions` array, which SARIF consumers read as "not suppressed".
+pub fn sarif_report(files: &[FileDiagnostics<'_>], rules: &[Box<dyn Rule>]) -> JsonValue {
+    let rule_descriptors = rules
+        .iter()
+        .map(|rule| {
+            JsonValue::object([
+                ("id", rule.name().into()),
+                (
+                    "shortDescription",
+                    JsonValue::object([("text", rule.description().into())]),
+                ),
+                (
+                    "defaultConfiguration",
+                    JsonValue::object([("level", sarif_level(rule.default_severity()).into())]),
+                ),
+                (
+                    "properties",
+                    JsonValue::object([
+                        ("code", rule.code().into()),
+                        (
+                            "tags",
+                            JsonValue::Array(vec![rule.category().as_str().into()]),
+                        ),
+                    ]),
+                ),
+            ])
+        })
+        .collect();
+
+    let mut results = Vec::new();
+    for file in files {
+        let line_starts = compute_line_starts(file.source);
+        for diag in file.diagnostics {
+            results.push(sarif_result(file, &line_starts, diag, rules));
+        }
+    }
+
+    let driver = JsonValue::object([
+        ("name", "blazelint".into()),
+        ("version", env!("CARGO_PKG_VERSION").into()),
+        ("informationUri", env!("CARGO_PKG_REPOSITORY").into()),
+        ("rules", JsonValue::Array(rule_descriptors)),
+    ]);
+
+    JsonValue::object([
+        ("$schema", SARIF_SCHEMA.into()),
+        ("version", SARIF_VERSION.into()),
+        (
+            "runs",
+            JsonValue::Array(vec![JsonValue::object([
+                ("tool", JsonValue::object([("driver", driver)])),
+                ("columnKind", "unicodeCodePoints".into()),
+                ("results", JsonValue::Array(results)),
+            ])]),
+        ),
+    ])
+}
+
+/// Describes a single diagnostic as a SARIF `result` object.
+fn sarif_result(
+    file: &FileDiagnostics<'_>,
+    line_starts: &[usize],
+    diag: &Diagnostic,
+    rules: &[Box<dyn Rule>],
+) -> JsonValue {
+    let source = file.source;
+    let start = floor_char_boundary(source, diag.span.start.min(source.len()));
+    let end = floor_char_boundary(source, diag.span.end.min(source.len())).max(start);
+    let location = |span: &Span| {
+        let start = floor_char_boundary(source, span.start.min(source.len()));
+        let end = floor_char_boundary(source, span.end.min(source.len())).max(start);
+        let (start_line, start_column) = char_line_col(source, line_starts, start);
+        let (end_line, end_column) = char_line_col(source, line_starts, end);
+        JsonValue::object([
+            (
+                "artifactLocation",
+                JsonValue::object([("uri", artifact_uri(file.file_name).into())]),
+            ),
+            (
+                "region",
+                JsonValue::object([
+                    ("startLine", start_line.into()),
+                    ("startColumn", start_column.into()),
+                    ("endLine", end_line.into()),
+                    ("endColumn", end_column.into()),
+                    ("byteOffset", start.into()),
+                    ("byteLength", (end - start).into()),
+                ]),
+            ),
+        ])
+    };
+
+    let mut text = diag.message.clone();
+    for note in &diag.notes {
+        text.push_str("\nnote: ");
+        text.push_str(note);
+    }
+    for help in &diag.help {
+        text.push_str("\nhelp: ");
+        text.push_str(help);
+    }
+
+    let mut entries = vec![(
+        "ruleId",
+        diag.rule.as_deref().unwrap_or(diag.kind.as_str()).into(),
+    )];
+    if let Some(index) = diag
+        .rule
+        .as_deref()
+        .and_then(|name| rules.iter().position(|rule| rule.name() == name))
+    {
+        entries.push(("ruleIndex", index.into()));
+    }
+    entries.extend([
+        ("level", sarif_level(diag.severity).into()),
+        ("message", JsonValue::object([("text", text.into())])),
+        (
+            "locations",
+            JsonValue::Array(vec![JsonValue::object([(
+                "physicalLocation",
+                location(&(start..end)),
+            )])]),
+        ),
+        ("suppressions", sarif_suppressions(diag)),
+    ]);
+    if !diag.secondary.is_empty() {
+        let related = diag
+            .secondary
+            .iter()
+            .enumerate()
+            .map(|(id, label)| {
+                JsonValue::object([
+                    ("id", id.into()),
+                    ("physicalLocation", location(&label.span)),
+                    (
+                        "message",
+                        JsonValue::object([("text", label.message.as_str().into())]),
+                    ),
+                ])
+            })
+            .collect();
+        entries.push(("relatedLocations", JsonValue::Array(related)));
+    }
+    if let Some(fix) = &diag.fix {
+        entries.push(("fixes", sarif_fixes(file, fix)));
+    }
+    JsonValue::object(entries)
+}
+
+/// Describes a suggested fix as a SARIF `fixes` array.
+fn sarif_fixes(file: &FileDiagnostics<'_>, fix: &Fix) -> JsonValue {
+    let replacements = fix
+        .edits
+        .iter()
+        .map(|edit| {
+            JsonValue::object([
+                (
+                    "deletedRegion",
+                    JsonValue::object([
+                        ("byteOffset", edit.span.start.into()),
+                        ("byteLength", (edit.span.end - edit.span.start).into()),
+                    ]),
+                ),
+                (
+                    "insertedContent",
+                    JsonValue::object([("text", edit.replacement.as_str().into())]),
+                ),
+            ])
+        })
+        .collect();
+    JsonValue::Array(vec![JsonValue::object([
+        (
+            "description",
+            JsonValue::object([("text", fix.message.as_str().into())]),
+        ),
+        (
+            "artifactChanges",
+            JsonValue::Array(vec![JsonValue::object([
+                (
+                    "artifactLocation",
+                    JsonValue::object([("uri", artifact_uri(file.file_name).into())]),
+                ),
+                ("replacements", JsonValue::Array(replacements)),
+            ])]),
+        ),
+    ])])
+}
+
+/// Describes the suppression state of a diagnostic.
+fn sarif_suppressions(diag: &Diagnostic) -> JsonValue {
+    let Some(suppression) = &diag.suppression else {
+        return JsonValue::Array(Vec::new());
+    };
+    let mut entry = vec![("kind", JsonValue::from("inSource"))];
+    if let Some(justification) = &suppression.justification {
+        entry.push(("justification", justification.as_str().into()));
+    }
+    JsonValue::Array(vec![JsonValue::object(entry)])
+}
+
+/// Maps a severity onto SARIF's `level` values.
+fn sarif_level(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Error => "error",
+        Severity::Warning => "warning",
+        Severity::Info | Severity::Hint => "note",
+    }
+}
+
+/// Turns a file path into a URI: relative paths stay relative references and
+/// absolute paths become `file:` URIs. Other characters are percent-encoded.
+pub(crate) fn artifact_uri(path: &str) -> String {
+    let path = path.replace('\\', "/");
+    let path = path.strip_prefix("./").unwrap_or(&path);
+    let (mut uri, rest) = if let Some(rest) = path.strip_prefix('/') {
+        ("file:///".to_string(), rest)
+    } else if path.as_bytes().get(1) == Some(&b':') {
+        // A Windows drive letter such as `C:/src/main.bal`.
+        (format!("file:///{}", &path[..2]), &path[2..])
+    } else {
+        (String::new(), path)
+    };
+    for byte in rest.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' => {
+                uri.push(byte as char)
+            }
+            other => uri.push_str(&format!("%{other:02X}")),
+        }
+    }
+    uri
+}
+
+/// Converts a byte index into a 1-based (line, column) pair whose column
+/// counts Unicode code points, as JSON and SARIF output report them.
+fn char_line_col(source: &str, line_starts: &[usize], index: usize) -> (usize, usize) {
+    let index = floor_char_boundary(source, index);
+    let (line, _) = byte_to_line_col(line_starts, index);
+    let line_start = line_starts[line - 1].min(index);
+    (line, source[line_start..index].chars().count() + 1)
+}
+
+/// Moves `index` back to the nearest UTF-8 character boundary.
+fn floor_char_boundary(source: &str, mut index: usize) -> usize {
+    while !source.is_char_boundary(index) {
+        index -= 1;
+    }
+    index
+}
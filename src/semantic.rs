@@ -4,9 +4,18 @@
 //! scopes, and enforces the subset of Ballerina typing rules supported by the
 //! linter. Each visitor emits structured diagnostics tagged with source spans
 //! so the CLI can highlight offending code precisely.
-use crate::ast::{BinaryOp, Expr, Literal, Stmt, TypeDescriptor, UnaryOp};
+//!
+//! Analysis also produces a [`SemanticModel`] recording what it learned: every
+//! declaration, the scope tree, the symbol each identifier resolves to, and the
+//! type of each expression. Lint rules query it instead of re-deriving types.
+use crate::ast::{
+    BinaryOp, ClassMember, Expr, Literal, MapEntry, MethodDecl, ObjectMember, PathSegment,
+    Qualifier, RecordField, Stmt, TypeDescriptor, UnaryOp,
+};
+use crate::codes;
 use crate::errors::{Diagnostic, DiagnosticKind, Span};
 use std::collections::{HashMap, HashSet};
+use std::fmt;
 
 /// Internal representation of the types the analyzer understands.
 #[derive(Debug, Clone, PartialEq)]
@@ -19,9 +28,159 @@ pub enum Type {
     Nil,
     Array(Box<Type>),
     Map(Box<Type>),
+    Record(Box<RecordType>),
+    Object(Box<ObjectType>),
+    /// A union of two or more distinct types, from `T1|T2`.
+    Union(Vec<Type>),
     Unknown(String),
 }
 
+/// A record type, from a `record` type descriptor.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordType {
+    /// The name of the type definition that declared it, if any.
+    pub name: Option<String>,
+    pub fields: Vec<Field>,
+    /// Written with `{| |}`.
+    pub closed: bool,
+    /// The type given by a `T...;` rest descriptor.
+    pub rest: Option<Type>,
+}
+
+/// A field of a [`RecordType`] or [`ObjectType`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Field {
+    pub name: String,
+    pub ty: Type,
+    /// Whether values must give the field: it is neither optional (`name?`)
+    /// nor has a default value. An object's `init` sets such fields.
+    pub required: bool,
+    /// `final`, or a field of a `readonly` object: only `init` may set it.
+    pub is_final: bool,
+    /// `private`: only the methods of the declaring class may use it.
+    pub is_private: bool,
+    /// The declared name of an object field, for labels on diagnostics.
+    pub name_span: Option<Span>,
+}
+
+/// An object type, from a class definition or an `object` type descriptor.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ObjectType {
+    /// The class or type definition that declared it, if any.
+    pub name: Option<String>,
+    /// Declared by a class, so only instances of that class belong to it;
+    /// other object types admit any object with their fields and methods.
+    pub is_class: bool,
+    /// Declared `readonly`, so no field changes after `init`.
+    pub is_readonly: bool,
+    /// Declared fields, then those of included types.
+    pub fields: Vec<Field>,
+    /// Declared methods, then those of included types.
+    pub methods: Vec<Method>,
+}
+
+/// A method of an [`ObjectType`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Method {
+    pub name: String,
+    pub params: Vec<Type>,
+    pub return_type: Type,
+}
+
+impl RecordType {
+    pub fn field(&self, name: &str) -> Option<&Field> {
+        self.fields.iter().find(|field| field.name == name)
+    }
+
+    /// The type of fields beyond the declared ones: the rest descriptor's, or
+    /// `anydata` for an open record without one. `None` when the record
+    /// admits no other fields.
+    pub fn rest_type(&self) -> Option<Type> {
+        match &self.rest {
+            Some(rest) => Some(rest.clone()),
+            None if !self.closed => Some(Type::Unknown("anydata".to_string())),
+            None => None,
+        }
+    }
+
+    fn description(&self) -> String {
+        if let Some(name) = &self.name {
+            return name.clone();
+        }
+        let (open, close) = if self.closed {
+            ("{|", "|}")
+        } else {
+            ("{", "}")
+        };
+        let mut text = format!("record {open}");
+        for field in &self.fields {
+            text.push_str(&format!(" {} {};", field.ty.description(), field.name));
+        }
+        if let Some(rest) = &self.rest {
+            text.push_str(&format!(" {}...;", rest.description()));
+        }
+        text.push_str(&format!(" {close}"));
+        text
+    }
+}
+
+impl ObjectType {
+    pub fn field(&self, name: &str) -> Option<&Field> {
+        self.fields.iter().find(|field| field.name == name)
+    }
+
+    pub fn method(&self, name: &str) -> Option<&Method> {
+        self.methods.iter().find(|method| method.name == name)
+    }
+
+    /// Whether `value` has every field and method of this type, with the
+    /// same types.
+    fn admits(&self, value: &ObjectType) -> bool {
+        self.fields.iter().all(|field| {
+            value
+                .field(&field.name)
+                .is_some_and(|other| other.ty == field.ty)
+        }) && self
+            .methods
+            .iter()
+            .all(|method| value.method(&method.name) == Some(method))
+    }
+
+    fn description(&self) -> String {
+        if let Some(name) = &self.name {
+            return name.clone();
+        }
+        let mut text = "object {".to_string();
+        for field in &self.fields {
+            text.push_str(&format!(" {} {};", field.ty.description(), field.name));
+        }
+        for method in &self.methods {
+            let params: Vec<String> = method.params.iter().map(Type::description).collect();
+            text.push_str(&format!(" function {}({})", method.name, params.join(", ")));
+            if method.return_type != Type::Nil {
+                text.push_str(&format!(" returns {}", method.return_type.description()));
+            }
+            text.push(';');
+        }
+        text.push_str(" }");
+        text
+    }
+
+    /// "Class C" or "Object type T", to start diagnostics about the type.
+    fn kind_description(&self) -> String {
+        if self.is_class && self.name.is_none() {
+            // Only services declare unnamed classes.
+            return "Service".to_string();
+        }
+        let kind = if self.is_class {
+            "Class"
+        } else {
+            "Object type"
+        };
+        format!("{kind} {}", self.description())
+    }
+}
+
 impl Type {
     /// Returns a human-readable name used in diagnostics and notes.
     fn description(&self) -> String {
@@ -34,16 +193,251 @@ impl Type {
             Type::Nil => "()".to_string(),
             Type::Array(elem) => format!("{}[]", elem.description()),
             Type::Map(val) => format!("map<{}>", val.description()),
+            Type::Record(record) => record.description(),
+            Type::Object(object) => object.description(),
+            Type::Union(members) => members
+                .iter()
+                .map(Type::description)
+                .collect::<Vec<_>>()
+                .join("|"),
             Type::Unknown(name) => name.clone(),
         }
     }
 
+    /// The union of `members`, flattening nested unions and dropping
+    /// repeated members; a single remaining member is returned as is.
+    fn union(members: Vec<Type>) -> Type {
+        let mut flat = Vec::new();
+        for member in members {
+            let nested = match member {
+                Type::Union(nested) => nested,
+                other => vec![other],
+            };
+            for member in nested {
+                if !flat.contains(&member) {
+                    flat.push(member);
+                }
+            }
+        }
+        if flat.len() == 1 {
+            flat.remove(0)
+        } else {
+            Type::Union(flat)
+        }
+    }
+
+    /// Whether values of the type may be errors: `error`, a union with an
+    /// `error` member, or a type the analyzer does not know.
+    fn may_be_error(&self) -> bool {
+        match self {
+            Type::Error | Type::Unknown(_) => true,
+            Type::Union(members) => members.iter().any(Type::may_be_error),
+            _ => false,
+        }
+    }
+
+    /// The type of `check e` or `checkpanic e` for `e` of this type: the
+    /// type without its `error` members.
+    fn checked(&self) -> Type {
+        match self {
+            // Only ever an error, so the expression never has a value.
+            Type::Error => Type::Unknown("never".to_string()),
+            Type::Union(members) => Type::union(
+                members
+                    .iter()
+                    .filter(|member| **member != Type::Error)
+                    .cloned()
+                    .collect(),
+            ),
+            other => other.clone(),
+        }
+    }
+
     /// Indicates whether the value arose from an unresolved or deferred type.
     fn is_unknown(&self) -> bool {
         matches!(self, Type::Unknown(_))
     }
 }
 
+impl fmt::Display for Type {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.description())
+    }
+}
+
+/// What introduced a symbol into scope.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymbolKind {
+    Variable,
+    Constant,
+    Parameter,
+    LoopVariable,
+    Function,
+    /// A type definition.
+    Type,
+    Class,
+    /// A method of a class or service.
+    Method,
+    Listener,
+}
+
+impl SymbolKind {
+    /// Lower-case name used in debug output.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SymbolKind::Variable => "variable",
+            SymbolKind::Constant => "constant",
+            SymbolKind::Parameter => "parameter",
+            SymbolKind::LoopVariable => "loop variable",
+            SymbolKind::Function => "function",
+            SymbolKind::Type => "type",
+            SymbolKind::Class => "class",
+            SymbolKind::Method => "method",
+            SymbolKind::Listener => "listener",
+        }
+    }
+}
+
+/// Identifies a declaration within one [`SemanticModel`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SymbolId(usize);
+
+impl SymbolId {
+    /// Position of the declaration in [`SemanticModel::symbols`].
+    pub fn index(self) -> usize {
+        self.0
+    }
+}
+
+/// Identifies a scope within one [`SemanticModel`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ScopeId(usize);
+
+impl ScopeId {
+    /// Position of the scope in [`SemanticModel::scopes`].
+    pub fn index(self) -> usize {
+        self.0
+    }
+}
+
+/// A declaration recorded during analysis, as listed by [`symbols`].
+#[derive(Debug, Clone)]
+pub struct SymbolInfo {
+    pub id: SymbolId,
+    pub name: String,
+    pub kind: SymbolKind,
+    /// Declared or inferred type; a function's type is its return type.
+    pub ty: Type,
+    /// Span the analyzer associates with the declaration.
+    pub span: Span,
+    /// The declared name itself, when the AST records where it is; parameters
+    /// and loop variables have none.
+    pub name_span: Option<Span>,
+    /// The scope the declaration is bound in.
+    pub scope: ScopeId,
+    /// Nesting depth of the declaring scope; 0 is module level.
+    pub scope_depth: usize,
+}
+
+/// What opened a scope.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScopeKind {
+    Module,
+    Function,
+    /// An `if` or `else` branch.
+    Block,
+    /// A `while` or `foreach` body.
+    Loop,
+    /// A class or service body, holding its methods.
+    Class,
+}
+
+/// A node of the scope tree.
+#[derive(Debug, Clone)]
+pub struct ScopeInfo {
+    pub kind: ScopeKind,
+    /// The enclosing scope; `None` only for the module scope.
+    pub parent: Option<ScopeId>,
+    /// Span of the statement that opened the scope; `None` for the module.
+    pub span: Option<Span>,
+    /// Declarations bound directly in this scope, in order.
+    pub symbols: Vec<SymbolId>,
+}
+
+/// Everything semantic analysis learned about a program.
+///
+/// Identifiers and expressions are looked up by their span, as recorded in
+/// the AST.
+#[derive(Debug, Clone)]
+pub struct SemanticModel {
+    symbols: Vec<SymbolInfo>,
+    scopes: Vec<ScopeInfo>,
+    references: HashMap<Span, SymbolId>,
+    expr_types: HashMap<Span, Type>,
+}
+
+impl Default for SemanticModel {
+    /// A model of an empty program: just the module scope.
+    fn default() -> Self {
+        Self {
+            symbols: Vec::new(),
+            scopes: vec![ScopeInfo {
+                kind: ScopeKind::Module,
+                parent: None,
+                span: None,
+                symbols: Vec::new(),
+            }],
+            references: HashMap::new(),
+            expr_types: HashMap::new(),
+        }
+    }
+}
+
+impl SemanticModel {
+    /// Every declaration, in the order the analyzer met them.
+    pub fn symbols(&self) -> &[SymbolInfo] {
+        &self.symbols
+    }
+
+    pub fn symbol(&self, id: SymbolId) -> &SymbolInfo {
+        &self.symbols[id.0]
+    }
+
+    /// Every scope; the module scope comes first and parents precede their
+    /// children.
+    pub fn scopes(&self) -> &[ScopeInfo] {
+        &self.scopes
+    }
+
+    pub fn scope(&self, id: ScopeId) -> &ScopeInfo {
+        &self.scopes[id.0]
+    }
+
+    /// The declaration an identifier refers to: a variable reference, an
+    /// assignment target, or a called function name. `span` covers just the
+    /// identifier.
+    pub fn resolve(&self, span: &Span) -> Option<&SymbolInfo> {
+        self.references.get(span).map(|&id| self.symbol(id))
+    }
+
+    /// Spans of every identifier resolving to `id`, in source order.
+    pub fn references(&self, id: SymbolId) -> Vec<Span> {
+        let mut spans: Vec<Span> = self
+            .references
+            .iter()
+            .filter(|(_, &target)| target == id)
+            .map(|(span, _)| span.clone())
+            .collect();
+        spans.sort_by_key(|span| (span.start, span.end));
+        spans
+    }
+
+    /// The type inferred for `expr`, if analysis reached it.
+    pub fn type_of(&self, expr: &Expr) -> Option<&Type> {
+        self.expr_types.get(expr.span())
+    }
+}
+
 /// Tracked metadata for a symbol bound in the current scope stack.
 #[derive(Clone)]
 pub struct Symbol {
@@ -57,16 +451,59 @@ pub struct Symbol {
 /// Context for the function currently being analyzed.
 struct FunctionContext {
     return_type: Type,
+    /// The name of a method of a class or service, and its `self` type.
+    method: Option<(String, Type)>,
+}
+
+/// A function collected before checking, so calls reached ahead of its
+/// declaration know what it returns.
+struct FunctionInfo {
+    name_span: Span,
+    return_type: ReturnType,
+}
+
+/// The return type of a [`FunctionInfo`], converted the first time a call or
+/// the declaration needs it.
+enum ReturnType {
+    /// As written; `None` returns nil.
+    Declared(Option<TypeDescriptor>),
+    Resolved(Type),
+}
+
+/// A module-level `type` or class definition, kept until the type is
+/// resolved. A class is kept as the object type descriptor of its fields,
+/// inclusions, and method signatures.
+#[derive(Clone)]
+struct TypeDefinition {
+    type_desc: TypeDescriptor,
+    is_class: bool,
+    name_span: Span,
+    span: Span,
 }
 
 /// Performs semantic validation over a sequence of statements.
 pub struct Analyzer {
-    scopes: Vec<HashMap<String, Symbol>>,
+    /// Names bound in each open scope, innermost last.
+    scopes: Vec<HashMap<String, SymbolId>>,
+    /// The model scope matching each entry of `scopes`.
+    scope_ids: Vec<ScopeId>,
+    /// Binding state of every declared symbol, indexed by `SymbolId`.
+    symbol_states: Vec<Symbol>,
     diagnostics: Vec<Diagnostic>,
     current_function: Option<FunctionContext>,
-    functions: HashSet<String>,
+    /// Functions by name; the first declaration of a name wins.
+    functions: HashMap<String, FunctionInfo>,
+    /// Module-level type definitions by name.
+    type_definitions: HashMap<String, TypeDefinition>,
+    /// Type definitions resolved so far.
+    types: HashMap<String, Type>,
+    /// Type definitions being resolved, to stop at recursive references.
+    resolving_types: HashSet<String>,
     imports: HashSet<String>,
     loop_depth: usize,
+    /// Calls to local functions, resolved once every function is declared.
+    pending_calls: Vec<(String, Span)>,
+    model: SemanticModel,
 }
 
 impl Analyzer {
@@ -74,24 +511,38 @@ impl Analyzer {
     fn new() -> Self {
         Self {
             scopes: vec![HashMap::new()],
+            scope_ids: vec![ScopeId(0)],
+            symbol_states: Vec::new(),
             diagnostics: Vec::new(),
             current_function: None,
-            functions: HashSet::new(),
+            functions: HashMap::new(),
+            type_definitions: HashMap::new(),
+            types: HashMap::new(),
+            resolving_types: HashSet::new(),
             imports: HashSet::new(),
             loop_depth: 0,
+            pending_calls: Vec::new(),
+            model: SemanticModel::default(),
         }
     }
 
-    /// Entry point used by the public `analyze` facade.
-    fn analyze(mut self, stmts: &[Stmt]) -> Result<(), Vec<Diagnostic>> {
+    /// Checks every top-level statement, recording diagnostics and declarations.
+    fn check_program(&mut self, stmts: &[Stmt]) {
         self.collect_functions(stmts);
+        self.collect_types(stmts);
         for stmt in stmts {
             self.check_stmt(stmt);
         }
-        if self.diagnostics.is_empty() {
-            Ok(())
-        } else {
-            Err(self.diagnostics)
+
+        for (name, span) in std::mem::take(&mut self.pending_calls) {
+            let function = self
+                .model
+                .symbols
+                .iter()
+                .find(|symbol| symbol.kind == SymbolKind::Function && symbol.name == name);
+            if let Some(function) = function {
+                self.model.references.insert(span, function.id);
+            }
         }
     }
 
@@ -117,18 +568,23 @@ impl Analyzer {
 
                 if *is_final && initializer.is_none() {
                     self.report(
+                        codes::FINAL_NOT_INITIALIZED,
                         span.clone(),
                         format!("final variable '{name}' must be initialised"),
                     );
                 }
 
-                if let Some(existing) = self.current_scope().get(name) {
-                    self.report(
-                        name_span.clone(),
-                        format!(
-                            "Redeclaration of variable '{name}' (previously declared at {}..{})",
-                            existing.declared_span.start, existing.declared_span.end
-                        ),
+                if let Some(previous) = self.current_declaration(name) {
+                    let previous = previous.name_span.clone().unwrap_or(previous.span.clone());
+                    self.diagnostics.push(
+                        Diagnostic::new(
+                            DiagnosticKind::Semantic,
+                            format!("Redeclaration of variable '{name}'"),
+                            name_span.clone(),
+                        )
+                        .with_code(codes::REDECLARATION)
+                        .with_label("redeclared here")
+                        .with_secondary(previous, format!("'{name}' first declared here")),
                     );
                     return;
                 }
@@ -142,10 +598,14 @@ impl Analyzer {
                 };
 
                 if let Some(expr) = initializer {
-                    let expr_type = self.check_expr(expr);
+                    let expr_type = match &declared_type {
+                        Some(declared) => self.check_expected(expr, declared),
+                        None => self.check_expr(expr),
+                    };
                     if let Some(declared) = declared_type {
                         if !Self::can_assign(&declared, &expr_type) {
                             self.report(
+                                codes::TYPE_MISMATCH,
                                 expr.span().clone(),
                                 format!(
                                     "Type mismatch in initializer: expected {}, found {}",
@@ -161,7 +621,7 @@ impl Analyzer {
                     symbol.initialized = true;
                 }
 
-                self.current_scope_mut().insert(name.clone(), symbol);
+                self.declare(name, SymbolKind::Variable, Some(name_span), symbol);
             }
             Stmt::ConstDecl {
                 name,
@@ -174,13 +634,17 @@ impl Analyzer {
                     .as_ref()
                     .map(|ann| self.type_from_annotation(ann, span.clone()));
 
-                if let Some(existing) = self.current_scope().get(name) {
-                    self.report(
-                        name_span.clone(),
-                        format!(
-                            "Redeclaration of constant '{name}' (previously declared at {}..{})",
-                            existing.declared_span.start, existing.declared_span.end
-                        ),
+                if let Some(previous) = self.current_declaration(name) {
+                    let previous = previous.name_span.clone().unwrap_or(previous.span.clone());
+                    self.diagnostics.push(
+                        Diagnostic::new(
+                            DiagnosticKind::Semantic,
+                            format!("Redeclaration of constant '{name}'"),
+                            name_span.clone(),
+                        )
+                        .with_code(codes::REDECLARATION)
+                        .with_label("redeclared here")
+                        .with_secondary(previous, format!("'{name}' first declared here")),
                     );
                     return;
                 }
@@ -199,6 +663,7 @@ impl Analyzer {
                 if let Some(declared) = declared_type {
                     if !Self::can_assign(&declared, &expr_type) {
                         self.report(
+                            codes::TYPE_MISMATCH,
                             initializer.span().clone(),
                             format!(
                                 "Type mismatch in initializer: expected {}, found {}",
@@ -212,7 +677,7 @@ impl Analyzer {
                     symbol.ty = expr_type;
                 }
 
-                self.current_scope_mut().insert(name.clone(), symbol);
+                self.declare(name, SymbolKind::Constant, Some(name_span), symbol);
             }
             Stmt::Expression { expression, .. } => {
                 self.check_expr(expression);
@@ -226,9 +691,10 @@ impl Analyzer {
 
                 match value {
                     Some(expr) => {
-                        let value_type = self.check_expr(expr);
+                        let value_type = self.check_expected(expr, &expected);
                         if !Self::can_assign(&expected, &value_type) {
                             self.report(
+                                codes::TYPE_MISMATCH,
                                 expr.span().clone(),
                                 format!(
                                     "Type mismatch in return: expected {}, found {}",
@@ -239,8 +705,9 @@ impl Analyzer {
                         }
                     }
                     None => {
-                        if expected != Type::Nil {
+                        if !Self::can_assign(&expected, &Type::Nil) {
                             self.report(
+                                codes::MISSING_RETURN_VALUE,
                                 span.clone(),
                                 format!(
                                     "Missing return value: expected {}",
@@ -255,6 +722,7 @@ impl Analyzer {
                 let value_type = self.check_expr(value);
                 if value_type != Type::Error && !value_type.is_unknown() {
                     self.report(
+                        codes::PANIC_NON_ERROR,
                         span.clone(),
                         format!(
                             "panic expects expression of type error, found {}",
@@ -267,11 +735,12 @@ impl Analyzer {
                 condition,
                 then_branch,
                 else_branch,
-                ..
+                span,
             } => {
                 let condition_type = self.check_expr(condition);
                 if condition_type != Type::Boolean && !condition_type.is_unknown() {
                     self.report(
+                        codes::NON_BOOLEAN_CONDITION,
                         condition.span().clone(),
                         format!(
                             "if condition must be boolean, found {}",
@@ -279,13 +748,13 @@ impl Analyzer {
                         ),
                     );
                 }
-                self.with_scope(|analyzer| {
+                self.with_scope(ScopeKind::Block, span, |analyzer| {
                     for stmt in then_branch {
                         analyzer.check_stmt(stmt);
                     }
                 });
                 if let Some(else_branch) = else_branch {
-                    self.with_scope(|analyzer| {
+                    self.with_scope(ScopeKind::Block, span, |analyzer| {
                         for stmt in else_branch {
                             analyzer.check_stmt(stmt);
                         }
@@ -293,11 +762,14 @@ impl Analyzer {
                 }
             }
             Stmt::While {
-                condition, body, ..
+                condition,
+                body,
+                span,
             } => {
                 let condition_type = self.check_expr(condition);
                 if condition_type != Type::Boolean && !condition_type.is_unknown() {
                     self.report(
+                        codes::NON_BOOLEAN_CONDITION,
                         condition.span().clone(),
                         format!(
                             "while condition must be boolean, found {}",
@@ -306,7 +778,7 @@ impl Analyzer {
                     );
                 }
                 self.loop_depth += 1;
-                self.with_scope(|analyzer| {
+                self.with_scope(ScopeKind::Loop, span, |analyzer| {
                     for stmt in body {
                         analyzer.check_stmt(stmt);
                     }
@@ -318,21 +790,23 @@ impl Analyzer {
                 variable,
                 iterable,
                 body,
-                span: _,
+                span,
             } => {
                 let _iterable_type = self.check_expr(iterable);
                 // TODO: Check that iterable is actually iterable
 
                 self.loop_depth += 1;
-                self.with_scope(|analyzer| {
+                self.with_scope(ScopeKind::Loop, span, |analyzer| {
                     let var_type = if let Some(type_ann) = type_annotation {
                         analyzer.type_from_annotation(type_ann, iterable.span().clone())
                     } else {
                         Type::Unknown("foreach_var".to_string())
                     };
 
-                    analyzer.current_scope_mut().insert(
-                        variable.clone(),
+                    analyzer.declare(
+                        variable,
+                        SymbolKind::LoopVariable,
+                        None,
                         Symbol {
                             ty: var_type,
                             is_final: true,
@@ -350,61 +824,623 @@ impl Analyzer {
             }
             Stmt::Break { span } => {
                 if self.loop_depth == 0 {
-                    self.report(span.clone(), "Break statement outside of loop".to_string());
+                    self.report(
+                        codes::LOOP_CONTROL_OUTSIDE_LOOP,
+                        span.clone(),
+                        "Break statement outside of loop".to_string(),
+                    );
                 }
             }
             Stmt::Continue { span } => {
                 if self.loop_depth == 0 {
                     self.report(
+                        codes::LOOP_CONTROL_OUTSIDE_LOOP,
                         span.clone(),
                         "Continue statement outside of loop".to_string(),
                     );
                 }
             }
             Stmt::Function {
-                name: _,
+                name,
                 name_span,
                 params,
                 return_type,
-                body,
                 ..
             } => {
-                let return_ty = return_type
+                let return_ty = match self.functions.get(name) {
+                    Some(info) if info.name_span == *name_span => {
+                        self.function_return_type(name).unwrap_or(Type::Nil)
+                    }
+                    _ => return_type
+                        .as_ref()
+                        .map(|ty| self.type_from_annotation(ty, name_span.clone()))
+                        .unwrap_or(Type::Nil),
+                };
+                let signature = Method {
+                    name: name.clone(),
+                    params: params
+                        .iter()
+                        .map(|(_, ty)| self.type_from_annotation(ty, name_span.clone()))
+                        .collect(),
+                    return_type: return_ty,
+                };
+                self.check_function(stmt, SymbolKind::Function, &signature, None);
+            }
+            Stmt::TypeDefinition {
+                name,
+                name_span,
+                type_desc,
+                span,
+                ..
+            } => {
+                // A second definition of the name was reported when collecting.
+                if !matches!(self.type_definitions.get(name), Some(first) if first.span == *span) {
+                    return;
+                }
+                let ty = self
+                    .resolve_named_type(name)
+                    .unwrap_or(Type::Unknown(name.clone()));
+                self.record_declaration(
+                    name,
+                    SymbolKind::Type,
+                    ty.clone(),
+                    span.clone(),
+                    Some(name_span),
+                );
+
+                match (type_desc, &ty) {
+                    (TypeDescriptor::Record { fields, .. }, Type::Record(record)) => {
+                        for field in fields {
+                            if let (Some(default), Some(declared)) =
+                                (&field.default, record.field(&field.name))
+                            {
+                                self.check_field_default(&field.name, default, &declared.ty);
+                            }
+                        }
+                    }
+                    (TypeDescriptor::Object { members, .. }, Type::Object(object)) => {
+                        for member in members {
+                            if let ObjectMember::Field(field) = member {
+                                if let (Some(default), Some(declared)) =
+                                    (&field.default, object.field(&field.name))
+                                {
+                                    self.check_field_default(&field.name, default, &declared.ty);
+                                }
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            Stmt::Class {
+                name,
+                name_span,
+                members,
+                span,
+                ..
+            } => {
+                // A second definition of the name was reported when collecting.
+                if !matches!(self.type_definitions.get(name), Some(first) if first.span == *span) {
+                    return;
+                }
+                let ty = self
+                    .resolve_named_type(name)
+                    .unwrap_or(Type::Unknown(name.clone()));
+                self.record_declaration(
+                    name,
+                    SymbolKind::Class,
+                    ty.clone(),
+                    span.clone(),
+                    Some(name_span),
+                );
+                self.check_members(members, &ty, span);
+            }
+            Stmt::Listener {
+                name,
+                name_span,
+                type_annotation,
+                initializer,
+                span,
+                ..
+            } => {
+                let declared_type = type_annotation
                     .as_ref()
-                    .map(|ty| self.type_from_annotation(ty, name_span.clone()))
-                    .unwrap_or(Type::Nil);
+                    .map(|ann| self.type_from_annotation(ann, span.clone()));
 
-                let previous = self.current_function.take();
-                self.current_function = Some(FunctionContext {
-                    return_type: return_ty.clone(),
-                });
+                if let Some(previous) = self.current_declaration(name) {
+                    let previous = previous.name_span.clone().unwrap_or(previous.span.clone());
+                    self.diagnostics.push(
+                        Diagnostic::new(
+                            DiagnosticKind::Semantic,
+                            format!("Redeclaration of listener '{name}'"),
+                            name_span.clone(),
+                        )
+                        .with_code(codes::REDECLARATION)
+                        .with_label("redeclared here")
+                        .with_secondary(previous, format!("'{name}' first declared here")),
+                    );
+                    return;
+                }
 
-                self.with_scope(|analyzer| {
-                    for (param_name, ty_name) in params {
-                        let param_type = analyzer.type_from_annotation(ty_name, name_span.clone());
-                        analyzer.current_scope_mut().insert(
-                            param_name.clone(),
-                            Symbol {
-                                ty: param_type,
-                                is_final: true,
-                                is_const: false,
-                                initialized: true,
-                                declared_span: name_span.clone(),
-                            },
-                        );
+                let ty = match declared_type {
+                    Some(declared) => {
+                        let expr_type = self.check_expected(initializer, &declared);
+                        if !Self::can_assign(&declared, &expr_type) && !expr_type.is_unknown() {
+                            self.report(
+                                codes::TYPE_MISMATCH,
+                                initializer.span().clone(),
+                                format!(
+                                    "Type mismatch in initializer: expected {}, found {}",
+                                    declared.description(),
+                                    expr_type.description()
+                                ),
+                            );
+                        }
+                        declared
                     }
-                    for stmt in body {
-                        analyzer.check_stmt(stmt);
+                    None => self.check_expr(initializer),
+                };
+                self.declare(
+                    name,
+                    SymbolKind::Listener,
+                    Some(name_span),
+                    Symbol {
+                        ty,
+                        is_final: true,
+                        is_const: false,
+                        initialized: true,
+                        declared_span: span.clone(),
+                    },
+                );
+            }
+            Stmt::Service {
+                qualifiers,
+                type_annotation,
+                listeners,
+                members,
+                span,
+                ..
+            } => {
+                if let Some(type_annotation) = type_annotation {
+                    self.type_from_annotation(type_annotation, span.clone());
+                }
+                for listener in listeners {
+                    self.check_expr(listener);
+                }
+                let mut ty = self.type_from_annotation(
+                    &class_type_descriptor(qualifiers, members),
+                    span.clone(),
+                );
+                if let Type::Object(object) = &mut ty {
+                    object.is_class = true;
+                }
+                self.check_members(members, &ty, span);
+            }
+            // Only reached within a service or class; see `check_members`.
+            Stmt::ResourceFunction { .. } => {}
+        }
+    }
+
+    /// Checks the field defaults and methods of a class or service, whose
+    /// object type is `ty`, in a scope opened by the definition at `span`.
+    fn check_members(&mut self, members: &[ClassMember], ty: &Type, span: &Span) {
+        let Type::Object(object) = ty else {
+            return;
+        };
+        self.check_included_methods(members, object);
+
+        self.with_scope(ScopeKind::Class, span, |analyzer| {
+            for member in members {
+                match member {
+                    ClassMember::Field(field) => {
+                        if let (Some(default), Some(declared)) =
+                            (&field.default, object.field(&field.name))
+                        {
+                            analyzer.check_field_default(&field.name, default, &declared.ty);
+                        }
+                    }
+                    ClassMember::Method(method @ Stmt::Function { name, .. }) => {
+                        // The signature was resolved with the class.
+                        let signature = match object.method(name) {
+                            Some(signature) => signature.clone(),
+                            None => continue,
+                        };
+                        analyzer.check_function(method, SymbolKind::Method, &signature, Some(ty));
                     }
+                    ClassMember::Method(
+                        method @ Stmt::ResourceFunction {
+                            accessor,
+                            accessor_span,
+                            path,
+                            params,
+                            return_type,
+                            ..
+                        },
+                    ) => {
+                        let mut param_types = Vec::new();
+                        for segment in path {
+                            match segment {
+                                PathSegment::Param { type_desc, .. } => param_types.push(
+                                    analyzer.type_from_annotation(type_desc, accessor_span.clone()),
+                                ),
+                                PathSegment::Rest { type_desc, .. } => {
+                                    let element = analyzer
+                                        .type_from_annotation(type_desc, accessor_span.clone());
+                                    param_types.push(Type::Array(Box::new(element)));
+                                }
+                                PathSegment::Name { .. } => {}
+                            }
+                        }
+                        for (_, param_type) in params {
+                            param_types.push(
+                                analyzer.type_from_annotation(param_type, accessor_span.clone()),
+                            );
+                        }
+                        let signature = Method {
+                            name: accessor.clone(),
+                            params: param_types,
+                            return_type: return_type
+                                .as_ref()
+                                .map(|ty| analyzer.type_from_annotation(ty, accessor_span.clone()))
+                                .unwrap_or(Type::Nil),
+                        };
+                        analyzer.check_function(method, SymbolKind::Method, &signature, Some(ty));
+                    }
+                    ClassMember::Method(_) | ClassMember::Inclusion { .. } => {}
+                }
+            }
+        });
+    }
+
+    /// Reports each method of a type included with `*T;` that the class or
+    /// service `object` does not define: the inclusion only copies its
+    /// signature, so nothing would run when it is called.
+    fn check_included_methods(&mut self, members: &[ClassMember], object: &ObjectType) {
+        for member in members {
+            let ClassMember::Inclusion { type_name, span } = member else {
+                continue;
+            };
+            // Other modules' types are unknown, and other kinds of type were
+            // reported with the class.
+            let Some(Type::Object(included)) = self.types.get(type_name).cloned() else {
+                continue;
+            };
+            for method in &included.methods {
+                let defined = members.iter().any(|member| {
+                    matches!(member, ClassMember::Method(Stmt::Function { name, .. }) if *name == method.name)
                 });
+                if defined {
+                    continue;
+                }
+                self.diagnostics.push(
+                    Diagnostic::new(
+                        DiagnosticKind::Semantic,
+                        format!(
+                            "{} does not define method '{}' of included type {type_name}",
+                            object.kind_description(),
+                            method.name
+                        ),
+                        span.clone(),
+                    )
+                    .with_code(codes::MISSING_METHOD)
+                    .with_label("method included here")
+                    .with_help(format!("add a method '{}' with a body", method.name)),
+                );
+            }
+        }
+    }
+
+    /// Checks the body of a function, method, or resource method with the
+    /// given resolved signature. A method's body also sees `self`, of type
+    /// `self_type`.
+    ///
+    /// A resource method's signature lists its path parameters, then its
+    /// other parameters.
+    fn check_function(
+        &mut self,
+        function: &Stmt,
+        kind: SymbolKind,
+        signature: &Method,
+        self_type: Option<&Type>,
+    ) {
+        let (param_names, body, span, name_span): (Vec<&str>, _, _, _) = match function {
+            Stmt::Function {
+                name,
+                name_span,
+                params,
+                body,
+                span,
+                ..
+            } => {
+                // Functions live in their own namespace (see `functions`),
+                // and methods in their class, so they are recorded without
+                // being bound in the scope.
+                self.record_declaration(
+                    name,
+                    kind,
+                    signature.return_type.clone(),
+                    name_span.clone(),
+                    Some(name_span),
+                );
+                let names = params.iter().map(|(name, _)| name.as_str()).collect();
+                (names, body, span, name_span)
+            }
+            // Resource methods are reached through their path, not a name,
+            // so only their parameters are recorded.
+            Stmt::ResourceFunction {
+                accessor_span,
+                path,
+                params,
+                body,
+                span,
+                ..
+            } => {
+                let names = path
+                    .iter()
+                    .filter_map(|segment| match segment {
+                        PathSegment::Param { name, .. } | PathSegment::Rest { name, .. } => {
+                            Some(name.as_str())
+                        }
+                        PathSegment::Name { .. } => None,
+                    })
+                    .chain(params.iter().map(|(name, _)| name.as_str()))
+                    .collect();
+                (names, body, span, accessor_span)
+            }
+            _ => return,
+        };
 
-                self.current_function = previous;
+        let previous = self.current_function.take();
+        self.current_function = Some(FunctionContext {
+            return_type: signature.return_type.clone(),
+            method: self_type.map(|ty| (signature.name.clone(), ty.clone())),
+        });
+
+        self.with_scope(ScopeKind::Function, span, |analyzer| {
+            if let Some(self_type) = self_type {
+                analyzer.declare(
+                    "self",
+                    SymbolKind::Parameter,
+                    None,
+                    Symbol {
+                        ty: self_type.clone(),
+                        is_final: true,
+                        is_const: false,
+                        initialized: true,
+                        declared_span: name_span.clone(),
+                    },
+                );
+            }
+            for (param_name, param_type) in param_names.iter().zip(&signature.params) {
+                analyzer.declare(
+                    param_name,
+                    SymbolKind::Parameter,
+                    None,
+                    Symbol {
+                        ty: param_type.clone(),
+                        is_final: true,
+                        is_const: false,
+                        initialized: true,
+                        declared_span: name_span.clone(),
+                    },
+                );
             }
+            for stmt in body {
+                analyzer.check_stmt(stmt);
+            }
+        });
+
+        self.current_function = previous;
+    }
+
+    /// Checks the default value of a record or object field of type
+    /// `declared`.
+    fn check_field_default(&mut self, name: &str, default: &Expr, declared: &Type) {
+        let default_type = self.check_expected(default, declared);
+        if !Self::can_assign(declared, &default_type) && !default_type.is_unknown() {
+            self.report(
+                codes::TYPE_MISMATCH,
+                default.span().clone(),
+                format!(
+                    "Type mismatch in default of field '{name}': expected {}, found {}",
+                    declared.description(),
+                    default_type.description()
+                ),
+            );
         }
     }
 
-    /// Evaluates an expression and returns its inferred static type.
+    /// Evaluates an expression and returns its inferred static type, which is
+    /// also recorded in the model.
     fn check_expr(&mut self, expr: &Expr) -> Type {
+        let ty = self.infer_expr(expr);
+        self.model
+            .expr_types
+            .insert(expr.span().clone(), ty.clone());
+        ty
+    }
+
+    /// Checks an expression whose value must have type `expected`, as in an
+    /// initializer or `return`. Mapping constructors are checked as values of
+    /// an expected record type, and take that type; `new` without a class
+    /// name constructs the expected class, or a class of another module.
+    /// Against a union, a constructor takes the member it can construct.
+    fn check_expected(&mut self, expr: &Expr, expected: &Type) -> Type {
+        match (expr, expected) {
+            (
+                Expr::MapLiteral { .. } | Expr::ArrayLiteral { .. } | Expr::New { .. },
+                Type::Union(members),
+            ) => {
+                let member = members.iter().find(|member| match (expr, member) {
+                    (Expr::MapLiteral { .. }, Type::Record(_)) => true,
+                    (Expr::ArrayLiteral { .. }, Type::Array(_)) => true,
+                    (
+                        Expr::New {
+                            class_name: None, ..
+                        },
+                        Type::Object(object),
+                    ) => object.is_class,
+                    _ => false,
+                });
+                match member {
+                    Some(member) => self.check_expected(expr, member),
+                    None => self.check_expr(expr),
+                }
+            }
+            (Expr::MapLiteral { entries, span }, Type::Record(record)) => {
+                self.check_record_value(entries, span, record);
+                self.model.expr_types.insert(span.clone(), expected.clone());
+                expected.clone()
+            }
+            (Expr::ArrayLiteral { elements, span }, Type::Array(element_type))
+                if matches!(**element_type, Type::Record(_)) =>
+            {
+                for element in elements {
+                    let actual = self.check_expected(element, element_type);
+                    if !Self::can_assign(element_type, &actual) && !actual.is_unknown() {
+                        self.report(
+                            codes::TYPE_MISMATCH,
+                            element.span().clone(),
+                            format!(
+                                "Type mismatch in array element: expected {}, found {}",
+                                element_type.description(),
+                                actual.description()
+                            ),
+                        );
+                    }
+                }
+                self.model.expr_types.insert(span.clone(), expected.clone());
+                expected.clone()
+            }
+            (Expr::MapLiteral { entries, span }, Type::Map(value_type))
+                if matches!(**value_type, Type::Record(_)) =>
+            {
+                for MapEntry { value, .. } in entries {
+                    let actual = self.check_expected(value, value_type);
+                    if !Self::can_assign(value_type, &actual) && !actual.is_unknown() {
+                        self.report(
+                            codes::TYPE_MISMATCH,
+                            value.span().clone(),
+                            format!(
+                                "Type mismatch in map value: expected {}, found {}",
+                                value_type.description(),
+                                actual.description()
+                            ),
+                        );
+                    }
+                }
+                self.model.expr_types.insert(span.clone(), expected.clone());
+                expected.clone()
+            }
+            (
+                Expr::New {
+                    class_name: None,
+                    arguments,
+                    span,
+                },
+                expected,
+            ) if matches!(expected, Type::Object(object) if object.is_class)
+                || expected.is_unknown() =>
+            {
+                match expected {
+                    Type::Object(object) => self.check_init_arguments(object, arguments, span),
+                    _ => {
+                        for argument in arguments {
+                            self.check_expr(argument);
+                        }
+                    }
+                }
+                self.model.expr_types.insert(span.clone(), expected.clone());
+                expected.clone()
+            }
+            _ => self.check_expr(expr),
+        }
+    }
+
+    /// Checks the fields of a mapping constructor against `record`: each
+    /// field must be declared (or admitted by the rest type) and have its
+    /// type, and every required field must be given.
+    fn check_record_value(&mut self, entries: &[MapEntry], span: &Span, record: &RecordType) {
+        for MapEntry {
+            key,
+            key_span,
+            value,
+        } in entries
+        {
+            let expected = match record.field(key) {
+                Some(field) => Some(field.ty.clone()),
+                None => record.rest_type(),
+            };
+            let Some(expected) = expected else {
+                self.check_expr(value);
+                let mut diagnostic = Diagnostic::new(
+                    DiagnosticKind::Semantic,
+                    format!("Record type {} has no field '{key}'", record.description()),
+                    key_span.clone(),
+                )
+                .with_code(codes::UNKNOWN_FIELD);
+                let names = record.fields.iter().map(|field| &field.name);
+                if let Some(similar) = similar_name(key, names) {
+                    diagnostic = diagnostic
+                        .with_help(format!("a field with a similar name exists: '{similar}'"));
+                }
+                self.diagnostics.push(diagnostic);
+                continue;
+            };
+            let actual = self.check_expected(value, &expected);
+            if !Self::can_assign(&expected, &actual)
+                && !actual.is_unknown()
+                && !expected.is_unknown()
+            {
+                self.diagnostics.push(
+                    Diagnostic::new(
+                        DiagnosticKind::Semantic,
+                        format!(
+                            "Type mismatch in field '{key}': expected {}, found {}",
+                            expected.description(),
+                            actual.description()
+                        ),
+                        key_span.clone(),
+                    )
+                    .with_code(codes::TYPE_MISMATCH)
+                    .with_secondary(
+                        value.span().clone(),
+                        format!("this value is {}", actual.description()),
+                    ),
+                );
+            }
+        }
+
+        let missing: Vec<&str> = record
+            .fields
+            .iter()
+            .filter(|field| field.required && !entries.iter().any(|entry| entry.key == field.name))
+            .map(|field| field.name.as_str())
+            .collect();
+        if !missing.is_empty() {
+            let list = missing
+                .iter()
+                .map(|name| format!("'{name}'"))
+                .collect::<Vec<_>>()
+                .join(", ");
+            let noun = if missing.len() == 1 {
+                "field"
+            } else {
+                "fields"
+            };
+            self.report(
+                codes::MISSING_FIELD,
+                span.clone(),
+                format!(
+                    "Missing required {noun} {list} in value of record type {}",
+                    record.description()
+                ),
+            );
+        }
+    }
+
+    /// Works out the static type of an expression, checking its operands.
+    fn infer_expr(&mut self, expr: &Expr) -> Type {
         match expr {
             Expr::Literal { value, .. } => self.type_from_literal(value),
             Expr::Variable { name, span } => self.lookup_variable(name, span.clone()),
@@ -417,12 +1453,86 @@ impl Analyzer {
                 span,
             } => self.check_binary(left, op, right, span.clone()),
             Expr::Assign { name, value, span } => {
-                let rhs_type = self.check_expr(value);
+                let rhs_type = match self.lookup_symbol(name) {
+                    Some(id) => {
+                        let target_type = self.symbol_states[id.0].ty.clone();
+                        self.check_expected(value, &target_type)
+                    }
+                    None => self.check_expr(value),
+                };
+                // The target name starts the assignment expression.
+                let name_span = span.start..span.start + name.len();
+                if let Some(id) = self.lookup_symbol(name) {
+                    self.model.references.insert(name_span, id);
+                }
                 self.assign_variable(name, value.span().clone(), span.clone(), rhs_type)
             }
+            Expr::FieldAssign {
+                object,
+                field,
+                field_span,
+                op,
+                value,
+                ..
+            } => {
+                let obj_type = self.check_expr(object);
+                let field_type = self.field_type(&obj_type, field, field_span.clone());
+                if let Type::Object(object_type) = &obj_type {
+                    if let Some(declared) = object_type.field(field) {
+                        if declared.is_final && !self.initializing(object_type, object) {
+                            self.report_final_field(object_type, declared, expr.span().clone());
+                        }
+                    }
+                }
+                let value_type = match op {
+                    None => self.check_expected(value, &field_type),
+                    Some(op) => {
+                        let value_type = self.check_expr(value);
+                        match self.numeric_result(&field_type, &value_type, false) {
+                            Some(result) => result,
+                            None if field_type.is_unknown() || value_type.is_unknown() => {
+                                Type::Unknown("compound_assign".into())
+                            }
+                            None => {
+                                self.report(
+                                    codes::INVALID_OPERAND,
+                                    expr.span().clone(),
+                                    format!(
+                                        "Operator {:?} requires numeric operands, found {} and {}",
+                                        op,
+                                        field_type.description(),
+                                        value_type.description()
+                                    ),
+                                );
+                                Type::Unknown("compound_assign".into())
+                            }
+                        }
+                    }
+                };
+                if !Self::can_assign(&field_type, &value_type)
+                    && !field_type.is_unknown()
+                    && !value_type.is_unknown()
+                {
+                    self.report(
+                        codes::TYPE_MISMATCH,
+                        value.span().clone(),
+                        format!(
+                            "Type mismatch in assignment to field '{field}': expected {}, found {}",
+                            field_type.description(),
+                            value_type.description()
+                        ),
+                    );
+                }
+                field_type
+            }
             Expr::Call {
                 callee, arguments, ..
             } => self.check_call(callee, arguments),
+            Expr::New {
+                class_name,
+                arguments,
+                span,
+            } => self.check_new(class_name.as_deref(), arguments, span.clone()),
             Expr::MemberAccess { object, member, .. } => {
                 let obj_type = self.check_expr(object);
                 self.check_expr(member);
@@ -431,9 +1541,21 @@ impl Analyzer {
                 match obj_type {
                     Type::Array(elem_type) => *elem_type,
                     Type::Map(val_type) => *val_type,
+                    Type::Record(record) => match &**member {
+                        Expr::Literal {
+                            value: Literal::String(key),
+                            ..
+                        } => record
+                            .field(key)
+                            .map(|field| field.ty.clone())
+                            .or_else(|| record.rest_type())
+                            .unwrap_or(Type::Nil),
+                        _ => Type::Unknown("member_access".to_string()),
+                    },
                     Type::Unknown(_) => Type::Unknown("member_access".to_string()),
                     _ => {
                         self.report(
+                            codes::INVALID_INDEX,
                             object.span().clone(),
                             format!("Cannot index type {}", obj_type.description()),
                         );
@@ -441,9 +1563,19 @@ impl Analyzer {
                     }
                 }
             }
+            Expr::FieldAccess {
+                object,
+                field,
+                field_span,
+                ..
+            } => {
+                let obj_type = self.check_expr(object);
+                self.field_type(&obj_type, field, field_span.clone())
+            }
             Expr::MethodCall {
                 object,
                 method,
+                method_span,
                 arguments,
                 ..
             } => {
@@ -472,6 +1604,26 @@ impl Analyzer {
                     (Type::Map(val_type), "values") => Type::Array(val_type),
                     (Type::Map(_), "length") => Type::Int,
 
+                    (Type::Object(object), name) => match object.method(name) {
+                        Some(declared) => declared.return_type.clone(),
+                        None => {
+                            let mut diagnostic = Diagnostic::new(
+                                DiagnosticKind::Semantic,
+                                format!("{} has no method '{name}'", object.kind_description()),
+                                method_span.clone(),
+                            )
+                            .with_code(codes::UNKNOWN_METHOD);
+                            let names = object.methods.iter().map(|method| &method.name);
+                            if let Some(similar) = similar_name(name, names) {
+                                diagnostic = diagnostic.with_help(format!(
+                                    "a method with a similar name exists: '{similar}'"
+                                ));
+                            }
+                            self.diagnostics.push(diagnostic);
+                            Type::Unknown("method_call".to_string())
+                        }
+                    },
+
                     _ => Type::Unknown("method_call".to_string()),
                 }
             }
@@ -488,6 +1640,7 @@ impl Analyzer {
                     let elem_type = self.check_expr(elem);
                     if !Self::can_assign(&first_type, &elem_type) && !elem_type.is_unknown() {
                         self.report(
+                            codes::INCOMPATIBLE_ELEMENTS,
                             elem.span().clone(),
                             format!(
                                 "Array elements must have compatible types, expected {}, found {}",
@@ -506,13 +1659,14 @@ impl Analyzer {
                 }
 
                 // Infer type from first value
-                let first_type = self.check_expr(&entries[0].1);
+                let first_type = self.check_expr(&entries[0].value);
 
                 // Check all values have compatible types
-                for (_key, value) in &entries[1..] {
+                for MapEntry { value, .. } in &entries[1..] {
                     let val_type = self.check_expr(value);
                     if !Self::can_assign(&first_type, &val_type) && !val_type.is_unknown() {
                         self.report(
+                            codes::INCOMPATIBLE_ELEMENTS,
                             value.span().clone(),
                             format!(
                                 "Map values must have compatible types, expected {}, found {}",
@@ -534,6 +1688,7 @@ impl Analyzer {
                 let cond_type = self.check_expr(condition);
                 if cond_type != Type::Boolean && !cond_type.is_unknown() {
                     self.report(
+                        codes::NON_BOOLEAN_CONDITION,
                         condition.span().clone(),
                         format!(
                             "Ternary condition must be boolean, found {}",
@@ -577,6 +1732,148 @@ impl Analyzer {
         }
     }
 
+    /// Whether the current function is a method of the class `object`, and
+    /// so may use its private fields.
+    fn in_methods_of(&self, object: &ObjectType) -> bool {
+        matches!(
+            &self.current_function,
+            Some(FunctionContext {
+                method: Some((_, Type::Object(own))),
+                ..
+            }) if object.is_class && own.is_class && own.name == object.name
+        )
+    }
+
+    /// Whether `target` is `self` in the `init` method of the class
+    /// `object`, where its final fields are set.
+    fn initializing(&self, object: &ObjectType, target: &Expr) -> bool {
+        let in_init = matches!(
+            &self.current_function,
+            Some(FunctionContext {
+                method: Some((name, _)),
+                ..
+            }) if name == "init"
+        );
+        in_init
+            && self.in_methods_of(object)
+            && matches!(target, Expr::Variable { name, .. } if name == "self")
+    }
+
+    /// Reports an assignment, at `span`, to a final field or a field of a
+    /// readonly object outside its `init` method.
+    fn report_final_field(&mut self, object: &ObjectType, field: &Field, span: Span) {
+        let (message, label) = if object.is_readonly {
+            let kind = if object.is_class {
+                "class"
+            } else {
+                "object type"
+            };
+            (
+                format!(
+                    "Cannot assign to field '{}' of readonly {kind} {}",
+                    field.name,
+                    object.description()
+                ),
+                "field declared here",
+            )
+        } else {
+            (
+                format!("Cannot assign to final field '{}'", field.name),
+                "declared final here",
+            )
+        };
+        let mut diagnostic = Diagnostic::new(DiagnosticKind::Semantic, message, span)
+            .with_code(codes::ASSIGN_TO_IMMUTABLE)
+            .with_help("such fields can only be set through `self` in `init`");
+        if let Some(name_span) = &field.name_span {
+            diagnostic = diagnostic.with_secondary(name_span.clone(), label);
+        }
+        self.diagnostics.push(diagnostic);
+    }
+
+    /// The type of `.field` on a value of type `object`, reporting fields the
+    /// type does not have.
+    fn field_type(&mut self, object: &Type, field: &str, field_span: Span) -> Type {
+        match object {
+            Type::Record(record) => {
+                if let Some(declared) = record.field(field) {
+                    return declared.ty.clone();
+                }
+                let mut diagnostic = Diagnostic::new(
+                    DiagnosticKind::Semantic,
+                    format!(
+                        "Record type {} has no field '{field}'",
+                        record.description()
+                    ),
+                    field_span,
+                )
+                .with_code(codes::UNKNOWN_FIELD);
+                let names = record.fields.iter().map(|field| &field.name);
+                if let Some(similar) = similar_name(field, names) {
+                    diagnostic = diagnostic
+                        .with_help(format!("a field with a similar name exists: '{similar}'"));
+                } else if record.rest_type().is_some() {
+                    diagnostic = diagnostic.with_help(format!(
+                        "use member access, `[\"{field}\"]`, for fields the type does not declare"
+                    ));
+                }
+                self.diagnostics.push(diagnostic);
+                Type::Unknown("field_access".to_string())
+            }
+            Type::Object(object) => {
+                if let Some(declared) = object.field(field) {
+                    if declared.is_private && !self.in_methods_of(object) {
+                        let mut diagnostic = Diagnostic::new(
+                            DiagnosticKind::Semantic,
+                            format!("Field '{field}' of {} is private", object.description()),
+                            field_span,
+                        )
+                        .with_code(codes::PRIVATE_FIELD)
+                        .with_help(format!(
+                            "only methods of {} can use it",
+                            object.description()
+                        ));
+                        if let Some(name_span) = &declared.name_span {
+                            diagnostic = diagnostic
+                                .with_secondary(name_span.clone(), "declared private here");
+                        }
+                        self.diagnostics.push(diagnostic);
+                    }
+                    return declared.ty.clone();
+                }
+                let mut diagnostic = Diagnostic::new(
+                    DiagnosticKind::Semantic,
+                    format!("{} has no field '{field}'", object.kind_description()),
+                    field_span,
+                )
+                .with_code(codes::UNKNOWN_FIELD);
+                let names = object.fields.iter().map(|field| &field.name);
+                if let Some(similar) = similar_name(field, names) {
+                    diagnostic = diagnostic
+                        .with_help(format!("a field with a similar name exists: '{similar}'"));
+                } else if object.method(field).is_some() {
+                    diagnostic =
+                        diagnostic.with_help(format!("'{field}' is a method; call it with `()`"));
+                }
+                self.diagnostics.push(diagnostic);
+                Type::Unknown("field_access".to_string())
+            }
+            Type::Map(val_type) => (**val_type).clone(),
+            Type::Unknown(_) => Type::Unknown("field_access".to_string()),
+            _ => {
+                self.report(
+                    codes::UNKNOWN_FIELD,
+                    field_span,
+                    format!(
+                        "Cannot access field '{field}' of type {}",
+                        object.description()
+                    ),
+                );
+                Type::Unknown("field_access".to_string())
+            }
+        }
+    }
+
     /// Enforces the operand rules for unary expressions.
     fn check_unary(&mut self, op: &UnaryOp, operand: &Expr, span: Span) -> Type {
         let operand_type = self.check_expr(operand);
@@ -584,6 +1881,7 @@ impl Analyzer {
             UnaryOp::Bang => {
                 if operand_type != Type::Boolean && !operand_type.is_unknown() {
                     self.report(
+                        codes::INVALID_OPERAND,
                         span,
                         format!(
                             "Unary '!' expects boolean operand, found {}",
@@ -599,6 +1897,7 @@ impl Analyzer {
                 } else {
                     if !operand_type.is_unknown() {
                         self.report(
+                            codes::INVALID_OPERAND,
                             span,
                             format!(
                                 "Unary '-'/'+' expects numeric operand, found {}",
@@ -612,6 +1911,7 @@ impl Analyzer {
             UnaryOp::BitwiseNot => {
                 if operand_type != Type::Int && !operand_type.is_unknown() {
                     self.report(
+                        codes::INVALID_OPERAND,
                         span,
                         format!(
                             "Bitwise NOT expects integer operand, found {}",
@@ -621,6 +1921,31 @@ impl Analyzer {
                 }
                 Type::Int
             }
+            UnaryOp::Check => {
+                let without_error = self
+                    .current_function
+                    .as_ref()
+                    .filter(|function| !function.return_type.may_be_error());
+                if let Some(function) = without_error {
+                    let message = format!(
+                        "'check' in a function that cannot return an error: its return type is {}",
+                        function.return_type.description()
+                    );
+                    self.diagnostics.push(
+                        Diagnostic::new(
+                            DiagnosticKind::Semantic,
+                            message,
+                            span.start..span.start + "check".len(),
+                        )
+                        .with_code(codes::CHECK_WITHOUT_ERROR_RETURN)
+                        .with_help(
+                            "add `|error` to the return type, or use `checkpanic` to panic on an error",
+                        ),
+                    );
+                }
+                operand_type.checked()
+            }
+            UnaryOp::CheckPanic => operand_type.checked(),
         }
     }
 
@@ -639,6 +1964,7 @@ impl Analyzer {
                     result
                 } else {
                     self.report(
+                        codes::INVALID_OPERAND,
                         span,
                         format!(
                             "Operator {:?} requires numeric operands, found {} and {}",
@@ -655,6 +1981,7 @@ impl Analyzer {
                     result
                 } else {
                     self.report(
+                        codes::INVALID_OPERAND,
                         span,
                         format!(
                             "Operator '/' requires numeric operands, found {} and {}",
@@ -673,6 +2000,7 @@ impl Analyzer {
                     Type::Boolean
                 } else {
                     self.report(
+                        codes::INVALID_OPERAND,
                         span,
                         format!(
                             "Equality comparison requires matching operand types, found {} and {}",
@@ -691,6 +2019,7 @@ impl Analyzer {
                     Type::Boolean
                 } else {
                     self.report(
+                        codes::INVALID_OPERAND,
                         span,
                         format!(
                             "Ordered comparison requires numeric operands, found {} and {}",
@@ -710,6 +2039,7 @@ impl Analyzer {
                     Type::Boolean
                 } else {
                     self.report(
+                        codes::INVALID_OPERAND,
                         span,
                         format!(
                             "Logical operator requires boolean operands, found {} and {}",
@@ -725,6 +2055,7 @@ impl Analyzer {
                     Type::Int
                 } else {
                     self.report(
+                        codes::INVALID_OPERAND,
                         span,
                         format!(
                             "Bitwise operator requires integer operands, found {} and {}",
@@ -740,6 +2071,7 @@ impl Analyzer {
                     Type::Int
                 } else {
                     self.report(
+                        codes::INVALID_OPERAND,
                         span,
                         format!(
                             "Shift operator requires integer operands, found {} and {}",
@@ -769,36 +2101,62 @@ impl Analyzer {
         span: Span,
         rhs_type: Type,
     ) -> Type {
-        if let Some(symbol) = self.lookup_symbol_mut(name) {
+        if let Some(id) = self.lookup_symbol(name) {
+            let declaration = &self.model.symbols[id.0];
+            let declared_at = declaration
+                .name_span
+                .clone()
+                .unwrap_or(declaration.span.clone());
+            let symbol = &mut self.symbol_states[id.0];
             let symbol_type = symbol.ty.clone();
             let issue = if symbol.is_const {
-                Some((span.clone(), format!("Cannot assign to constant '{name}'")))
+                Some(
+                    Diagnostic::new(
+                        DiagnosticKind::Semantic,
+                        format!("Cannot assign to constant '{name}'"),
+                        span.clone(),
+                    )
+                    .with_code(codes::ASSIGN_TO_IMMUTABLE)
+                    .with_secondary(declared_at, "declared as a constant here"),
+                )
             } else if symbol.is_final && symbol.initialized {
-                Some((
-                    span.clone(),
-                    format!("Cannot assign to final variable '{name}'"),
-                ))
+                Some(
+                    Diagnostic::new(
+                        DiagnosticKind::Semantic,
+                        format!("Cannot assign to final variable '{name}'"),
+                        span.clone(),
+                    )
+                    .with_code(codes::ASSIGN_TO_IMMUTABLE)
+                    .with_secondary(declared_at, "declared final here")
+                    .with_help(format!(
+                        "remove `final` from the declaration of '{name}' to allow assignment"
+                    )),
+                )
             } else if !Self::can_assign(&symbol_type, &rhs_type) {
-                Some((
-                    value_span.clone(),
-                    format!(
-                        "Type mismatch in assignment: expected {}, found {}",
-                        symbol_type.description(),
-                        rhs_type.description()
-                    ),
-                ))
+                Some(
+                    Diagnostic::new(
+                        DiagnosticKind::Semantic,
+                        format!(
+                            "Type mismatch in assignment: expected {}, found {}",
+                            symbol_type.description(),
+                            rhs_type.description()
+                        ),
+                        value_span.clone(),
+                    )
+                    .with_code(codes::TYPE_MISMATCH),
+                )
             } else {
                 symbol.initialized = true;
                 None
             };
 
-            if let Some((issue_span, message)) = issue {
-                self.report(issue_span, message);
+            if let Some(diagnostic) = issue {
+                self.diagnostics.push(diagnostic);
             }
 
             symbol_type
         } else {
-            self.report(span, format!("Use of undeclared variable '{name}'"));
+            self.report_undeclared(name, span);
             Type::Unknown(name.to_string())
         }
     }
@@ -841,59 +2199,157 @@ impl Analyzer {
 
     /// Determines whether two operands can participate in an equality comparison.
     fn can_compare(&self, left: &Type, right: &Type) -> bool {
-        matches!(
-            (left, right),
-            (Type::Int, Type::Int)
-                | (Type::Float, Type::Float)
-                | (Type::Boolean, Type::Boolean)
-                | (Type::String, Type::String)
-                | (Type::Int, Type::Float)
-                | (Type::Float, Type::Int)
-        )
+        match (left, right) {
+            (Type::Union(members), other) | (other, Type::Union(members)) => members
+                .iter()
+                .any(|member| member == other || self.can_compare(member, other)),
+            _ => matches!(
+                (left, right),
+                (Type::Int, Type::Int)
+                    | (Type::Float, Type::Float)
+                    | (Type::Boolean, Type::Boolean)
+                    | (Type::String, Type::String)
+                    | (Type::Int, Type::Float)
+                    | (Type::Float, Type::Int)
+            ),
+        }
     }
 
     /// Resolves an identifier reference, emitting diagnostics when undefined or uninitialised.
     fn lookup_variable(&mut self, name: &str, span: Span) -> Type {
-        if let Some(symbol) = self.lookup_symbol(name).cloned() {
+        if let Some(id) = self.lookup_symbol(name) {
+            self.model.references.insert(span.clone(), id);
+            let symbol = self.symbol_states[id.0].clone();
             if !symbol.initialized {
                 self.report(
+                    codes::UNINITIALIZED_USE,
                     span.clone(),
                     format!("Variable '{name}' may be used before it is initialised"),
                 );
             }
             symbol.ty
         } else {
-            self.report(span, format!("Use of undeclared variable '{name}'"));
+            self.report_undeclared(name, span);
             Type::Unknown(name.to_string())
         }
     }
 
-    /// Searches the scope stack for a symbol without taking ownership.
-    fn lookup_symbol(&self, name: &str) -> Option<&Symbol> {
-        for scope in self.scopes.iter().rev() {
-            if let Some(symbol) = scope.get(name) {
-                return Some(symbol);
+    /// Searches the scope stack, innermost first, for the symbol bound to `name`.
+    fn lookup_symbol(&self, name: &str) -> Option<SymbolId> {
+        self.scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.get(name).copied())
+    }
+
+    /// Returns whether the analyzer permits assigning `value` into `target`.
+    fn can_assign(target: &Type, value: &Type) -> bool {
+        if target == value {
+            return true;
+        }
+        match (target, value) {
+            (Type::Float, Type::Int) => true,
+            (Type::Object(target), Type::Object(value)) => !target.is_class && target.admits(value),
+            (_, Type::Union(values)) => values.iter().all(|value| Self::can_assign(target, value)),
+            (Type::Union(members), _) => {
+                members.iter().any(|member| Self::can_assign(member, value))
             }
+            _ => false,
         }
-        None
     }
 
-    /// Finds a mutable reference to a symbol in the scope stack, if present.
-    fn lookup_symbol_mut(&mut self, name: &str) -> Option<&mut Symbol> {
-        for scope in self.scopes.iter_mut().rev() {
-            if let Some(symbol) = scope.get_mut(name) {
-                return Some(symbol);
+    /// Checks a `new` expression naming `class_name`, returning the class.
+    /// Without a name, the class comes from the expected type; see
+    /// [`Self::check_expected`].
+    fn check_new(&mut self, class_name: Option<&str>, arguments: &[Expr], span: Span) -> Type {
+        let class = match class_name {
+            // A class of another module.
+            Some(name) if !name.contains(':') => self.resolve_named_type(name),
+            _ => None,
+        };
+        match &class {
+            Some(Type::Object(object)) if object.is_class => {
+                self.check_init_arguments(object, arguments, &span);
+            }
+            _ => {
+                for argument in arguments {
+                    self.check_expr(argument);
+                }
+            }
+        }
+        let Some(name) = class_name else {
+            return Type::Unknown("new".to_string());
+        };
+        if name.contains(':') {
+            return Type::Unknown(name.to_string());
+        }
+        match class {
+            Some(Type::Object(object)) if object.is_class => Type::Object(object),
+            Some(ty) if ty.is_unknown() => ty,
+            Some(ty) => {
+                self.report(
+                    codes::UNKNOWN_TYPE,
+                    span,
+                    format!("Cannot construct type {ty} with 'new': it is not a class"),
+                );
+                Type::Unknown(name.to_string())
+            }
+            None => {
+                let mut diagnostic = Diagnostic::new(
+                    DiagnosticKind::Semantic,
+                    format!("Unknown class '{name}'"),
+                    span,
+                )
+                .with_code(codes::UNKNOWN_TYPE);
+                if let Some(similar) = similar_name(name, self.type_definitions.keys()) {
+                    diagnostic = diagnostic
+                        .with_help(format!("a type with a similar name exists: '{similar}'"));
+                }
+                self.diagnostics.push(diagnostic);
+                Type::Unknown(name.to_string())
             }
         }
-        None
     }
 
-    /// Returns whether the analyzer permits assigning `value` into `target`.
-    fn can_assign(target: &Type, value: &Type) -> bool {
-        if target == value {
-            return true;
+    /// Checks the arguments of `new` at `span` against the parameters of
+    /// the `init` method of `class`; a class without one takes none.
+    fn check_init_arguments(&mut self, class: &ObjectType, arguments: &[Expr], span: &Span) {
+        let params = class
+            .method("init")
+            .map(|init| init.params.clone())
+            .unwrap_or_default();
+        if arguments.len() != params.len() {
+            self.report(
+                codes::ARGUMENT_COUNT,
+                span.clone(),
+                format!(
+                    "'init' of {} takes {} argument{}, found {}",
+                    class.description(),
+                    params.len(),
+                    if params.len() == 1 { "" } else { "s" },
+                    arguments.len()
+                ),
+            );
+        }
+        for (index, argument) in arguments.iter().enumerate() {
+            let Some(param) = params.get(index) else {
+                self.check_expr(argument);
+                continue;
+            };
+            let actual = self.check_expected(argument, param);
+            if !Self::can_assign(param, &actual) && !actual.is_unknown() && !param.is_unknown() {
+                self.report(
+                    codes::TYPE_MISMATCH,
+                    argument.span().clone(),
+                    format!(
+                        "Type mismatch in argument {} of 'init': expected {}, found {}",
+                        index + 1,
+                        param.description(),
+                        actual.description()
+                    ),
+                );
+            }
         }
-        matches!((target, value), (Type::Float, Type::Int))
     }
 
     /// Validates call expressions and, for now, records the callee type as unknown.
@@ -922,12 +2378,22 @@ impl Analyzer {
                     }
                 }
 
-                if !self.functions.contains(name) {
-                    self.report(
-                        callee_span.clone(),
-                        format!("Call to unknown function '{name}'"),
-                    );
+                if let Some(return_type) = self.function_return_type(name) {
+                    self.pending_calls.push((name.clone(), callee_span.clone()));
+                    return return_type;
+                }
+                let mut diagnostic = Diagnostic::new(
+                    DiagnosticKind::Semantic,
+                    format!("Call to unknown function '{name}'"),
+                    callee_span.clone(),
+                )
+                .with_code(codes::UNKNOWN_FUNCTION);
+                if let Some(similar) = similar_name(name, self.functions.keys()) {
+                    diagnostic = diagnostic.with_help(format!(
+                        "a function with a similar name exists: '{similar}'"
+                    ));
                 }
+                self.diagnostics.push(diagnostic);
                 Type::Unknown(format!("call:{name}"))
             }
             _ => {
@@ -950,13 +2416,18 @@ impl Analyzer {
                 "string" => Type::String,
                 "decimal" => Type::Float, // Treat decimal as float for now
                 "byte" => Type::Int,      // Treat byte as int for now
-                "anydata" => Type::Unknown("anydata".to_string()),
+                "anydata" | "any" | "json" => Type::Unknown(name.clone()),
                 "error" => Type::Error,
                 "nil" => Type::Nil,
-                other => {
-                    self.report(span, format!("Unknown type '{other}'"));
-                    Type::Unknown(other.to_string())
-                }
+                // A type of another module.
+                other if other.contains(':') => Type::Unknown(other.to_string()),
+                other => match self.resolve_named_type(other) {
+                    Some(ty) => ty,
+                    None => {
+                        self.report(codes::UNKNOWN_TYPE, span, format!("Unknown type '{other}'"));
+                        Type::Unknown(other.to_string())
+                    }
+                },
             },
             TypeDescriptor::Array { element_type, .. } => {
                 let elem_ty = self.type_from_annotation(element_type, span);
@@ -966,39 +2437,300 @@ impl Analyzer {
                 let val_ty = self.type_from_annotation(value_type, span);
                 Type::Map(Box::new(val_ty))
             }
-            TypeDescriptor::Optional(inner) => self.type_from_annotation(inner, span),
-            TypeDescriptor::Union(types) => {
-                if !types.is_empty() {
-                    self.type_from_annotation(&types[0], span)
-                } else {
-                    Type::Unknown("union".to_string())
+            TypeDescriptor::Optional(inner) => {
+                Type::union(vec![self.type_from_annotation(inner, span), Type::Nil])
+            }
+            TypeDescriptor::Union(types) => Type::union(
+                types
+                    .iter()
+                    .map(|ty| self.type_from_annotation(ty, span.clone()))
+                    .collect(),
+            ),
+            TypeDescriptor::Record {
+                fields,
+                closed,
+                rest,
+            } => {
+                let rest = rest
+                    .as_ref()
+                    .map(|rest| self.type_from_annotation(rest, span));
+                Type::Record(Box::new(RecordType {
+                    name: None,
+                    fields: self.record_fields(fields),
+                    closed: *closed,
+                    rest,
+                }))
+            }
+            TypeDescriptor::Object {
+                qualifiers,
+                members,
+            } => Type::Object(Box::new(self.object_type(qualifiers, members))),
+        }
+    }
+
+    /// Converts the members of an object type descriptor, reporting members
+    /// declared twice and inclusions of types that are not object types.
+    fn object_type(&mut self, qualifiers: &[Qualifier], members: &[ObjectMember]) -> ObjectType {
+        let is_readonly = qualifiers.contains(&Qualifier::Readonly);
+        let mut object = ObjectType {
+            name: None,
+            is_class: false,
+            is_readonly,
+            fields: Vec::new(),
+            methods: Vec::new(),
+        };
+        let member_name = |member: &ObjectMember| match member {
+            ObjectMember::Field(field) => Some((field.name.clone(), field.name_span.clone())),
+            ObjectMember::Method(method) => Some((method.name.clone(), method.name_span.clone())),
+            ObjectMember::Inclusion { .. } => None,
+        };
+        for (index, member) in members.iter().enumerate() {
+            let Some((name, name_span)) = member_name(member) else {
+                continue;
+            };
+            if let Some((_, first)) = members[..index]
+                .iter()
+                .filter_map(member_name)
+                .find(|(other, _)| *other == name)
+            {
+                self.diagnostics.push(
+                    Diagnostic::new(
+                        DiagnosticKind::Semantic,
+                        format!("Redeclaration of member '{name}'"),
+                        name_span,
+                    )
+                    .with_code(codes::REDECLARATION)
+                    .with_label("redeclared here")
+                    .with_secondary(first, format!("'{name}' first declared here")),
+                );
+                continue;
+            }
+            match member {
+                ObjectMember::Field(field) => {
+                    let ty = self.type_from_annotation(&field.type_desc, field.span.clone());
+                    object.fields.push(Field {
+                        name,
+                        ty,
+                        required: field.default.is_none(),
+                        is_final: is_readonly || field.qualifiers.contains(&Qualifier::Final),
+                        is_private: field.qualifiers.contains(&Qualifier::Private),
+                        name_span: Some(field.name_span.clone()),
+                    });
+                }
+                ObjectMember::Method(method) => {
+                    let method = self.method_type(method);
+                    object.methods.push(method);
+                }
+                ObjectMember::Inclusion { .. } => {}
+            }
+        }
+
+        for member in members {
+            let ObjectMember::Inclusion {
+                type_name,
+                span: inclusion_span,
+            } = member
+            else {
+                continue;
+            };
+            if type_name.contains(':') {
+                // A type of another module.
+                continue;
+            }
+            match self.resolve_named_type(type_name) {
+                Some(Type::Object(included)) => {
+                    for field in included.fields {
+                        if object.field(&field.name).is_none() {
+                            object.fields.push(field);
+                        }
+                    }
+                    for method in included.methods {
+                        if object.method(&method.name).is_none() {
+                            object.methods.push(method);
+                        }
+                    }
                 }
+                Some(ty) if ty.is_unknown() => {}
+                Some(ty) => self.report(
+                    codes::TYPE_MISMATCH,
+                    inclusion_span.clone(),
+                    format!("Type inclusion expects an object type, found {ty}"),
+                ),
+                None => self.report(
+                    codes::UNKNOWN_TYPE,
+                    inclusion_span.clone(),
+                    format!("Unknown type '{type_name}'"),
+                ),
+            }
+        }
+        object
+    }
+
+    /// Resolves the parameter and return types of a method declaration.
+    fn method_type(&mut self, method: &MethodDecl) -> Method {
+        let params = method
+            .params
+            .iter()
+            .map(|(_, ty)| self.type_from_annotation(ty, method.name_span.clone()))
+            .collect();
+        let return_type = method
+            .return_type
+            .as_ref()
+            .map(|ty| self.type_from_annotation(ty, method.name_span.clone()))
+            .unwrap_or(Type::Nil);
+        Method {
+            name: method.name.clone(),
+            params,
+            return_type,
+        }
+    }
+
+    /// Converts the fields of a record type descriptor, reporting fields
+    /// declared twice.
+    fn record_fields(&mut self, fields: &[RecordField]) -> Vec<Field> {
+        let mut converted: Vec<Field> = Vec::new();
+        for (index, field) in fields.iter().enumerate() {
+            let ty = self.type_from_annotation(&field.type_desc, field.span.clone());
+            if let Some(first) = fields[..index]
+                .iter()
+                .find(|other| other.name == field.name)
+            {
+                self.diagnostics.push(
+                    Diagnostic::new(
+                        DiagnosticKind::Semantic,
+                        format!("Redeclaration of field '{}'", field.name),
+                        field.name_span.clone(),
+                    )
+                    .with_code(codes::REDECLARATION)
+                    .with_label("redeclared here")
+                    .with_secondary(
+                        first.name_span.clone(),
+                        format!("'{}' first declared here", field.name),
+                    ),
+                );
+                continue;
             }
+            converted.push(Field {
+                name: field.name.clone(),
+                ty,
+                required: !field.optional && field.default.is_none(),
+                is_final: false,
+                is_private: false,
+                name_span: None,
+            });
+        }
+        converted
+    }
+
+    /// Resolves the type defined as `name`, converting its definition on
+    /// first use. `None` when no type of that name is defined.
+    fn resolve_named_type(&mut self, name: &str) -> Option<Type> {
+        if let Some(ty) = self.types.get(name) {
+            return Some(ty.clone());
+        }
+        let definition = self.type_definitions.get(name)?.clone();
+        if !self.resolving_types.insert(name.to_string()) {
+            // A recursive reference, such as `Node? next;` within `Node`.
+            return Some(Type::Unknown(name.to_string()));
         }
+        let ty = match self.type_from_annotation(&definition.type_desc, definition.span) {
+            Type::Record(mut record) => {
+                record.name = Some(name.to_string());
+                Type::Record(record)
+            }
+            Type::Object(mut object) => {
+                object.name = Some(name.to_string());
+                object.is_class = definition.is_class;
+                Type::Object(object)
+            }
+            ty => ty,
+        };
+        self.resolving_types.remove(name);
+        self.types.insert(name.to_string(), ty.clone());
+        Some(ty)
     }
 
-    /// Appends a semantic diagnostic covering the provided span.
-    fn report(&mut self, span: Span, message: String) {
+    /// Appends a semantic diagnostic with the given code covering the
+    /// provided span.
+    fn report(&mut self, code: &'static str, span: Span, message: String) {
         self.diagnostics
-            .push(Diagnostic::new(DiagnosticKind::Semantic, message, span));
+            .push(Diagnostic::new(DiagnosticKind::Semantic, message, span).with_code(code));
+    }
+
+    /// Reports a use of `name`, which no visible scope declares, suggesting
+    /// a visible variable with a similar name.
+    fn report_undeclared(&mut self, name: &str, span: Span) {
+        let mut diagnostic = Diagnostic::new(
+            DiagnosticKind::Semantic,
+            format!("Use of undeclared variable '{name}'"),
+            span,
+        )
+        .with_code(codes::UNDECLARED_VARIABLE);
+        let visible = self.scopes.iter().flat_map(|scope| scope.keys());
+        if let Some(similar) = similar_name(name, visible) {
+            diagnostic = diagnostic.with_help(format!(
+                "a variable with a similar name exists: '{similar}'"
+            ));
+        }
+        self.diagnostics.push(diagnostic);
     }
 
-    /// Executes a closure with a new scope pushed on the stack.
-    fn with_scope<F>(&mut self, mut f: F)
+    /// Executes a closure with a new scope, opened by the statement at
+    /// `span`, pushed on the stack.
+    fn with_scope<F>(&mut self, kind: ScopeKind, span: &Span, mut f: F)
     where
         F: FnMut(&mut Self),
     {
+        let id = ScopeId(self.model.scopes.len());
+        self.model.scopes.push(ScopeInfo {
+            kind,
+            parent: self.scope_ids.last().copied(),
+            span: Some(span.clone()),
+            symbols: Vec::new(),
+        });
         self.scopes.push(HashMap::new());
+        self.scope_ids.push(id);
         f(self);
+        self.scope_ids.pop();
         self.scopes.pop();
     }
 
-    /// Collects function names ahead of time so undefined call targets can be reported.
+    /// The return type of the function `name`, converting its declared type
+    /// the first time it is needed. `None` when no function has the name.
+    fn function_return_type(&mut self, name: &str) -> Option<Type> {
+        let info = self.functions.get(name)?;
+        let declared = match &info.return_type {
+            ReturnType::Resolved(ty) => return Some(ty.clone()),
+            ReturnType::Declared(declared) => declared.clone(),
+        };
+        let span = info.name_span.clone();
+        let ty = declared
+            .map(|ty| self.type_from_annotation(&ty, span))
+            .unwrap_or(Type::Nil);
+        if let Some(info) = self.functions.get_mut(name) {
+            info.return_type = ReturnType::Resolved(ty.clone());
+        }
+        Some(ty)
+    }
+
+    /// Collects functions and their declared return types ahead of time, so
+    /// undefined call targets can be reported and calls typed.
     fn collect_functions(&mut self, stmts: &[Stmt]) {
         for stmt in stmts {
             match stmt {
-                Stmt::Function { name, body, .. } => {
-                    self.functions.insert(name.clone());
+                Stmt::Function {
+                    name,
+                    name_span,
+                    return_type,
+                    body,
+                    ..
+                } => {
+                    self.functions
+                        .entry(name.clone())
+                        .or_insert_with(|| FunctionInfo {
+                            name_span: name_span.clone(),
+                            return_type: ReturnType::Declared(return_type.clone()),
+                        });
                     self.collect_functions(body);
                 }
                 Stmt::If {
@@ -1016,18 +2748,215 @@ impl Analyzer {
         }
     }
 
-    /// Returns the current innermost scope.
-    fn current_scope(&self) -> &HashMap<String, Symbol> {
-        self.scopes.last().expect("at least one scope present")
+    /// Collects module-level type and class definitions ahead of time, so
+    /// types can be used before the definition that declares them.
+    fn collect_types(&mut self, stmts: &[Stmt]) {
+        for stmt in stmts {
+            let (name, name_span, type_desc, is_class, span) = match stmt {
+                Stmt::TypeDefinition {
+                    name,
+                    name_span,
+                    type_desc,
+                    span,
+                    ..
+                } => (name, name_span, type_desc.clone(), false, span),
+                Stmt::Class {
+                    qualifiers,
+                    name,
+                    name_span,
+                    members,
+                    span,
+                    ..
+                } => (
+                    name,
+                    name_span,
+                    class_type_descriptor(qualifiers, members),
+                    true,
+                    span,
+                ),
+                _ => continue,
+            };
+            if let Some(first) = self.type_definitions.get(name) {
+                let first_name = first.name_span.clone();
+                self.diagnostics.push(
+                    Diagnostic::new(
+                        DiagnosticKind::Semantic,
+                        format!("Redeclaration of type '{name}'"),
+                        name_span.clone(),
+                    )
+                    .with_code(codes::REDECLARATION)
+                    .with_label("redeclared here")
+                    .with_secondary(first_name, format!("'{name}' first declared here")),
+                );
+                continue;
+            }
+            self.type_definitions.insert(
+                name.clone(),
+                TypeDefinition {
+                    type_desc,
+                    is_class,
+                    name_span: name_span.clone(),
+                    span: span.clone(),
+                },
+            );
+        }
+    }
+
+    /// Binds `name` in the innermost scope and records the declaration.
+    fn declare(&mut self, name: &str, kind: SymbolKind, name_span: Option<&Span>, symbol: Symbol) {
+        let id = self.record_declaration(
+            name,
+            kind,
+            symbol.ty.clone(),
+            symbol.declared_span.clone(),
+            name_span,
+        );
+        self.symbol_states.push(symbol);
+        self.scopes
+            .last_mut()
+            .expect("at least one scope present")
+            .insert(name.to_string(), id);
+    }
+
+    /// Adds a declaration in the innermost scope to the model.
+    fn record_declaration(
+        &mut self,
+        name: &str,
+        kind: SymbolKind,
+        ty: Type,
+        span: Span,
+        name_span: Option<&Span>,
+    ) -> SymbolId {
+        let id = SymbolId(self.model.symbols.len());
+        let scope = *self.scope_ids.last().expect("at least one scope present");
+        self.model.symbols.push(SymbolInfo {
+            id,
+            name: name.to_string(),
+            kind,
+            ty,
+            span,
+            name_span: name_span.cloned(),
+            scope,
+            scope_depth: self.scopes.len() - 1,
+        });
+        self.model.scopes[scope.0].symbols.push(id);
+        // Functions, methods, and types have no binding state; keep
+        // `symbol_states` aligned.
+        if matches!(
+            kind,
+            SymbolKind::Function | SymbolKind::Type | SymbolKind::Class | SymbolKind::Method
+        ) {
+            self.symbol_states.push(Symbol {
+                ty: Type::Nil,
+                is_final: true,
+                is_const: true,
+                initialized: true,
+                declared_span: Span::default(),
+            });
+        }
+        id
     }
 
-    /// Returns a mutable reference to the current innermost scope.
-    fn current_scope_mut(&mut self) -> &mut HashMap<String, Symbol> {
-        self.scopes.last_mut().expect("at least one scope present")
+    /// Returns the symbol bound to `name` in the innermost scope, if any.
+    fn current_declaration(&self, name: &str) -> Option<&SymbolInfo> {
+        self.scopes
+            .last()
+            .expect("at least one scope present")
+            .get(name)
+            .map(|id| &self.model.symbols[id.0])
     }
 }
 
+/// The object type a class definition declares: its fields, inclusions, and
+/// the signatures of its methods.
+fn class_type_descriptor(qualifiers: &[Qualifier], members: &[ClassMember]) -> TypeDescriptor {
+    let members = members
+        .iter()
+        .filter_map(|member| match member {
+            ClassMember::Field(field) => Some(ObjectMember::Field(field.clone())),
+            ClassMember::Method(Stmt::Function {
+                is_public,
+                qualifiers,
+                name,
+                name_span,
+                params,
+                return_type,
+                span,
+                ..
+            }) => Some(ObjectMember::Method(MethodDecl {
+                is_public: *is_public,
+                qualifiers: qualifiers.clone(),
+                name: name.clone(),
+                name_span: name_span.clone(),
+                params: params.clone(),
+                return_type: return_type.clone(),
+                span: span.clone(),
+            })),
+            ClassMember::Method(_) => None,
+            ClassMember::Inclusion { type_name, span } => Some(ObjectMember::Inclusion {
+                type_name: type_name.clone(),
+                span: span.clone(),
+            }),
+        })
+        .collect();
+    TypeDescriptor::Object {
+        qualifiers: qualifiers.to_vec(),
+        members,
+    }
+}
+
+/// Picks the candidate closest to `name` by edit distance, if any is close
+/// enough to be a likely typo.
+fn similar_name<'a>(name: &str, candidates: impl Iterator<Item = &'a String>) -> Option<&'a str> {
+    // About one edit per three characters; very short names get no
+    // suggestions, as nearly everything is close to them.
+    let limit = (name.chars().count() + 1) / 3;
+    candidates
+        .filter(|candidate| candidate.as_str() != name)
+        .map(|candidate| (edit_distance(name, candidate), candidate))
+        .filter(|(distance, _)| *distance <= limit)
+        .min()
+        .map(|(_, candidate)| candidate.as_str())
+}
+
+/// Levenshtein distance between `a` and `b`, counted in characters.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(ca != *cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+    row[b.len()]
+}
+
 /// Public facade used by the rest of the crate to run semantic analysis.
 pub fn analyze(statements: &[Stmt]) -> Result<(), Vec<Diagnostic>> {
-    Analyzer::new().analyze(statements)
+    let (_, diagnostics) = analyze_program(statements);
+    if diagnostics.is_empty() {
+        Ok(())
+    } else {
+        Err(diagnostics)
+    }
+}
+
+/// Analyzes `statements`, returning the semantic model alongside any
+/// diagnostics. The model is built even when the program has errors.
+pub fn analyze_program(statements: &[Stmt]) -> (SemanticModel, Vec<Diagnostic>) {
+    let mut analyzer = Analyzer::new();
+    analyzer.check_program(statements);
+    (analyzer.model, analyzer.diagnostics)
+}
+
+/// Lists every declaration the analyzer binds, in the order it meets them.
+///
+/// Analysis diagnostics are discarded; declarations that fail to bind, such as
+/// redeclarations, are omitted.
+pub fn symbols(statements: &[Stmt]) -> Vec<SymbolInfo> {
+    analyze_program(statements).0.symbols
 }
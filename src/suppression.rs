@@ -0,0 +1,383 @@
+//! Inline suppression comments.
+//!
+//! Lint findings can be silenced with comments naming the rules to suppress,
+//! separated by commas or spaces. Naming no rules suppresses every rule, and
+//! text after `--` is kept as the justification:
+//!
+//! ```ballerina
+//! // blazelint-disable-next-line camel_case -- generated name
+//! int snake_name = 1;
+//! int other_name = 2; // blazelint-disable-line camel_case
+//!
+//! /* blazelint-disable line_length */
+//! string banner = "...";
+//! /* blazelint-enable line_length */
+//! ```
+//!
+//! Only rule diagnostics can be suppressed. Directives that suppress nothing,
+//! name an unknown rule, or are misspelled are reported as warnings.
+
+use crate::codes;
+use crate::errors::{Diagnostic, DiagnosticKind, Severity, Span, Suppression};
+use crate::lexer::{Comment, CommentKind};
+use crate::linter::{renamed_rule, rule_names};
+use crate::report::{byte_to_line_col, compute_line_starts};
+
+/// Marker that starts every suppression comment.
+pub const DIRECTIVE_PREFIX: &str = "blazelint-";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DirectiveKind {
+    DisableNextLine,
+    DisableLine,
+    Disable,
+    Enable,
+}
+
+impl DirectiveKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            DirectiveKind::DisableNextLine => "blazelint-disable-next-line",
+            DirectiveKind::DisableLine => "blazelint-disable-line",
+            DirectiveKind::Disable => "blazelint-disable",
+            DirectiveKind::Enable => "blazelint-enable",
+        }
+    }
+}
+
+/// A parsed suppression comment.
+struct Directive {
+    kind: DirectiveKind,
+    /// Known rules named by the directive, with their spans.
+    rules: Vec<(String, Span)>,
+    /// True when the directive names no rules and so covers all of them.
+    all_rules: bool,
+    justification: Option<String>,
+    span: Span,
+    /// Whether each entry of `rules` (or, with `all_rules`, the directive as a
+    /// whole) silenced at least one diagnostic.
+    used: Vec<bool>,
+}
+
+/// A stretch of source in which a directive silences diagnostics.
+struct Region {
+    directive: usize,
+    /// Rules still disabled; ignored when the directive covers all rules.
+    rules: Vec<String>,
+    start: usize,
+    end: usize,
+}
+
+/// Applies suppression comments to `diagnostics`.
+///
+/// Suppressed diagnostics are removed, marked with their [`Suppression`], and
+/// returned. Warnings about the directives themselves are appended to
+/// `diagnostics`; `active_rules` lists the rules that ran, so directives for
+/// rules disabled in the configuration are not reported as unused.
+pub fn apply(
+    source: &str,
+    comments: &[Comment],
+    diagnostics: &mut Vec<Diagnostic>,
+    active_rules: &[&str],
+) -> Vec<Diagnostic> {
+    let mut warnings = Vec::new();
+    let mut directives: Vec<Directive> = comments
+        .iter()
+        .filter(|comment| comment.kind != CommentKind::Doc)
+        .filter_map(|comment| parse_directive(source, comment, &mut warnings))
+        .collect();
+    if directives.is_empty() {
+        diagnostics.extend(warnings);
+        return Vec::new();
+    }
+
+    let regions = build_regions(source, &directives, &mut warnings);
+
+    let mut suppressed = Vec::new();
+    let mut kept = Vec::new();
+    for mut diagnostic in diagnostics.drain(..) {
+        let Some(rule) = diagnostic.rule.as_deref() else {
+            kept.push(diagnostic);
+            continue;
+        };
+        let position = diagnostic.span.start;
+        let covering = regions.iter().find(|region| {
+            let directive = &directives[region.directive];
+            region.start <= position
+                && position < region.end
+                && (directive.all_rules || region.rules.iter().any(|name| name == rule))
+        });
+        let Some(region) = covering else {
+            kept.push(diagnostic);
+            continue;
+        };
+
+        let directive = &mut directives[region.directive];
+        let slot = if directive.all_rules {
+            0
+        } else {
+            directive
+                .rules
+                .iter()
+                .position(|(name, _)| name == rule)
+                .expect("region rules come from the directive")
+        };
+        directive.used[slot] = true;
+        diagnostic.suppression = Some(Suppression {
+            justification: directive.justification.clone(),
+        });
+        suppressed.push(diagnostic);
+    }
+
+    for directive in &directives {
+        if directive.kind == DirectiveKind::Enable {
+            continue;
+        }
+        if directive.all_rules {
+            if !directive.used[0] {
+                warnings.push(warning(
+                    codes::UNUSED_SUPPRESSION,
+                    format!(
+                        "Unused suppression: '{}' did not silence any diagnostic",
+                        directive.kind.as_str()
+                    ),
+                    directive.span.clone(),
+                ));
+            }
+            continue;
+        }
+        for ((name, span), used) in directive.rules.iter().zip(&directive.used) {
+            if !used && active_rules.contains(&name.as_str()) {
+                warnings.push(warning(
+                    codes::UNUSED_SUPPRESSION,
+                    format!("Unused suppression: '{name}' is not reported here"),
+                    span.clone(),
+                ));
+            }
+        }
+    }
+
+    kept.extend(warnings);
+    *diagnostics = kept;
+    suppressed
+}
+
+/// Parses `comment` as a directive, or returns `None` for ordinary comments.
+fn parse_directive(
+    source: &str,
+    comment: &Comment,
+    warnings: &mut Vec<Diagnostic>,
+) -> Option<Directive> {
+    let text = comment.text(source);
+    let text_start = comment.span.start + 2;
+    let leading = text.len() - text.trim_start().len();
+    let body = text.trim_start().strip_prefix(DIRECTIVE_PREFIX)?;
+    let body_start = text_start + leading + DIRECTIVE_PREFIX.len();
+
+    let word_len = body.find(char::is_whitespace).unwrap_or(body.len());
+    let kind = match &body[..word_len] {
+        "disable-next-line" => DirectiveKind::DisableNextLine,
+        "disable-line" => DirectiveKind::DisableLine,
+        "disable" => DirectiveKind::Disable,
+        "enable" => DirectiveKind::Enable,
+        other => {
+            let start = body_start - DIRECTIVE_PREFIX.len();
+            warnings.push(
+                warning(
+                    codes::INVALID_SUPPRESSION,
+                    format!("Unknown suppression directive '{DIRECTIVE_PREFIX}{other}'"),
+                    start..body_start + word_len,
+                )
+                .with_note(
+                    "expected blazelint-disable-next-line, blazelint-disable-line, \
+                     blazelint-disable, or blazelint-enable",
+                ),
+            );
+            return None;
+        }
+    };
+
+    let rest = &body[word_len..];
+    let (list, justification) = match rest.split_once("--") {
+        Some((list, reason)) => (list, Some(reason.trim().to_string())),
+        None => (rest, None),
+    };
+    let justification = justification.filter(|reason| !reason.is_empty());
+
+    let known = rule_names();
+    let list_start = body_start + word_len;
+    let mut rules = Vec::new();
+    let mut named_any = false;
+    for (offset, name) in split_rule_list(list) {
+        named_any = true;
+        let span = list_start + offset..list_start + offset + name.len();
+        if known.contains(&name) {
+            rules.push((name.to_string(), span));
+        } else if let Some(current) = renamed_rule(name) {
+            warnings.push(
+                warning(
+                    codes::INVALID_SUPPRESSION,
+                    format!("Rule '{name}' has been renamed to '{current}'"),
+                    span.clone(),
+                )
+                .with_help(format!("use '{current}' instead")),
+            );
+            rules.push((current.to_string(), span));
+        } else {
+            warnings.push(
+                warning(
+                    codes::INVALID_SUPPRESSION,
+                    format!("Unknown rule '{name}' in suppression comment"),
+                    span,
+                )
+                .with_note(format!("available rules: {}", known.join(", "))),
+            );
+        }
+    }
+
+    let all_rules = !named_any;
+    let used = vec![false; if all_rules { 1 } else { rules.len() }];
+    Some(Directive {
+        kind,
+        rules,
+        all_rules,
+        justification,
+        span: comment.span.clone(),
+        used,
+    })
+}
+
+/// Splits a rule list on commas and whitespace, yielding each name with its
+/// byte offset in `list`.
+fn split_rule_list(list: &str) -> Vec<(usize, &str)> {
+    let mut names = Vec::new();
+    let mut start = None;
+    for (idx, c) in list.char_indices() {
+        let separator = c == ',' || c.is_whitespace();
+        match (separator, start) {
+            (true, Some(begin)) => {
+                names.push((begin, &list[begin..idx]));
+                start = None;
+            }
+            (false, None) => start = Some(idx),
+            _ => {}
+        }
+    }
+    if let Some(begin) = start {
+        names.push((begin, &list[begin..]));
+    }
+    names
+}
+
+/// Works out the source ranges each directive covers.
+fn build_regions(
+    source: &str,
+    directives: &[Directive],
+    warnings: &mut Vec<Diagnostic>,
+) -> Vec<Region> {
+    let line_starts = compute_line_starts(source);
+    let line_range = |line: usize| -> (usize, usize) {
+        let start = line_starts.get(line - 1).copied().unwrap_or(source.len());
+        let end = line_starts.get(line).copied().unwrap_or(source.len());
+        (start, end)
+    };
+
+    let mut regions = Vec::new();
+    // Indices into `regions` opened by `blazelint-disable` and not yet closed.
+    let mut open: Vec<usize> = Vec::new();
+
+    for (index, directive) in directives.iter().enumerate() {
+        let rules: Vec<String> = directive
+            .rules
+            .iter()
+            .map(|(name, _)| name.clone())
+            .collect();
+        if !directive.all_rules && rules.is_empty() {
+            // Every named rule was unknown and has already been reported.
+            continue;
+        }
+        match directive.kind {
+            DirectiveKind::DisableLine | DirectiveKind::DisableNextLine => {
+                let (line, _) = byte_to_line_col(&line_starts, directive.span.start);
+                let line = if directive.kind == DirectiveKind::DisableLine {
+                    line
+                } else {
+                    byte_to_line_col(&line_starts, directive.span.end).0 + 1
+                };
+                let (start, end) = if line < line_starts.len() {
+                    line_range(line)
+                } else {
+                    (source.len(), source.len())
+                };
+                regions.push(Region {
+                    directive: index,
+                    rules,
+                    start,
+                    end,
+                });
+            }
+            DirectiveKind::Disable => {
+                open.push(regions.len());
+                regions.push(Region {
+                    directive: index,
+                    rules,
+                    start: directive.span.end,
+                    end: usize::MAX,
+                });
+            }
+            DirectiveKind::Enable => {
+                let position = directive.span.start;
+                let mut matched = false;
+                let mut reopened = Vec::new();
+                open.retain(|&region_index| {
+                    let region = &mut regions[region_index];
+                    let region_all = directives[region.directive].all_rules;
+                    if directive.all_rules {
+                        region.end = position;
+                        matched = true;
+                        return false;
+                    }
+                    if region_all || !region.rules.iter().any(|name| rules.contains(name)) {
+                        return true;
+                    }
+                    matched = true;
+                    region.end = position;
+                    let remaining: Vec<String> = region
+                        .rules
+                        .iter()
+                        .filter(|name| !rules.contains(name))
+                        .cloned()
+                        .collect();
+                    if !remaining.is_empty() {
+                        reopened.push(Region {
+                            directive: region.directive,
+                            rules: remaining,
+                            start: directive.span.end,
+                            end: usize::MAX,
+                        });
+                    }
+                    false
+                });
+                for region in reopened {
+                    open.push(regions.len());
+                    regions.push(region);
+                }
+                if !matched {
+                    warnings.push(warning(
+                        codes::INVALID_SUPPRESSION,
+                        "'blazelint-enable' does not match an earlier 'blazelint-disable'",
+                        directive.span.clone(),
+                    ));
+                }
+            }
+        }
+    }
+
+    regions
+}
+
+fn warning(code: &'static str, message: impl Into<String>, span: Span) -> Diagnostic {
+    Diagnostic::new(DiagnosticKind::Suppression, message, span)
+        .with_severity(Severity::Warning)
+        .with_code(code)
+}
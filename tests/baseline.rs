@@ -0,0 +1,169 @@
+mod common;
+
+use blazelint::baseline::{fingerprint, Baseline};
+use blazelint::{lint_source, LintOptions};
+use common::{run_cli, stdout};
+use std::fs;
+
+fn baseline_of(file: &str, source: &str) -> Baseline {
+    let report = lint_source(source, file, &LintOptions::default());
+    let mut baseline = Baseline::default();
+    for diagnostic in &report.diagnostics {
+        baseline.add(file, source, diagnostic);
+    }
+    baseline
+}
+
+/// Messages of the diagnostics in `source` that `baseline` does not cover.
+fn new_findings(baseline: &mut Baseline, file: &str, source: &str) -> Vec<String> {
+    let report = lint_source(source, file, &LintOptions::default());
+    let (new, _) = baseline.partition(file, source, report.diagnostics);
+    new.into_iter()
+        .map(|diagnostic| diagnostic.message)
+        .collect()
+}
+
+#[test]
+fn fingerprints_ignore_position_and_whitespace() {
+    let before = "int bad_name = 1;\n";
+    let after = "// header\n\nfunction f() {}\n    int  bad_name  =  1;\n";
+    let diagnostic = |source: &str| {
+        lint_source(source, "a.bal", &LintOptions::default())
+            .diagnostics
+            .remove(0)
+    };
+    assert_eq!(
+        fingerprint(before, &diagnostic(before)),
+        fingerprint(after, &diagnostic(after))
+    );
+    let edited = "int bad_name = 2;\n";
+    assert_ne!(
+        fingerprint(before, &diagnostic(before)),
+        fingerprint(edited, &diagnostic(edited))
+    );
+}
+
+#[test]
+fn baseline_reports_only_new_findings() {
+    let mut baseline = baseline_of("./a.bal", "int bad_name = 1;\nint other_name = 2;\n");
+    assert_eq!(baseline.len(), 2);
+
+    let source = "int new_name = 0;\nint bad_name = 1;\n\nint other_name = 2;\n";
+    assert_eq!(
+        new_findings(&mut baseline, "a.bal", source),
+        ["Variable \"new_name\" is not in camelCase."]
+    );
+    // Entries are keyed by file.
+    assert_eq!(
+        new_findings(&mut baseline_of("a.bal", source), "b.bal", source).len(),
+        3
+    );
+}
+
+#[test]
+fn identical_findings_are_counted() {
+    let mut baseline = baseline_of("a.bal", "function f() {\n    int bad_name = 1;\n}\n");
+    let source = "function f() {\n    int bad_name = 1;\n}\n\
+                  function g() {\n    int bad_name = 1;\n}\n";
+    assert_eq!(new_findings(&mut baseline, "a.bal", source).len(), 1);
+}
+
+#[test]
+fn baseline_round_trips_through_json() {
+    let baseline = baseline_of("a.bal", "int bad_name = 1;\nint bad_name = 1;\n");
+    let json = baseline.to_json().to_pretty_string();
+    let parsed = Baseline::parse(&json).expect("parses");
+    assert_eq!(parsed.len(), baseline.len());
+    assert_eq!(parsed.to_json(), baseline.to_json());
+    assert!(json.contains("\"rule\": \"camel_case\""), "{json}");
+    assert!(json.contains("\"rule\": \"BL3001\""), "{json}");
+
+    let err = Baseline::parse("{\"version\": 9, \"entries\": []}").expect_err("version");
+    assert_eq!(err, "unsupported baseline version 9");
+    let err = Baseline::parse("{\"version\": 1, \"entries\": [{}]}").expect_err("entry");
+    assert_eq!(err, "entry 0 has no 'file'");
+
+    let used_up = Baseline::parse(
+        "{\"version\": 1, \"entries\": [{\"file\": \"a.bal\", \"rule\": \"camel_case\", \
+         \"fingerprint\": \"0\", \"count\": 0}]}",
+    )
+    .expect("parses");
+    assert_eq!(used_up.len(), 0);
+    assert!(used_up.is_empty());
+    assert_eq!(used_up.to_json(), Baseline::default().to_json());
+}
+
+#[test]
+fn cli_writes_and_applies_a_baseline() {
+    let dir = tempfile::tempdir().expect("temp dir");
+    fs::write(dir.path().join("a.bal"), "int bad_name = 1;\n").expect("write");
+
+    let output = run_cli(
+        &["--baseline", "baseline.json", "--write-baseline", "."],
+        dir.path(),
+    );
+    assert!(output.status.success());
+    assert!(
+        stdout(&output).starts_with("Wrote 1 finding(s) to baseline baseline.json\n"),
+        "{}",
+        stdout(&output)
+    );
+
+    fs::write(
+        dir.path().join("a.bal"),
+        "int added_name = 0;\n\nint bad_name = 1;\n",
+    )
+    .expect("write");
+    let output = run_cli(
+        &["--baseline=baseline.json", "--deny-warnings", "a.bal"],
+        dir.path(),
+    );
+    let out = stdout(&output);
+    assert_eq!(output.status.code(), Some(1), "{out}");
+    assert!(out.contains("\"added_name\""), "{out}");
+    assert!(!out.contains("\"bad_name\""), "{out}");
+    assert!(
+        out.contains("Checked 1 file(s): 0 error(s), 1 warning(s), 1 baselined"),
+        "{out}"
+    );
+}
+
+#[test]
+fn cli_rejects_unusable_baselines() {
+    let dir = tempfile::tempdir().expect("temp dir");
+    fs::write(dir.path().join("a.bal"), "int x = 1;\n").expect("write");
+    fs::write(dir.path().join("bad.json"), "[]").expect("write");
+
+    let output = run_cli(&["--baseline", "bad.json", "a.bal"], dir.path());
+    assert_eq!(output.status.code(), Some(1));
+    let err = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        err.contains("Error reading baseline bad.json: missing baseline version"),
+        "{err}"
+    );
+
+    let output = run_cli(&["--write-baseline", "a.bal"], dir.path());
+    assert_eq!(output.status.code(), Some(1));
+    let err = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        err.contains("'--write-baseline' requires '--baseline <FILE>'"),
+        "{err}"
+    );
+
+    fs::write(dir.path().join("pr.diff"), "").expect("write");
+    let output = run_cli(
+        &[
+            "--baseline",
+            "baseline.json",
+            "--write-baseline",
+            "--diff",
+            "pr.diff",
+            "a.bal",
+        ],
+        dir.path(),
+    );
+    assert_eq!(output.status.code(), Some(1));
+    let err = String::from_utf8_lossy(&output.stderr);
+    assert!(err.contains("cannot be combined with '--diff'"), "{err}");
+    assert!(!dir.path().join("baseline.json").exists());
+}
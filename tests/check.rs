@@ -0,0 +1,236 @@
+use blazelint::ast::{Expr, Stmt, UnaryOp};
+use blazelint::codes;
+use blazelint::format::{format_source, FormatOptions};
+use blazelint::semantic::Type;
+use blazelint::{lint_source, parse, syntax_tree, tokenize, LintOptions};
+
+fn parse_program(source: &str) -> Vec<Stmt> {
+    let tokens = tokenize(source).expect("tokens");
+    let (ast, diagnostics) = parse(&tokens);
+    assert!(diagnostics.is_empty(), "{diagnostics:?}");
+    ast
+}
+
+/// `(code, message)` of every diagnostic for `source`.
+fn diagnostics(source: &str) -> Vec<(&'static str, String)> {
+    lint_source(source, "test.bal", &LintOptions::default())
+        .diagnostics
+        .into_iter()
+        .map(|diagnostic| (diagnostic.code.expect("code"), diagnostic.message))
+        .collect()
+}
+
+#[test]
+fn parses_check_and_checkpanic_as_unary_expressions() {
+    let program = parse_program(
+        "int a = check parse(\"1\");\n\
+         int b = checkpanic parse(\"2\") + 1;\n",
+    );
+
+    let Stmt::VarDecl {
+        initializer:
+            Some(Expr::Unary {
+                op: UnaryOp::Check,
+                operand,
+                ..
+            }),
+        ..
+    } = &program[0]
+    else {
+        panic!("expected a check expression: {:?}", program[0]);
+    };
+    assert!(matches!(operand.as_ref(), Expr::Call { .. }));
+
+    // Like `-`, `checkpanic` binds tighter than binary operators.
+    let Stmt::VarDecl {
+        initializer: Some(Expr::Binary { left, .. }),
+        ..
+    } = &program[1]
+    else {
+        panic!("expected a binary expression: {:?}", program[1]);
+    };
+    assert!(matches!(
+        left.as_ref(),
+        Expr::Unary {
+            op: UnaryOp::CheckPanic,
+            ..
+        }
+    ));
+}
+
+#[test]
+fn check_needs_a_function_that_can_return_an_error() {
+    let source = "function parse() returns int|error {\n\
+                  \x20   return 1;\n\
+                  }\n\
+                  \n\
+                  int|error parsed = 42;\n\
+                  int atModuleLevel = check parsed;\n\
+                  \n\
+                  function optional() returns error? {\n\
+                  \x20   int value = check parsed;\n\
+                  }\n\
+                  \n\
+                  function panics() returns int {\n\
+                  \x20   return checkpanic parsed;\n\
+                  }\n\
+                  \n\
+                  function main() {\n\
+                  \x20   int value = check parsed;\n\
+                  }\n\
+                  \n\
+                  function count() returns int|string {\n\
+                  \x20   return check parsed;\n\
+                  }\n";
+    assert_eq!(
+        diagnostics(source),
+        [
+            (
+                codes::CHECK_WITHOUT_ERROR_RETURN,
+                "'check' in a function that cannot return an error: its return type is ()"
+                    .to_string()
+            ),
+            (
+                codes::CHECK_WITHOUT_ERROR_RETURN,
+                "'check' in a function that cannot return an error: its return type is int|string"
+                    .to_string()
+            ),
+        ]
+    );
+}
+
+#[test]
+fn checked_expressions_drop_the_error_type() {
+    let source = "function main() returns error? {\n\
+                  \x20   int|string|error parsed = 42;\n\
+                  \x20   int|string value = check parsed;\n\
+                  \x20   int number = checkpanic parsed;\n\
+                  \x20   error failure = error(\"failed\");\n\
+                  \x20   int|error retried = failure;\n\
+                  }\n";
+    assert_eq!(
+        diagnostics(source),
+        [(
+            codes::TYPE_MISMATCH,
+            "Type mismatch in initializer: expected int, found int|string".to_string()
+        )]
+    );
+
+    let report = lint_source(
+        "function main() returns error? {\n\
+         \x20   int|error parsed = 42;\n\
+         \x20   var value = check parsed;\n\
+         }\n",
+        "test.bal",
+        &LintOptions::default(),
+    );
+    assert!(report.is_clean(), "{:?}", report.diagnostics);
+    let value = report
+        .semantic
+        .symbols()
+        .iter()
+        .find(|symbol| symbol.name == "value")
+        .map(|symbol| &symbol.ty);
+    assert_eq!(value, Some(&Type::Int));
+}
+
+#[test]
+fn checked_calls_take_the_declared_return_type() {
+    let source = "function parse(string text) returns int|error {\n\
+                  \x20   return 1;\n\
+                  }\n\
+                  \n\
+                  function total() returns int|error {\n\
+                  \x20   int first = check parse(\"1\");\n\
+                  \x20   int second = checkpanic parse(\"2\");\n\
+                  \x20   return check parse(\"3\") + first + second + later();\n\
+                  }\n\
+                  \n\
+                  function later() returns int {\n\
+                  \x20   return 0;\n\
+                  }\n";
+    let report = lint_source(source, "test.bal", &LintOptions::default());
+    assert!(report.is_clean(), "{:?}", report.diagnostics);
+
+    let source = "function parse() returns int|error {\n\
+                  \x20   return 1;\n\
+                  }\n\
+                  \n\
+                  function main() returns error? {\n\
+                  \x20   string text = check parse();\n\
+                  \x20   int value = parse();\n\
+                  }\n";
+    assert_eq!(
+        diagnostics(source),
+        [
+            (
+                codes::TYPE_MISMATCH,
+                "Type mismatch in initializer: expected string, found int".to_string()
+            ),
+            (
+                codes::TYPE_MISMATCH,
+                "Type mismatch in initializer: expected int, found int|error".to_string()
+            ),
+        ]
+    );
+}
+
+#[test]
+fn optional_return_types_accept_nil() {
+    let source = "function parse() returns int|error {\n\
+                  \x20   return 1;\n\
+                  }\n\
+                  \n\
+                  function early() returns error? {\n\
+                  \x20   int value = check parse();\n\
+                  \x20   if (value > 0) {\n\
+                  \x20       return;\n\
+                  \x20   }\n\
+                  \x20   return ();\n\
+                  }\n\
+                  \n\
+                  function name() returns string? {\n\
+                  \x20   return ();\n\
+                  }\n";
+    let report = lint_source(source, "test.bal", &LintOptions::default());
+    assert!(report.is_clean(), "{:?}", report.diagnostics);
+
+    let source = "function count() returns int {\n\
+                  \x20   return;\n\
+                  }\n\
+                  \n\
+                  function name() returns string? {\n\
+                  \x20   return 1;\n\
+                  }\n";
+    assert_eq!(
+        diagnostics(source),
+        [
+            (
+                codes::MISSING_RETURN_VALUE,
+                "Missing return value: expected int".to_string()
+            ),
+            (
+                codes::TYPE_MISMATCH,
+                "Type mismatch in return: expected string|(), found int".to_string()
+            ),
+        ]
+    );
+}
+
+#[test]
+fn formats_check_like_a_keyword() {
+    let source =
+        "function main() returns error? {int|error r=1;int v=check   r;int w=checkpanic r+1;}\n";
+    let formatted = format_source(source, &FormatOptions::default()).expect("formats");
+    assert_eq!(
+        formatted,
+        "function main() returns error? {\n\
+         \x20   int|error r = 1;\n\
+         \x20   int v = check r;\n\
+         \x20   int w = checkpanic r + 1;\n\
+         }\n"
+    );
+
+    let (tree, _) = syntax_tree(&formatted).expect("lexes");
+    assert_eq!(tree.text(), formatted);
+}
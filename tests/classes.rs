@@ -0,0 +1,480 @@
+use blazelint::ast::{BinaryOp, ClassMember, Expr, ObjectMember, Qualifier, Stmt, TypeDescriptor};
+use blazelint::codes;
+use blazelint::format::{format_source, FormatOptions};
+use blazelint::semantic::{SymbolKind, Type};
+use blazelint::{lint_source, parse, syntax_tree, tokenize, LintOptions};
+
+fn parse_program(source: &str) -> Vec<Stmt> {
+    let tokens = tokenize(source).expect("tokens");
+    let (ast, diagnostics) = parse(&tokens);
+    assert!(diagnostics.is_empty(), "{diagnostics:?}");
+    ast
+}
+
+/// `(code, message)` of every diagnostic for `source`.
+fn diagnostics(source: &str) -> Vec<(&'static str, String)> {
+    lint_source(source, "test.bal", &LintOptions::default())
+        .diagnostics
+        .into_iter()
+        .map(|diagnostic| (diagnostic.code.expect("code"), diagnostic.message))
+        .collect()
+}
+
+const SHAPES: &str = "type Shape object {\n\
+                      \x20   function area() returns float;\n\
+                      };\n\
+                      \n\
+                      public isolated class Circle {\n\
+                      \x20   *Shape;\n\
+                      \x20   private final float radius;\n\
+                      \x20   int calls = 0;\n\
+                      \n\
+                      \x20   public function init(float radius) {\n\
+                      \x20       self.radius = radius;\n\
+                      \x20   }\n\
+                      \n\
+                      \x20   public isolated function area() returns float {\n\
+                      \x20       self.calls += 1;\n\
+                      \x20       return 3.14 * self.radius * self.radius;\n\
+                      \x20   }\n\
+                      }\n";
+
+#[test]
+fn parses_class_members_in_source_order() {
+    let program = parse_program(SHAPES);
+
+    let Stmt::Class {
+        is_public: true,
+        qualifiers,
+        name,
+        members,
+        ..
+    } = &program[1]
+    else {
+        panic!("expected a class: {:?}", program[1]);
+    };
+    assert_eq!(name, "Circle");
+    assert_eq!(qualifiers, &[Qualifier::Isolated]);
+
+    let ClassMember::Inclusion { type_name, .. } = &members[0] else {
+        panic!("expected an inclusion: {:?}", members[0]);
+    };
+    assert_eq!(type_name, "Shape");
+    let ClassMember::Field(radius) = &members[1] else {
+        panic!("expected a field: {:?}", members[1]);
+    };
+    assert_eq!(radius.name, "radius");
+    assert_eq!(radius.qualifiers, [Qualifier::Private, Qualifier::Final]);
+    assert!(matches!(&members[2], ClassMember::Field(calls) if calls.default.is_some()));
+
+    let ClassMember::Method(Stmt::Function {
+        is_public: true,
+        qualifiers,
+        name,
+        ..
+    }) = &members[4]
+    else {
+        panic!("expected a method: {:?}", members[4]);
+    };
+    assert_eq!(name, "area");
+    assert_eq!(qualifiers, &[Qualifier::Isolated]);
+}
+
+#[test]
+fn parses_object_type_descriptors() {
+    let program = parse_program(
+        "type Named object {\n    string name;\n};\n\n\
+         type Account client object {\n    *Named;\n    remote function deposit(int amount) returns int;\n};\n",
+    );
+
+    let Stmt::TypeDefinition {
+        type_desc: TypeDescriptor::Object {
+            qualifiers,
+            members,
+        },
+        ..
+    } = &program[1]
+    else {
+        panic!("expected an object type: {:?}", program[1]);
+    };
+    assert_eq!(qualifiers, &[Qualifier::Client]);
+    assert!(
+        matches!(&members[0], ObjectMember::Inclusion { type_name, .. } if type_name == "Named")
+    );
+    let ObjectMember::Method(deposit) = &members[1] else {
+        panic!("expected a method declaration: {:?}", members[1]);
+    };
+    assert_eq!(deposit.name, "deposit");
+    assert_eq!(deposit.qualifiers, [Qualifier::Remote]);
+    assert_eq!(
+        deposit.return_type,
+        Some(TypeDescriptor::Basic("int".to_string()))
+    );
+}
+
+#[test]
+fn parses_new_expressions_and_field_assignments() {
+    let program = parse_program(
+        "function main() {\n\
+         \x20   var a = new;\n\
+         \x20   var b = new (1, 2);\n\
+         \x20   var c = new http:Client(\"url\");\n\
+         \x20   c.timeout -= 5;\n\
+         }\n",
+    );
+    let Stmt::Function { body, .. } = &program[0] else {
+        panic!("expected a function");
+    };
+    let constructed: Vec<(Option<&str>, usize)> = body[..3]
+        .iter()
+        .map(|stmt| match stmt {
+            Stmt::VarDecl {
+                initializer:
+                    Some(Expr::New {
+                        class_name,
+                        arguments,
+                        ..
+                    }),
+                ..
+            } => (class_name.as_deref(), arguments.len()),
+            other => panic!("expected `new`: {other:?}"),
+        })
+        .collect();
+    assert_eq!(
+        constructed,
+        [(None, 0), (None, 2), (Some("http:Client"), 1)]
+    );
+
+    let Stmt::Expression {
+        expression: Expr::FieldAssign { field, op, .. },
+        ..
+    } = &body[3]
+    else {
+        panic!("expected a field assignment: {:?}", body[3]);
+    };
+    assert_eq!(field, "timeout");
+    assert_eq!(op, &Some(BinaryOp::MinusAssign));
+}
+
+#[test]
+fn classes_are_module_level_only() {
+    let tokens = tokenize("function main() {\n    isolated class C {}\n}\n").expect("tokens");
+    let (_, diagnostics) = parse(&tokens);
+    assert_eq!(diagnostics[0].code, Some(codes::UNEXPECTED_TOKEN));
+    assert_eq!(
+        diagnostics[0].message,
+        "Class definitions are only allowed at module level"
+    );
+}
+
+#[test]
+fn self_fields_and_methods_resolve_in_methods() {
+    let source = "class Counter {\n\
+                  \x20   int count = 0;\n\
+                  \n\
+                  \x20   function increment() returns int {\n\
+                  \x20       self.count += 1;\n\
+                  \x20       self.cuont = 2;\n\
+                  \x20       self.count = \"many\";\n\
+                  \x20       return self.current();\n\
+                  \x20   }\n\
+                  \n\
+                  \x20   function current() returns int {\n\
+                  \x20       return self.count;\n\
+                  \x20   }\n\
+                  }\n\
+                  \n\
+                  function main() {\n\
+                  \x20   Counter counter = new;\n\
+                  \x20   int total = counter.increment();\n\
+                  \x20   counter.reset();\n\
+                  }\n";
+    assert_eq!(
+        diagnostics(source),
+        [
+            (
+                codes::UNKNOWN_FIELD,
+                "Class Counter has no field 'cuont'".to_string()
+            ),
+            (
+                codes::TYPE_MISMATCH,
+                "Type mismatch in assignment to field 'count': expected int, found string"
+                    .to_string()
+            ),
+            (
+                codes::UNKNOWN_METHOD,
+                "Class Counter has no method 'reset'".to_string()
+            ),
+        ]
+    );
+}
+
+#[test]
+fn final_readonly_and_private_fields_are_enforced() {
+    let source = "class Holder {\n\
+                  \x20   final int v;\n\
+                  \x20   private int secret = 0;\n\
+                  \n\
+                  \x20   function init(int v) {\n\
+                  \x20       self.v = v;\n\
+                  \x20       self.secret = 1;\n\
+                  \x20   }\n\
+                  \n\
+                  \x20   function bump() {\n\
+                  \x20       self.v = 2;\n\
+                  \x20       self.secret += 1;\n\
+                  \x20   }\n\
+                  }\n\
+                  \n\
+                  readonly class Point {\n\
+                  \x20   int x;\n\
+                  \n\
+                  \x20   function init() {\n\
+                  \x20       self.x = 0;\n\
+                  \x20   }\n\
+                  \n\
+                  \x20   function move() {\n\
+                  \x20       self.x = 1;\n\
+                  \x20   }\n\
+                  }\n\
+                  \n\
+                  function main() {\n\
+                  \x20   Holder h = new (1);\n\
+                  \x20   h.v = 5;\n\
+                  \x20   int s = h.secret;\n\
+                  }\n";
+    assert_eq!(
+        diagnostics(source),
+        [
+            (
+                codes::ASSIGN_TO_IMMUTABLE,
+                "Cannot assign to final field 'v'".to_string()
+            ),
+            (
+                codes::ASSIGN_TO_IMMUTABLE,
+                "Cannot assign to field 'x' of readonly class Point".to_string()
+            ),
+            (
+                codes::ASSIGN_TO_IMMUTABLE,
+                "Cannot assign to final field 'v'".to_string()
+            ),
+            (
+                codes::PRIVATE_FIELD,
+                "Field 'secret' of Holder is private".to_string()
+            ),
+        ]
+    );
+
+    let report = lint_source(source, "test.bal", &LintOptions::default());
+    let labels: Vec<(&str, &str)> = report
+        .diagnostics
+        .iter()
+        .map(|diagnostic| {
+            let label = &diagnostic.secondary[0];
+            (&source[label.span.clone()], label.message.as_str())
+        })
+        .collect();
+    assert_eq!(
+        labels,
+        [
+            ("v", "declared final here"),
+            ("x", "field declared here"),
+            ("v", "declared final here"),
+            ("secret", "declared private here"),
+        ]
+    );
+}
+
+#[test]
+fn classes_and_methods_are_recorded_in_the_model() {
+    let report = lint_source(SHAPES, "test.bal", &LintOptions::default());
+    assert!(report.is_clean(), "{:?}", report.diagnostics);
+
+    let symbols = report.semantic.symbols();
+    let circle = symbols
+        .iter()
+        .find(|symbol| symbol.name == "Circle")
+        .expect("class symbol");
+    assert_eq!(circle.kind, SymbolKind::Class);
+    let Type::Object(object) = &circle.ty else {
+        panic!("expected an object type: {:?}", circle.ty);
+    };
+    assert!(object.is_class);
+    assert_eq!(
+        object.method("area").map(|method| &method.return_type),
+        Some(&Type::Float)
+    );
+
+    let methods: Vec<&str> = symbols
+        .iter()
+        .filter(|symbol| symbol.kind == SymbolKind::Method)
+        .map(|symbol| symbol.name.as_str())
+        .collect();
+    assert_eq!(methods, ["init", "area"]);
+    let receivers = symbols
+        .iter()
+        .filter(|symbol| symbol.name == "self" && symbol.ty == circle.ty)
+        .count();
+    assert_eq!(receivers, 2);
+}
+
+#[test]
+fn object_types_admit_objects_with_their_members() {
+    let source = format!(
+        "{SHAPES}\n\
+         class Square {{\n\
+         \x20   float side = 1.0;\n\
+         }}\n\
+         \n\
+         function main() {{\n\
+         \x20   Circle circle = new (2.0);\n\
+         \x20   Shape shape = circle;\n\
+         \x20   float area = shape.area();\n\
+         \x20   Shape square = new Square();\n\
+         \x20   Circle other = new Square();\n\
+         }}\n"
+    );
+    assert_eq!(
+        diagnostics(&source),
+        [
+            (
+                codes::TYPE_MISMATCH,
+                "Type mismatch in initializer: expected Shape, found Square".to_string()
+            ),
+            (
+                codes::TYPE_MISMATCH,
+                "Type mismatch in initializer: expected Circle, found Square".to_string()
+            ),
+        ]
+    );
+}
+
+#[test]
+fn new_and_inclusions_name_known_types() {
+    let source = "type Id int;\n\
+                  \n\
+                  class Entity {\n\
+                  \x20   *Id;\n\
+                  \x20   *Missing;\n\
+                  }\n\
+                  \n\
+                  Entity entity = new Entty();\n\
+                  Id id = new Id();\n";
+    assert_eq!(
+        diagnostics(source),
+        [
+            (
+                codes::TYPE_MISMATCH,
+                "Type inclusion expects an object type, found int".to_string()
+            ),
+            (codes::UNKNOWN_TYPE, "Unknown type 'Missing'".to_string()),
+            (codes::UNKNOWN_TYPE, "Unknown class 'Entty'".to_string()),
+            (
+                codes::TYPE_MISMATCH,
+                "Type mismatch in initializer: expected Entity, found Entty".to_string()
+            ),
+            (
+                codes::UNKNOWN_TYPE,
+                "Cannot construct type int with 'new': it is not a class".to_string()
+            ),
+            (
+                codes::TYPE_MISMATCH,
+                "Type mismatch in initializer: expected int, found Id".to_string()
+            ),
+        ]
+    );
+}
+
+#[test]
+fn classes_define_the_methods_they_include() {
+    let source = "type Shape object {\n\
+                  \x20   function area() returns int;\n\
+                  \x20   function name() returns string;\n\
+                  };\n\
+                  \n\
+                  class Square {\n\
+                  \x20   *Shape;\n\
+                  \n\
+                  \x20   function area() returns int {\n\
+                  \x20       return 4;\n\
+                  \x20   }\n\
+                  }\n\
+                  \n\
+                  type Named object {\n\
+                  \x20   *Shape;\n\
+                  };\n";
+    assert_eq!(
+        diagnostics(source),
+        [(
+            codes::MISSING_METHOD,
+            "Class Square does not define method 'name' of included type Shape".to_string()
+        )]
+    );
+}
+
+#[test]
+fn new_arguments_are_checked_against_init() {
+    let source = "class Point {\n\
+                  \x20   int x;\n\
+                  \x20   int y;\n\
+                  \n\
+                  \x20   function init(int x, int y) {\n\
+                  \x20       self.x = x;\n\
+                  \x20       self.y = y;\n\
+                  \x20   }\n\
+                  }\n\
+                  \n\
+                  class Origin {\n\
+                  }\n\
+                  \n\
+                  function main() {\n\
+                  \x20   Point ok = new Point(1, 2);\n\
+                  \x20   Point inferred = new (1, 2);\n\
+                  \x20   Point extra = new Point(1, 2, 3);\n\
+                  \x20   Point wrong = new (1, \"two\");\n\
+                  \x20   Origin origin = new Origin(0);\n\
+                  }\n";
+    assert_eq!(
+        diagnostics(source),
+        [
+            (
+                codes::ARGUMENT_COUNT,
+                "'init' of Point takes 2 arguments, found 3".to_string()
+            ),
+            (
+                codes::TYPE_MISMATCH,
+                "Type mismatch in argument 2 of 'init': expected int, found string".to_string()
+            ),
+            (
+                codes::ARGUMENT_COUNT,
+                "'init' of Origin takes 0 arguments, found 1".to_string()
+            ),
+        ]
+    );
+}
+
+#[test]
+fn formats_one_member_per_line() {
+    let source = "type Shape object{function area() returns float;};\n\
+                  isolated class Circle{*Shape;private float radius=1.0;\
+                  function area() returns float{return self.radius*self.radius;}}\n\
+                  Circle c=new (2.0);\n";
+    let formatted = format_source(source, &FormatOptions::default()).expect("formats");
+    assert_eq!(
+        formatted,
+        "type Shape object {\n\
+         \x20   function area() returns float;\n\
+         };\n\
+         isolated class Circle {\n\
+         \x20   *Shape;\n\
+         \x20   private float radius = 1.0;\n\
+         \x20   function area() returns float {\n\
+         \x20       return self.radius * self.radius;\n\
+         \x20   }\n\
+         }\n\
+         Circle c = new (2.0);\n"
+    );
+
+    let (tree, _) = syntax_tree(&formatted).expect("lexes");
+    assert_eq!(tree.text(), formatted);
+}
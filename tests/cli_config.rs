@@ -0,0 +1,156 @@
+mod common;
+
+use blazelint::config::{Config, ConfigValue};
+use blazelint::errors::Severity;
+use blazelint::linter::RuleSet;
+use common::{run_cli, stdout, write};
+
+const LONG_LINE: &str = "string s = \"0123456789012345678901234567890123456789\";\n";
+
+#[test]
+fn parses_rule_tables_and_shorthand() {
+    let (config, diagnostics) = Config::parse(
+        "# comment\n[rules]\ncamel_case = \"off\"\nconstant-case = \"warning\"\n\n\
+         [rules.line_length]\nseverity = \"info\" # trailing\nmax_length = 80\n",
+    );
+    assert!(diagnostics.is_empty(), "{diagnostics:?}");
+    assert!(!config.rules["camel_case"].enabled);
+    assert_eq!(
+        config.rules["constant-case"].severity,
+        Some(Severity::Warning)
+    );
+    let line_length = &config.rules["line_length"];
+    assert_eq!(line_length.severity, Some(Severity::Info));
+    assert_eq!(
+        line_length.options["max_length"].value,
+        ConfigValue::Integer(80)
+    );
+}
+
+#[test]
+fn reports_syntax_errors_with_spans() {
+    let source = "[rules]\ncamel_case = maybe\n[linting]\n";
+    let (_, diagnostics) = Config::parse(source);
+    assert_eq!(diagnostics.len(), 2);
+    assert_eq!(&source[diagnostics[0].span.clone()], "maybe");
+    assert!(diagnostics[1]
+        .message
+        .contains("Unknown section '[linting]'"));
+}
+
+#[test]
+fn rule_table_names_point_at_the_name_in_the_header() {
+    let source = "[rules.rules]\n[ rules. les ]\n";
+    let (config, diagnostics) = Config::parse(source);
+    assert!(diagnostics.is_empty(), "{diagnostics:?}");
+    assert_eq!(config.rules["rules"].name_span, 7..12);
+    assert_eq!(config.rules["les"].name_span, 23..26);
+}
+
+#[test]
+fn rule_set_reports_unknown_rules_and_options() {
+    let source = "[rules]\nno_such_rule = true\n[rules.line_length]\nmax_length = -3\nwidth = 4\n";
+    let (config, _) = Config::parse(source);
+    let (rules, diagnostics) = RuleSet::from_config(&config);
+
+    let messages: Vec<_> = diagnostics.iter().map(|d| d.message.as_str()).collect();
+    assert!(messages.iter().any(|m| m.contains("'max_length'")));
+    assert!(messages.contains(&"Unknown option 'width' for rule 'line_length'"));
+    assert!(messages.contains(&"Unknown rule 'no_such_rule'"));
+    assert_eq!(rules.names().len(), 3);
+}
+
+#[test]
+fn discovered_config_disables_and_tunes_rules() {
+    let dir = tempfile::tempdir().expect("temp dir");
+    write(
+        dir.path(),
+        ".blazerc",
+        "[rules]\ncamel_case = false\n\n[rules.line_length]\nmax_length = 40\n",
+    );
+    write(
+        dir.path(),
+        "modules/a/a.bal",
+        &format!("int bad_name = 1;\n{LONG_LINE}"),
+    );
+
+    let output = run_cli(&["modules/a/a.bal"], dir.path());
+    let out = stdout(&output);
+    assert!(!out.contains("camelCase"), "{out}");
+    assert!(out.contains("linter warning[BL4003]: Lines should not exceed 40 characters."));
+}
+
+#[test]
+fn severity_override_is_rendered() {
+    let dir = tempfile::tempdir().expect("temp dir");
+    write(
+        dir.path(),
+        ".blazerc",
+        "[rules]\ncamel_case = \"warning\"\n",
+    );
+    write(dir.path(), "a.bal", "int bad_name = 1;\n");
+
+    let out = stdout(&run_cli(&["a.bal"], dir.path()));
+    assert!(out.contains("linter warning[BL4001]: Variable \"bad_name\" is not in camelCase."));
+}
+
+#[test]
+fn explicit_config_overrides_discovery() {
+    let dir = tempfile::tempdir().expect("temp dir");
+    write(dir.path(), ".blazerc", "[rules]\ncamel_case = true\n");
+    write(dir.path(), "ci.blazerc", "[rules]\ncamel_case = \"off\"\n");
+    write(dir.path(), "a.bal", "int bad_name = 1;\n");
+
+    let output = run_cli(&["--config", "ci.blazerc", "a.bal"], dir.path());
+    assert!(output.status.success(), "{}", stdout(&output));
+}
+
+#[test]
+fn invalid_config_is_reported_and_fails() {
+    let dir = tempfile::tempdir().expect("temp dir");
+    write(dir.path(), ".blazerc", "[rules]\ncamelcase = false\n");
+    write(dir.path(), "a.bal", "int goodName = 1;\n");
+
+    let output = run_cli(&["a.bal"], dir.path());
+    let out = stdout(&output);
+    assert!(!output.status.success());
+    assert!(
+        out.contains("config error[BL5001]: Unknown rule 'camelcase'"),
+        "{out}"
+    );
+    assert!(out.contains(".blazerc:2:1-2:10"), "{out}");
+    assert!(out.contains("note: available rules: camel_case"), "{out}");
+}
+
+#[test]
+fn deprecated_rule_names_still_apply_with_a_warning() {
+    let dir = tempfile::tempdir().expect("temp dir");
+    write(
+        dir.path(),
+        ".blazerc",
+        "[rules.constant-case]\nseverity = \"error\"\n",
+    );
+    write(
+        dir.path(),
+        "a.bal",
+        "// blazelint-disable-next-line constant-case\nconst lowerConst = 1;\nconst otherConst = 2;\n",
+    );
+
+    let output = run_cli(&["a.bal"], dir.path());
+    let out = stdout(&output);
+    assert!(
+        out.contains(
+            "config warning[BL5001]: Rule 'constant-case' has been renamed to 'constant_case'"
+        ),
+        "{out}"
+    );
+    assert!(
+        out.contains(
+            "suppression warning[BL5003]: Rule 'constant-case' has been renamed to 'constant_case'"
+        ),
+        "{out}"
+    );
+    assert!(!out.contains("lowerConst"), "{out}");
+    assert!(out.contains("linter error[BL4002]"), "{out}");
+    assert!(!output.status.success());
+}
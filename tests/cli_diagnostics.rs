@@ -6,20 +6,35 @@ use std::process::Output;
 const COMPREHENSIVE_TEST: &str = include_str!("test-bal-files/comprehensive_test.bal");
 
 fn run_cli(source: &str) -> Output {
+    run_cli_with(source, &[])
+}
+
+/// Lints `source` with extra command-line arguments placed before the path.
+fn run_cli_with(source: &str, args: &[&str]) -> Output {
     let file = tempfile::NamedTempFile::new().expect("create temp file");
     fs::write(file.path(), source).expect("write temp source");
     Command::cargo_bin("blazelint")
         .expect("binary")
+        .args(args)
         .arg(file.path())
         .output()
         .expect("run blazelint")
 }
 
+/// Lints `source`, dumping the parsed AST.
+fn run_cli_ast(source: &str) -> Output {
+    run_cli_with(source, &["--emit", "ast"])
+}
+
+/// Lints `source`, dumping the token stream.
+fn run_cli_tokens(source: &str) -> Output {
+    run_cli_with(source, &["--emit", "tokens"])
+}
+
 fn stdout(output: &Output) -> String {
     String::from_utf8_lossy(&output.stdout).into_owned()
 }
 
-#[allow(dead_code)]
 fn stderr(output: &Output) -> String {
     String::from_utf8_lossy(&output.stderr).into_owned()
 }
@@ -30,25 +45,25 @@ fn stderr(output: &Output) -> String {
 
 #[test]
 fn comprehensive_test_passes() {
-    let output = run_cli(COMPREHENSIVE_TEST);
+    let output = run_cli_with(COMPREHENSIVE_TEST, &["--emit", "tokens", "--emit", "ast"]);
     let out = stdout(&output);
 
     // Should complete all stages
-    assert!(out.contains("Lexing complete!"), "Lexing should complete");
-    assert!(out.contains("-- AST --"), "Should generate AST");
+    assert!(out.contains("--- Tokens ("), "Lexing should complete");
+    assert!(out.contains("--- AST ("), "Should generate AST");
 
     // Should have no errors (check for the actual error format, not comments)
     assert!(
-        !out.contains("parser error:"),
+        !out.contains("parser error["),
         "Should have no parser errors"
     );
     assert!(
-        !out.contains("semantic error:"),
+        !out.contains("semantic error["),
         "Should have no semantic errors"
     );
     assert!(
-        !out.contains("linter error:"),
-        "Should have no linter errors"
+        !out.contains("linter error[") && !out.contains("linter warning["),
+        "Should have no linter findings"
     );
 
     assert!(
@@ -63,7 +78,7 @@ fn comprehensive_test_passes() {
 
 #[test]
 fn lexer_tokenizes_imports() {
-    let output = run_cli("import ballerina/io;");
+    let output = run_cli_tokens("import ballerina/io;");
     let out = stdout(&output);
     assert!(out.contains("Token: Import"));
     assert!(out.contains("Token: Identifier(\"ballerina\")"));
@@ -74,7 +89,7 @@ fn lexer_tokenizes_imports() {
 #[test]
 fn lexer_tokenizes_all_operators() {
     let code = "int x = 5 + 3 - 2 * 4 / 2 % 3;";
-    let output = run_cli(code);
+    let output = run_cli_tokens(code);
     let out = stdout(&output);
     assert!(out.contains("Token: Plus"));
     assert!(out.contains("Token: Minus"));
@@ -86,7 +101,7 @@ fn lexer_tokenizes_all_operators() {
 #[test]
 fn lexer_tokenizes_bitwise_operators() {
     let code = "int x = 5 & 3 | 2 ^ 1; int y = ~x; int z = 4 << 2 >> 1;";
-    let output = run_cli(code);
+    let output = run_cli_tokens(code);
     let out = stdout(&output);
     assert!(out.contains("Token: Amp"));
     assert!(out.contains("Token: Pipe"));
@@ -99,7 +114,7 @@ fn lexer_tokenizes_bitwise_operators() {
 #[test]
 fn lexer_tokenizes_keywords() {
     let code = "function main() { if (true) { while (false) { } } }";
-    let output = run_cli(code);
+    let output = run_cli_tokens(code);
     let out = stdout(&output);
     assert!(out.contains("Token: Function"));
     assert!(out.contains("Token: If"));
@@ -113,7 +128,7 @@ fn lexer_reports_unterminated_string() {
     let output = run_cli("var a = \"unterminated;");
     assert!(!output.status.success());
     let out = stdout(&output);
-    assert!(out.contains("lexer error: Unterminated string literal"));
+    assert!(out.contains("lexer error[BL1002]: Unterminated string literal"));
     assert!(out.contains("^"));
 }
 
@@ -122,7 +137,7 @@ fn lexer_reports_unterminated_block_comment() {
     let output = run_cli("var a = 1; /* unterminated block comment");
     assert!(!output.status.success());
     let out = stdout(&output);
-    assert!(out.contains("lexer error: Unterminated block comment"));
+    assert!(out.contains("lexer error[BL1001]: Unterminated block comment"));
 }
 
 #[test]
@@ -131,7 +146,7 @@ fn parser_reports_unexpected_bitwise_and() {
     assert!(!output.status.success());
     let out = stdout(&output);
     // Now that we support bitwise operators, single & is tokenized but creates parser error
-    assert!(out.contains("parser error:"));
+    assert!(out.contains("parser error["));
 }
 
 #[test]
@@ -139,7 +154,7 @@ fn lexer_reports_malformed_exponent() {
     let output = run_cli("var a = 1e+;");
     assert!(!output.status.success());
     let out = stdout(&output);
-    assert!(out.contains("lexer error: Malformed exponent in number literal"));
+    assert!(out.contains("lexer error[BL1003]: Malformed exponent in number literal"));
 }
 
 #[test]
@@ -147,7 +162,7 @@ fn lexer_reports_unexpected_character() {
     let output = run_cli("var a = 1 @;");
     assert!(!output.status.success());
     let out = stdout(&output);
-    assert!(out.contains("lexer error: Unexpected character: '@'"));
+    assert!(out.contains("lexer error[BL1004]: Unexpected character: '@'"));
 }
 
 // ============================================================================
@@ -157,7 +172,7 @@ fn lexer_reports_unexpected_character() {
 #[test]
 fn parser_handles_function_declarations() {
     let code = "function add(int a, int b) returns int { return a + b; }";
-    let output = run_cli(code);
+    let output = run_cli_ast(code);
     let out = stdout(&output);
     assert!(out.contains("Function {"));
     assert!(out.contains("name: \"add\""));
@@ -177,7 +192,7 @@ fn parser_handles_if_else_if_else() {
             }
         }
     "#;
-    let output = run_cli(code);
+    let output = run_cli_ast(code);
     let out = stdout(&output);
     assert!(out.contains("If {"));
     assert!(!out.contains("parser error"));
@@ -186,7 +201,7 @@ fn parser_handles_if_else_if_else() {
 #[test]
 fn parser_handles_while_loops() {
     let code = "function test() { int i = 0; while (i < 5) { i += 1; } }";
-    let output = run_cli(code);
+    let output = run_cli_ast(code);
     let out = stdout(&output);
     assert!(out.contains("While {"));
     assert!(!out.contains("parser error"));
@@ -195,7 +210,7 @@ fn parser_handles_while_loops() {
 #[test]
 fn parser_handles_foreach_loops() {
     let code = "function test() { int[] nums = [1,2,3]; foreach int n in nums { int x = n; } }";
-    let output = run_cli(code);
+    let output = run_cli_ast(code);
     let out = stdout(&output);
     assert!(out.contains("Foreach {"));
     assert!(!out.contains("parser error"));
@@ -204,7 +219,7 @@ fn parser_handles_foreach_loops() {
 #[test]
 fn parser_handles_ternary_operator() {
     let code = "function test(int x) returns int { return (x > 0) ? 1 : 0; }";
-    let output = run_cli(code);
+    let output = run_cli_ast(code);
     let out = stdout(&output);
     assert!(out.contains("Ternary {"));
     assert!(!out.contains("parser error"));
@@ -218,7 +233,7 @@ fn parser_handles_arrays_and_maps() {
             map<string> m = {key: "value"};
         }
     "#;
-    let output = run_cli(code);
+    let output = run_cli_ast(code);
     let out = stdout(&output);
     assert!(out.contains("ArrayLiteral {"));
     assert!(out.contains("MapLiteral {"));
@@ -230,7 +245,7 @@ fn parser_reports_missing_semicolon() {
     let output = run_cli("int a = 1\nint b = 2;");
     assert!(!output.status.success());
     let out = stdout(&output);
-    assert!(out.contains("parser error: Expected ';' after variable declaration"));
+    assert!(out.contains("parser error[BL2001]: Expected ';' after variable declaration"));
     assert!(out.contains("note: expected: ';'"));
 }
 
@@ -238,13 +253,13 @@ fn parser_reports_missing_semicolon() {
 fn parser_recovers_from_multiple_errors() {
     // Test error recovery - should report parser errors but continue
     let code = "int a = 1\nint b = 2\nint c = 3;";
-    let output = run_cli(code);
+    let output = run_cli_tokens(code);
     assert!(!output.status.success());
     let out = stdout(&output);
     // Should catch semicolon error
-    assert!(out.contains("parser error:"));
+    assert!(out.contains("parser error["));
     // Error recovery allows parser to continue
-    assert!(out.contains("Lexing complete!"));
+    assert!(out.contains("Token: Int"));
 }
 
 #[test]
@@ -252,7 +267,7 @@ fn parser_reports_invalid_assignment_target() {
     let output = run_cli("int a = 1; (a + 1) = 3;");
     assert!(!output.status.success());
     let out = stdout(&output);
-    assert!(out.contains("parser error: Invalid assignment target"));
+    assert!(out.contains("parser error[BL2004]: Invalid assignment target"));
 }
 
 #[test]
@@ -260,7 +275,7 @@ fn parser_reports_missing_closing_paren() {
     let output = run_cli("int a = (1 + 2;");
     assert!(!output.status.success());
     let out = stdout(&output);
-    assert!(out.contains("parser error: Expected ')' after expression"));
+    assert!(out.contains("parser error[BL2001]: Expected ')' after expression"));
 }
 
 #[test]
@@ -268,7 +283,7 @@ fn parser_reports_unexpected_eof_in_block() {
     let output = run_cli("function foo() { int a = 1;");
     assert!(!output.status.success());
     let out = stdout(&output);
-    assert!(out.contains("parser error: Expected '}' at end of block"));
+    assert!(out.contains("parser error[BL2001]: Expected '}' at end of block"));
     assert!(out.contains("note: expected: '}'"));
 }
 
@@ -278,7 +293,7 @@ fn parser_reports_const_with_type() {
     let output = run_cli(code);
     assert!(!output.status.success());
     let out = stdout(&output);
-    assert!(out.contains("parser error: const declarations cannot have a type annotation"));
+    assert!(out.contains("parser error[BL2005]: const declarations cannot have a type annotation"));
 }
 
 // ============================================================================
@@ -291,7 +306,7 @@ fn semantic_reports_type_mismatch_in_assignment() {
     let output = run_cli(code);
     assert!(!output.status.success());
     let out = stdout(&output);
-    assert!(out.contains("semantic error: Type mismatch in assignment"));
+    assert!(out.contains("semantic error[BL3008]: Type mismatch in assignment"));
 }
 
 #[test]
@@ -300,7 +315,7 @@ fn semantic_reports_final_reassignment() {
     let output = run_cli(code);
     assert!(!output.status.success());
     let out = stdout(&output);
-    assert!(out.contains("semantic error: Cannot assign to final variable"));
+    assert!(out.contains("semantic error[BL3007]: Cannot assign to final variable"));
 }
 
 #[test]
@@ -309,7 +324,7 @@ fn semantic_reports_missing_return_value() {
     let output = run_cli(code);
     assert!(!output.status.success());
     let out = stdout(&output);
-    assert!(out.contains("semantic error: Missing return value"));
+    assert!(out.contains("semantic error[BL3009]: Missing return value"));
 }
 
 #[test]
@@ -318,7 +333,7 @@ fn semantic_reports_const_reassignment() {
     let output = run_cli(code);
     assert!(!output.status.success());
     let out = stdout(&output);
-    assert!(out.contains("semantic error: Cannot assign to constant"));
+    assert!(out.contains("semantic error[BL3007]: Cannot assign to constant"));
 }
 
 // ============================================================================
@@ -329,30 +344,33 @@ fn semantic_reports_const_reassignment() {
 fn linter_reports_line_length() {
     let code = "string long_line = \"this is a very long line that is longer than 120 characters just to test the line length rule in the linter, so that it will trigger the error and we can see the output of the linter\";";
     let output = run_cli(code);
-    assert!(!output.status.success());
+    assert!(
+        output.status.success(),
+        "Style warnings should not fail the run"
+    );
     let out = stdout(&output);
-    assert!(out.contains("linter error: Lines should not exceed 120 characters."));
-    assert!(out.contains("linter error: Variable \"long_line\" is not in camelCase."));
+    assert!(out.contains("linter warning[BL4003]: Lines should not exceed 120 characters."));
+    assert!(out.contains("linter warning[BL4001]: Variable \"long_line\" is not in camelCase."));
 }
 
 #[test]
 fn linter_reports_camel_case() {
     let code = "int a_b = 1;";
     let output = run_cli(code);
-    assert!(!output.status.success());
+    assert!(output.status.success());
     let out = stdout(&output);
-    assert!(out.contains("linter error: Variable \"a_b\" is not in camelCase."));
+    assert!(out.contains("linter warning[BL4001]: Variable \"a_b\" is not in camelCase."));
 }
 
 #[test]
 fn linter_reports_constant_case() {
     let code = "const badConstant = 100;";
     let output = run_cli(code);
-    assert!(!output.status.success());
+    assert!(output.status.success());
     let out = stdout(&output);
-    assert!(
-        out.contains("linter error: Constant variable names should be in SCREAMING_SNAKE_CASE.")
-    );
+    assert!(out.contains(
+        "linter warning[BL4002]: Constant variable names should be in SCREAMING_SNAKE_CASE."
+    ));
 }
 
 #[test]
@@ -360,7 +378,7 @@ fn linter_accepts_valid_camel_case() {
     let code = "int myVariable = 42; string userName = \"test\";";
     let output = run_cli(code);
     let out = stdout(&output);
-    assert!(!out.contains("linter error: Variable"));
+    assert!(!out.contains("[BL4001]"), "{out}");
 }
 
 #[test]
@@ -368,10 +386,42 @@ fn linter_accepts_valid_constant_case() {
     let code = "const MAX_SIZE = 100; const DEFAULT_NAME = \"test\";";
     let output = run_cli(code);
     let out = stdout(&output);
-    assert!(!out.contains("linter error: Constant"));
+    assert!(!out.contains("[BL4002]"), "{out}");
     assert!(output.status.success(), "Valid constants should pass");
 }
 
+#[test]
+fn deny_warnings_fails_on_style_findings() {
+    let file = tempfile::NamedTempFile::new().expect("create temp file");
+    fs::write(file.path(), "int a_b = 1;").expect("write temp source");
+    let output = Command::cargo_bin("blazelint")
+        .expect("binary")
+        .arg("--deny-warnings")
+        .arg(file.path())
+        .output()
+        .expect("run blazelint");
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("warnings are denied"));
+}
+
+#[test]
+fn max_warnings_limits_the_warning_count() {
+    let file = tempfile::NamedTempFile::new().expect("create temp file");
+    fs::write(file.path(), "int a_b = 1; int c_d = 2;").expect("write temp source");
+    let run = |limit: &str| {
+        Command::cargo_bin("blazelint")
+            .expect("binary")
+            .args(["--max-warnings", limit])
+            .arg(file.path())
+            .output()
+            .expect("run blazelint")
+    };
+    assert!(run("2").status.success());
+    let output = run("1");
+    assert!(!output.status.success());
+    assert!(stderr(&output).contains("2 warning(s) exceed the limit of 1"));
+}
+
 // ============================================================================
 // ERROR RECOVERY TESTS
 // ============================================================================
@@ -412,7 +462,10 @@ fn error_recovery_runs_all_stages() {
         out.contains("semantic error"),
         "Should have semantic errors"
     );
-    assert!(out.contains("linter error"), "Should have linter errors");
+    assert!(
+        out.contains("linter warning"),
+        "Should have linter warnings"
+    );
 }
 
 #[test]
@@ -421,15 +474,15 @@ fn error_recovery_still_parses_valid_code() {
         int badSyntax = 
         function goodFunc() returns int { return 42; }
     "#;
-    let output = run_cli(code);
+    let output = run_cli_with(code, &["--emit", "ast"]);
     let out = stdout(&output);
 
     // Should have parser error for incomplete expression
-    assert!(out.contains("parser error:"));
+    assert!(out.contains("parser error["));
 
     // Should still generate AST output (even if some nodes are skipped)
     assert!(
-        out.contains("-- AST --"),
+        out.contains("--- AST ("),
         "Should still generate AST despite errors"
     );
 }
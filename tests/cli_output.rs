@@ -0,0 +1,323 @@
+mod common;
+
+use blazelint::format::DEFAULT_MAX_WIDTH;
+use blazelint::json::JsonValue;
+use blazelint::linter::default_rules;
+use blazelint::report::{json_report, sarif_report, FileDiagnostics};
+use blazelint::{lint_source, LintOptions};
+use common::{run_cli, stdout, write};
+use std::fs;
+
+#[test]
+fn json_report_describes_each_diagnostic() {
+    let source = "int x = 1;\nint bad_name = 2;\n";
+    let report = lint_source(source, "a.bal", &LintOptions::default());
+    let json = json_report(
+        &[FileDiagnostics {
+            file_name: "a.bal",
+            source,
+            diagnostics: &report.diagnostics,
+        }],
+        1,
+    )
+    .to_string();
+
+    assert!(json.starts_with("{\"version\":1,"), "{json}");
+    assert!(json.contains("\"file\":\"a.bal\""), "{json}");
+    assert!(json.contains("\"rule\":\"camel_case\""), "{json}");
+    assert!(
+        json.contains("\"kind\":\"linter\",\"severity\":\"warning\""),
+        "{json}"
+    );
+    assert!(
+        json.contains("\"span\":{\"start\":15,\"end\":23}"),
+        "{json}"
+    );
+    assert!(
+        json.contains(
+            "\"range\":{\"start\":{\"line\":2,\"column\":5},\"end\":{\"line\":2,\"column\":13}}"
+        ),
+        "{json}"
+    );
+    assert!(
+        json.contains(
+            "\"summary\":{\"files\":1,\"errors\":0,\"warnings\":1,\"infos\":0,\"hints\":0}"
+        ),
+        "{json}"
+    );
+}
+
+#[test]
+fn json_columns_count_characters_like_sarif() {
+    let source = "string s = \"é\"; int bad_name = 2;\n";
+    let report = lint_source(source, "a.bal", &LintOptions::default());
+    let json = json_report(
+        &[FileDiagnostics {
+            file_name: "a.bal",
+            source,
+            diagnostics: &report.diagnostics,
+        }],
+        1,
+    )
+    .to_string();
+
+    // The two-byte `é` moves the byte offsets but counts as one column.
+    assert!(
+        json.contains("\"span\":{\"start\":21,\"end\":29}"),
+        "{json}"
+    );
+    assert!(
+        json.contains(
+            "\"range\":{\"start\":{\"line\":1,\"column\":21},\"end\":{\"line\":1,\"column\":29}}"
+        ),
+        "{json}"
+    );
+}
+
+#[test]
+fn json_format_writes_only_the_document() {
+    let dir = tempfile::tempdir().expect("temp dir");
+    write(dir.path(), "a.bal", "int bad_name = 1;\n");
+    write(dir.path(), "b.bal", "int x = \"text\";\n");
+
+    let output = run_cli(&["--format", "json", "."], dir.path());
+    let out = stdout(&output);
+    assert!(!output.status.success());
+    assert!(out.starts_with("{\n  \"version\": 1,"), "{out}");
+    assert!(out.trim_end().ends_with('}'), "{out}");
+    assert!(!out.contains("Ballerina Linter"), "{out}");
+    assert!(!out.contains("Token:"), "{out}");
+    assert!(out.contains("\"rule\": \"camel_case\""), "{out}");
+    assert!(out.contains("\"kind\": \"semantic\""), "{out}");
+    assert!(out.contains("\"rule\": null"), "{out}");
+    assert!(out.contains("\"files\": 2"), "{out}");
+}
+
+#[test]
+fn json_format_reports_config_problems() {
+    let dir = tempfile::tempdir().expect("temp dir");
+    write(dir.path(), ".blazerc", "[rules]\ncamelcase = false\n");
+    write(dir.path(), "a.bal", "int goodName = 1;\n");
+
+    let output = run_cli(&["--format=json", "a.bal"], dir.path());
+    let out = stdout(&output);
+    assert!(!output.status.success());
+    assert!(out.contains("\"kind\": \"config\""), "{out}");
+    assert!(
+        out.contains("\"message\": \"Unknown rule 'camelcase'\""),
+        "{out}"
+    );
+    assert!(out.contains("\"notes\": [\n"), "{out}");
+}
+
+#[test]
+fn clean_json_run_has_no_diagnostics() {
+    let dir = tempfile::tempdir().expect("temp dir");
+    write(dir.path(), "a.bal", "int goodName = 1;\n");
+
+    let output = run_cli(&["--format", "json", "a.bal"], dir.path());
+    let out = stdout(&output);
+    assert!(output.status.success(), "{out}");
+    assert!(out.contains("\"diagnostics\": [],"), "{out}");
+}
+
+#[test]
+fn unknown_format_is_a_usage_error() {
+    let dir = tempfile::tempdir().expect("temp dir");
+    let output = run_cli(&["--format", "xml", "a.bal"], dir.path());
+    let err = String::from_utf8_lossy(&output.stderr);
+    assert!(!output.status.success());
+    assert!(
+        err.contains("option '--format' expects 'text', 'json', or 'sarif', found 'xml'"),
+        "{err}"
+    );
+}
+
+#[test]
+fn sarif_report_lists_rules_and_regions() {
+    let source = "string s = \"é\"; int bad_name = 2;\n";
+    let report = lint_source(source, "src/a b.bal", &LintOptions::default());
+    let sarif = sarif_report(
+        &[FileDiagnostics {
+            file_name: "src/a b.bal",
+            source,
+            diagnostics: &report.diagnostics,
+        }],
+        &default_rules(),
+    )
+    .to_string();
+
+    assert!(sarif.contains("\"version\":\"2.1.0\""), "{sarif}");
+    assert!(sarif.contains("\"name\":\"blazelint\""), "{sarif}");
+    assert!(
+        sarif.contains(
+            "{\"id\":\"line_length\",\"shortDescription\":{\"text\":\"Lines should not exceed"
+        ),
+        "{sarif}"
+    );
+    assert!(
+        sarif.contains("\"ruleId\":\"camel_case\",\"ruleIndex\":0,\"level\":\"warning\""),
+        "{sarif}"
+    );
+    assert!(sarif.contains("\"uri\":\"src/a%20b.bal\""), "{sarif}");
+    // Columns count code points, so the two-byte `é` counts once.
+    assert!(
+        sarif.contains(
+            "\"region\":{\"startLine\":1,\"startColumn\":21,\"endLine\":1,\"endColumn\":29,\
+             \"byteOffset\":21,\"byteLength\":8}"
+        ),
+        "{sarif}"
+    );
+    assert!(sarif.contains("\"suppressions\":[]"), "{sarif}");
+}
+
+#[test]
+fn sarif_format_reports_compiler_diagnostics_by_kind() {
+    let dir = tempfile::tempdir().expect("temp dir");
+    write(dir.path(), "a.bal", "int x = \"text\";\n");
+
+    let output = run_cli(&["--format", "sarif", "a.bal"], dir.path());
+    let out = stdout(&output);
+    assert!(!output.status.success());
+    assert!(out.starts_with("{\n  \"$schema\": "), "{out}");
+    assert!(out.contains("\"ruleId\": \"semantic\""), "{out}");
+    assert!(out.contains("\"level\": \"error\""), "{out}");
+    assert!(out.contains("\"uri\": \"a.bal\""), "{out}");
+}
+
+#[test]
+fn text_output_is_quiet_by_default() {
+    let dir = tempfile::tempdir().expect("temp dir");
+    write(dir.path(), "a.bal", "int goodName = 1;\n");
+
+    let output = run_cli(&["a.bal"], dir.path());
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "");
+}
+
+#[test]
+fn emit_symbols_lists_declarations_by_scope() {
+    let dir = tempfile::tempdir().expect("temp dir");
+    write(
+        dir.path(),
+        "a.bal",
+        "const LIMIT = 3;\nfunction twice(int n) returns int {\n    int doubled = n * 2;\n    return doubled;\n}\n",
+    );
+
+    let out = stdout(&run_cli(&["--emit", "symbols", "a.bal"], dir.path()));
+    assert!(out.starts_with("--- Symbols (a.bal) ---\n"), "{out}");
+    assert!(out.contains("\nconstant LIMIT: int @ 1:1\n"), "{out}");
+    assert!(out.contains("\nfunction twice: int @ 2:10\n"), "{out}");
+    assert!(out.contains("\n  parameter n: int @ 2:10\n"), "{out}");
+    assert!(out.contains("\n  variable doubled: int @ 3:5\n"), "{out}");
+}
+
+#[test]
+fn emit_cst_shows_tokens_and_trivia() {
+    let dir = tempfile::tempdir().expect("temp dir");
+    write(dir.path(), "a.bal", "int count = 1; // start\n");
+
+    let out = stdout(&run_cli(&["--emit", "cst", "a.bal"], dir.path()));
+    assert!(out.starts_with("--- CST (a.bal) ---\nSourceFile@0..24\n  VarDecl@0..14\n    Int@0..3 \"int\"\n    Whitespace@3..4 \" \"\n"), "{out}");
+    assert!(
+        out.contains("\n  LineComment@15..23 \"// start\"\n  Newline@23..24 \"\\n\"\n"),
+        "{out}"
+    );
+}
+
+#[test]
+fn emit_ast_json_writes_to_a_file() {
+    let dir = tempfile::tempdir().expect("temp dir");
+    write(dir.path(), "a.bal", "int x = 1 + 2;\n");
+
+    let output = run_cli(
+        &["--format", "json", "--emit=ast-json=ast.json", "a.bal"],
+        dir.path(),
+    );
+    assert!(output.status.success());
+    assert!(stdout(&output).starts_with("{\n  \"version\": 1,"));
+
+    let ast = fs::read_to_string(dir.path().join("ast.json")).expect("ast.json written");
+    assert!(ast.contains("\"file\": \"a.bal\""), "{ast}");
+    assert!(ast.contains("\"node\": \"VarDecl\""), "{ast}");
+    assert!(ast.contains("\"op\": \"Plus\""), "{ast}");
+}
+
+#[test]
+fn emit_to_stdout_conflicts_with_machine_formats() {
+    let dir = tempfile::tempdir().expect("temp dir");
+    let output = run_cli(
+        &["--format", "sarif", "--emit", "tokens", "a.bal"],
+        dir.path(),
+    );
+    let err = String::from_utf8_lossy(&output.stderr);
+    assert!(!output.status.success());
+    assert!(err.contains("'--emit' needs a file name"), "{err}");
+}
+
+#[test]
+fn list_rules_prints_a_table() {
+    let dir = tempfile::tempdir().expect("temp dir");
+    let output = run_cli(&["--list-rules"], dir.path());
+    assert!(output.status.success());
+    let out = stdout(&output);
+    let lines: Vec<&str> = out.lines().collect();
+    assert!(lines[0].starts_with("RULE "), "{out}");
+    assert_eq!(lines.len(), default_rules().len() + 1, "{out}");
+    assert!(
+        out.contains("line_length    BL4003  style     warning   no   max_length=120  "),
+        "{out}"
+    );
+}
+
+#[test]
+fn list_rules_as_json_uses_registry_names() {
+    let dir = tempfile::tempdir().expect("temp dir");
+    let output = run_cli(&["--list-rules", "--format", "json"], dir.path());
+    assert!(output.status.success());
+    let document = JsonValue::parse(&stdout(&output)).expect("valid JSON");
+    let rules = document["rules"].as_array().expect("rules array");
+
+    let names: Vec<&str> = rules
+        .iter()
+        .map(|rule| rule["name"].as_str().expect("name"))
+        .collect();
+    assert_eq!(names, ["camel_case", "constant_case", "line_length"]);
+    assert_eq!(rules[1]["code"].as_str(), Some("BL4002"));
+    assert_eq!(rules[1]["fixable"], JsonValue::Bool(true));
+    let options = rules[2]["options"].as_array().expect("options array");
+    assert_eq!(options[0]["name"].as_str(), Some("max_length"));
+    assert_eq!(options[0]["default"].as_str(), Some("120"));
+    assert_eq!(
+        options[0]["default"].as_str(),
+        Some(DEFAULT_MAX_WIDTH.to_string().as_str())
+    );
+    let description = rules[2]["description"].as_str().expect("description");
+    assert!(
+        description.contains(&format!("({DEFAULT_MAX_WIDTH} characters by default)")),
+        "{description}"
+    );
+
+    let output = run_cli(&["--list-rules", "--format", "sarif"], dir.path());
+    assert_eq!(output.status.code(), Some(1));
+}
+
+#[test]
+fn color_option_controls_ansi_escapes() {
+    let dir = tempfile::tempdir().expect("temp dir");
+    write(dir.path(), "a.bal", "int bad_name = 1;\n");
+
+    let colored = stdout(&run_cli(&["--color", "always", "a.bal"], dir.path()));
+    assert!(
+        colored.contains("\x1b[1;33mlinter warning[BL4001]\x1b[0m"),
+        "{colored}"
+    );
+    let plain = stdout(&run_cli(&["--color=never", "a.bal"], dir.path()));
+    assert!(!plain.contains('\x1b'), "{plain}");
+    // Piped output is not a terminal, so `auto` stays plain.
+    let auto = stdout(&run_cli(&["a.bal"], dir.path()));
+    assert_eq!(auto, plain);
+
+    let output = run_cli(&["--color", "sometimes", "a.bal"], dir.path());
+    assert_eq!(output.status.code(), Some(1));
+}
@@ -0,0 +1,129 @@
+mod common;
+
+use common::{run_cli, run_cli_with_stdin, stdout, write};
+use std::fs;
+
+/// Lays out a small Ballerina package with one offending file per directory.
+fn sample_package() -> tempfile::TempDir {
+    let dir = tempfile::tempdir().expect("temp dir");
+    write(dir.path(), "Ballerina.toml", "[package]\nname = \"demo\"\n");
+    write(dir.path(), "main.bal", "int goodName = 1;\n");
+    write(dir.path(), "modules/util/util.bal", "int bad_name = 1;\n");
+    write(dir.path(), "tests/util_test.bal", "const badConst = 1;\n");
+    write(dir.path(), "target/bin/gen.bal", "int skipped_name = 1;\n");
+    write(dir.path(), ".hidden/x.bal", "int hidden_name = 1;\n");
+    dir
+}
+
+#[test]
+fn lints_directories_recursively() {
+    let dir = sample_package();
+    let output = run_cli(&["--deny-warnings", "."], dir.path());
+    let out = stdout(&output);
+
+    assert!(!output.status.success());
+    assert!(out.contains("modules/util/util.bal:1:5-1:13"), "{out}");
+    assert!(out.contains("tests/util_test.bal:1:7"), "{out}");
+    assert!(!out.contains("skipped_name"), "target/ must be skipped");
+    assert!(!out.contains("hidden_name"), "hidden dirs must be skipped");
+    assert!(out.contains("Checked 3 file(s): 0 error(s), 2 warning(s)"));
+}
+
+#[test]
+fn ignore_globs_skip_matching_paths() {
+    let dir = sample_package();
+    let output = run_cli(
+        &["--ignore", "tests", "--ignore=modules/**/*.bal", "."],
+        dir.path(),
+    );
+    let out = stdout(&output);
+
+    assert!(output.status.success(), "{out}");
+    assert!(!out.contains("linter warning"), "{out}");
+}
+
+#[test]
+fn accepts_multiple_file_arguments() {
+    let dir = sample_package();
+    let output = run_cli(&["main.bal", "modules/util/util.bal"], dir.path());
+    let out = stdout(&output);
+
+    assert!(output.status.success());
+    assert!(out.contains("Variable \"bad_name\" is not in camelCase."));
+    assert!(out.contains("Checked 2 file(s): 0 error(s), 1 warning(s)"));
+}
+
+#[test]
+fn clean_files_exit_successfully() {
+    let dir = sample_package();
+    let output = run_cli(&["main.bal"], dir.path());
+    assert!(output.status.success());
+}
+
+#[test]
+fn missing_paths_are_reported() {
+    let dir = tempfile::tempdir().expect("temp dir");
+    let output = run_cli(&["does-not-exist"], dir.path());
+    assert!(!output.status.success());
+    let err = String::from_utf8_lossy(&output.stderr);
+    assert!(err.contains("cannot access does-not-exist"), "{err}");
+}
+
+#[test]
+fn lints_source_from_stdin() {
+    let dir = tempfile::tempdir().expect("temp dir");
+    let output = run_cli_with_stdin(&["-"], dir.path(), "int bad_name = 1;\n");
+    let out = stdout(&output);
+
+    assert!(output.status.success(), "{out}");
+    assert!(out.contains(" --> <stdin>:1:5-1:13"), "{out}");
+    assert!(out.contains("Checked 1 file(s): 0 error(s), 1 warning(s)"));
+}
+
+#[test]
+fn stdin_filename_names_the_source_and_finds_its_config() {
+    let dir = sample_package();
+    write(
+        dir.path(),
+        "modules/util/.blazerc",
+        "[rules.camel_case]\nseverity = \"error\"\n",
+    );
+    let output = run_cli_with_stdin(
+        &["--stdin", "--stdin-filename", "modules/util/util.bal"],
+        dir.path(),
+        "int unsaved_name = 1;\n",
+    );
+    let out = stdout(&output);
+
+    assert_eq!(output.status.code(), Some(1), "{out}");
+    assert!(
+        out.contains("linter error[BL4001]: Variable \"unsaved_name\""),
+        "{out}"
+    );
+    assert!(out.contains(" --> modules/util/util.bal:1:5-1:17"), "{out}");
+    assert_eq!(
+        fs::read_to_string(dir.path().join("modules/util/util.bal")).expect("read"),
+        "int bad_name = 1;\n"
+    );
+}
+
+#[test]
+fn stdin_usage_errors() {
+    let dir = tempfile::tempdir().expect("temp dir");
+    for (args, message) in [
+        (
+            &["-", "main.bal"][..],
+            "stdin input cannot be combined with paths",
+        ),
+        (
+            &["--stdin-filename", "a.bal", "main.bal"][..],
+            "'--stdin-filename' requires '--stdin' or '-'",
+        ),
+        (&["-", "--fix"][..], "'--fix' cannot write back to stdin"),
+    ] {
+        let output = run_cli_with_stdin(args, dir.path(), "");
+        assert_eq!(output.status.code(), Some(1), "{args:?}");
+        let err = String::from_utf8_lossy(&output.stderr);
+        assert!(err.contains(message), "{err}");
+    }
+}
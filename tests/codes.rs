@@ -0,0 +1,126 @@
+use assert_cmd::Command;
+use blazelint::codes::{self, CODES};
+use blazelint::config::Config;
+use blazelint::errors::Diagnostic;
+use blazelint::format::{format_source, unformatted_diagnostic, FormatOptions};
+use blazelint::linter::RuleSet;
+use blazelint::{lint_source, LintOptions};
+use std::fs;
+use std::process::Output;
+
+fn run_cli(args: &[&str]) -> Output {
+    Command::cargo_bin("blazelint")
+        .expect("binary")
+        .args(args)
+        .output()
+        .expect("run blazelint")
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+/// Diagnostics the example input of `code` produces.
+fn diagnostics_for(code: &str, input: &str) -> Vec<Diagnostic> {
+    match code {
+        codes::INVALID_CONFIG => {
+            let (config, mut diagnostics) = Config::parse(input);
+            diagnostics.extend(RuleSet::from_config(&config).1);
+            diagnostics
+        }
+        codes::UNFORMATTED_FILE => {
+            let formatted = format_source(input, &FormatOptions::default()).expect("formats");
+            if formatted == input {
+                Vec::new()
+            } else {
+                vec![unformatted_diagnostic(input, &formatted)]
+            }
+        }
+        _ => lint_source(input, "example.bal", &LintOptions::default()).diagnostics,
+    }
+}
+
+#[test]
+fn codes_are_unique_and_ordered() {
+    for pair in CODES.windows(2) {
+        assert!(
+            pair[0].code < pair[1].code,
+            "{} before {}",
+            pair[0].code,
+            pair[1].code
+        );
+    }
+    for info in CODES {
+        assert!(
+            info.code.starts_with("BL") && info.code.len() == 6,
+            "{}",
+            info.code
+        );
+    }
+}
+
+#[test]
+fn examples_report_their_code() {
+    for info in CODES {
+        let bad = diagnostics_for(info.code, info.bad);
+        assert!(
+            bad.iter()
+                .any(|diagnostic| diagnostic.code == Some(info.code)),
+            "{}: bad example reports {bad:?}",
+            info.code
+        );
+        let good = diagnostics_for(info.code, info.good);
+        assert!(
+            good.is_empty(),
+            "{}: good example reports {good:?}",
+            info.code
+        );
+    }
+}
+
+#[test]
+fn every_diagnostic_has_a_documented_code() {
+    for entry in fs::read_dir("tests/test-bal-files").expect("example directory") {
+        let path = entry.expect("entry").path();
+        let source = fs::read_to_string(&path).expect("example file");
+        let report = lint_source(&source, "example.bal", &LintOptions::default());
+        for diagnostic in &report.diagnostics {
+            let code = diagnostic.code.expect("diagnostic code");
+            assert!(codes::lookup(code).is_some(), "{code} is not documented");
+        }
+    }
+}
+
+#[test]
+fn text_output_shows_the_code() {
+    let dir = tempfile::tempdir().expect("temp dir");
+    let file = dir.path().join("a.bal");
+    fs::write(&file, "function main() {\n    break;\n}\n").expect("write");
+
+    let output = run_cli(&[file.to_str().expect("utf-8 path")]);
+    assert!(
+        stdout(&output).contains("semantic error[BL3014]: Break statement outside of loop"),
+        "{}",
+        stdout(&output)
+    );
+}
+
+#[test]
+fn explain_prints_description_and_examples() {
+    let output = run_cli(&["--explain", "bl4001"]);
+    assert!(output.status.success());
+    let out = stdout(&output);
+    assert!(
+        out.starts_with("BL4001: Variable name is not camelCase"),
+        "{out}"
+    );
+    assert!(out.contains("\n    int retry_count = 3;\n"), "{out}");
+    assert!(
+        out.contains("Corrected:\n\n    int retryCount = 3;\n"),
+        "{out}"
+    );
+
+    let output = run_cli(&["--explain", "BL9999"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("unknown diagnostic code 'BL9999'"));
+}
@@ -0,0 +1,41 @@
+//! Helpers shared by the tests that run the `blazelint` binary.
+//!
+//! Each test crate uses only some of them.
+#![allow(dead_code)]
+
+use assert_cmd::Command;
+use std::fs;
+use std::path::Path;
+use std::process::Output;
+
+/// Runs `blazelint` with `args` in `cwd`.
+pub fn run_cli(args: &[&str], cwd: &Path) -> Output {
+    Command::cargo_bin("blazelint")
+        .expect("binary")
+        .current_dir(cwd)
+        .args(args)
+        .output()
+        .expect("run blazelint")
+}
+
+/// Runs `blazelint` with `args` in `cwd`, writing `input` to its stdin.
+pub fn run_cli_with_stdin(args: &[&str], cwd: &Path, input: &str) -> Output {
+    Command::cargo_bin("blazelint")
+        .expect("binary")
+        .current_dir(cwd)
+        .args(args)
+        .write_stdin(input)
+        .output()
+        .expect("run blazelint")
+}
+
+pub fn stdout(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+/// Writes `contents` to `relative` under `root`, creating its directories.
+pub fn write(root: &Path, relative: &str, contents: &str) {
+    let path = root.join(relative);
+    fs::create_dir_all(path.parent().unwrap()).expect("create dirs");
+    fs::write(path, contents).expect("write file");
+}
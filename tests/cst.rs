@@ -0,0 +1,54 @@
+use blazelint::ast::NodeKind;
+use blazelint::cst::{SyntaxKind, SyntaxTokenKind};
+use blazelint::lexer::{CommentKind, TriviaKind};
+use blazelint::syntax_tree;
+use std::fs;
+
+#[test]
+fn example_files_round_trip() {
+    for entry in fs::read_dir("tests/test-bal-files").expect("example directory") {
+        let path = entry.expect("entry").path();
+        let source = fs::read_to_string(&path).expect("example file");
+        let Ok((tree, _)) = syntax_tree(&source) else {
+            continue;
+        };
+        assert_eq!(tree.text(), source, "{}", path.display());
+        assert_eq!(tree.span, 0..source.len());
+    }
+}
+
+#[test]
+fn unparsable_code_still_round_trips() {
+    let source = "int = ;\r\n\tfunction (\n/* open */ x +";
+    let (tree, diagnostics) = syntax_tree(source).expect("lexes");
+    assert!(!diagnostics.is_empty());
+    assert_eq!(tree.text(), source);
+}
+
+#[test]
+fn nodes_exclude_surrounding_trivia() {
+    let source = "function f() {\n    int x = 1; // one\n}\n";
+    let (tree, diagnostics) = syntax_tree(source).expect("lexes");
+    assert!(diagnostics.is_empty(), "{diagnostics:?}");
+
+    let declaration = tree
+        .descendants()
+        .into_iter()
+        .find(|node| node.kind == SyntaxKind::Node(NodeKind::VarDecl))
+        .expect("declaration node");
+    assert_eq!(declaration.text(), "int x = 1;");
+
+    let function = tree.child_nodes()[0];
+    let comment = function
+        .tokens()
+        .into_iter()
+        .find(|token| token.kind == SyntaxTokenKind::Trivia(TriviaKind::Comment(CommentKind::Line)))
+        .expect("comment kept");
+    assert_eq!(comment.text, "// one");
+
+    let literal = source.find('1').expect("literal");
+    let covering = tree
+        .covering_node(&(literal..literal + 1))
+        .expect("covering node");
+    assert_eq!(covering.kind, SyntaxKind::Node(NodeKind::Literal));
+}
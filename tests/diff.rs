@@ -0,0 +1,179 @@
+mod common;
+
+use blazelint::diff::ChangedLines;
+use common::{run_cli_with_stdin, stdout};
+use std::fs;
+
+const GIT_DIFF: &str = "\
+diff --git a/src/main.bal b/src/main.bal
+index 5c1e043..0e149b6 100644
+--- a/src/main.bal
++++ b/src/main.bal
+@@ -1,4 +1,4 @@
+ int a = 1;
+-int b = 2;
++int b = 3;
++int c = 4;
+ int d = 5;
+-int e = 6;
+@@ -10 +11,0 @@
+-int removed = 0;
+diff --git a/old.bal b/old.bal
+deleted file mode 100644
+--- a/old.bal
++++ /dev/null
+@@ -1 +0,0 @@
+-int gone = 1;
+";
+
+#[test]
+fn parses_added_and_modified_lines() {
+    let changed = ChangedLines::parse(GIT_DIFF).expect("parses");
+    let touched: Vec<usize> = (1..=12)
+        .filter(|&line| changed.touches("src/main.bal", line, line))
+        .collect();
+    assert_eq!(touched, [2, 3]);
+    assert!(changed.touches("./src/main.bal", 1, 5));
+    assert!(!changed.touches("old.bal", 1, 1));
+    assert!(!changed.touches("other.bal", 2, 2));
+}
+
+#[test]
+fn matches_paths_relative_to_a_subdirectory() {
+    let changed = ChangedLines::parse(GIT_DIFF).expect("parses");
+    assert!(changed.touches("main.bal", 2, 2));
+    assert!(!changed.touches("repo/src/main.bal", 2, 2));
+    assert!(!changed.touches("ain.bal", 2, 2));
+
+    // A name shared by several changed files matches none of them.
+    let diff = "+++ b/modules/a/main.bal\n@@ -0,0 +1 @@\n+int x = 1;\n\
+                +++ b/modules/b/main.bal\n@@ -0,0 +1 @@\n+int y = 1;\n";
+    let changed = ChangedLines::parse(diff).expect("parses");
+    assert!(changed.touches("a/main.bal", 1, 1));
+    assert!(!changed.touches("main.bal", 1, 1));
+}
+
+#[test]
+fn accepts_plain_diff_u_output() {
+    let diff = "--- a.bal\t2024-01-01 00:00:00\n\
+                +++ a.bal\t2024-01-02 00:00:00\n\
+                @@ -1 +1 @@\n\
+                -int x = 1;\n\
+                \\ No newline at end of file\n\
+                +int x = 2;\n\
+                \\ No newline at end of file\n";
+    let changed = ChangedLines::parse(diff).expect("parses");
+    assert!(changed.touches("a.bal", 1, 1));
+}
+
+#[test]
+fn rejects_malformed_diffs() {
+    let err = ChangedLines::parse("@@ -1 +1 @@\n+x\n").expect_err("no header");
+    assert_eq!(err, "line 1: hunk before any '+++' header");
+    let err = ChangedLines::parse("+++ b/a.bal\n@@ -1 +x @@\n").expect_err("header");
+    assert_eq!(err, "line 2: malformed hunk header '@@ -1 +x @@'");
+    let err = ChangedLines::parse("+++ b/a.bal\n@@ -1,2 +1,2 @@\n x\n").expect_err("short");
+    assert_eq!(err, "diff ends in the middle of a hunk");
+}
+
+#[test]
+fn cli_reports_only_findings_on_changed_lines() {
+    let dir = tempfile::tempdir().expect("temp dir");
+    fs::create_dir(dir.path().join("src")).expect("mkdir");
+    fs::write(
+        dir.path().join("src/main.bal"),
+        "int old_name = 1;\nint new_name = 3;\nfunction f() {\n    int x = y;\n}\n",
+    )
+    .expect("write");
+    let diff = "--- a/src/main.bal\n+++ b/src/main.bal\n\
+                @@ -1,2 +1,2 @@\n int old_name = 1;\n-int new_name = 2;\n+int new_name = 3;\n";
+    fs::write(dir.path().join("pr.diff"), diff).expect("write");
+
+    let output = run_cli_with_stdin(&["--diff", "pr.diff", "src"], dir.path(), "");
+    let out = stdout(&output);
+    assert!(output.status.success(), "{out}");
+    assert!(out.contains("\"new_name\""), "{out}");
+    assert!(!out.contains("\"old_name\""), "{out}");
+    assert!(!out.contains("Undeclared"), "{out}");
+    assert!(
+        out.contains("Checked 1 file(s): 0 error(s), 1 warning(s)"),
+        "{out}"
+    );
+
+    let output = run_cli_with_stdin(&["--diff=-", "src/main.bal"], dir.path(), diff);
+    assert_eq!(stdout(&output), out);
+
+    let output = run_cli_with_stdin(&["--diff", "-", "-"], dir.path(), diff);
+    assert_eq!(output.status.code(), Some(1));
+    let err = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        err.contains("the source and '--diff' cannot both be read from stdin"),
+        "{err}"
+    );
+
+    let source = fs::read_to_string(dir.path().join("src/main.bal")).expect("read");
+    let output = run_cli_with_stdin(&["--diff", "pr.diff", "--stdin"], dir.path(), &source);
+    assert_eq!(output.status.code(), Some(1));
+    let err = String::from_utf8_lossy(&output.stderr);
+    assert!(err.contains("requires '--stdin-filename'"), "{err}");
+
+    let output = run_cli_with_stdin(
+        &["--diff", "pr.diff", "--stdin-filename", "src/main.bal", "-"],
+        dir.path(),
+        &source,
+    );
+    assert!(
+        stdout(&output).contains("\"new_name\""),
+        "{}",
+        stdout(&output)
+    );
+}
+
+#[test]
+fn cli_matches_diff_paths_from_the_repository_root() {
+    let dir = tempfile::tempdir().expect("temp dir");
+    fs::create_dir(dir.path().join(".git")).expect("mkdir");
+    fs::create_dir_all(dir.path().join("modules/foo")).expect("mkdir");
+    fs::write(dir.path().join("main.bal"), "int root_name = 1;\n").expect("write");
+    fs::write(
+        dir.path().join("modules/foo/main.bal"),
+        "int module_name = 1;\n",
+    )
+    .expect("write");
+    let root_diff =
+        "--- a/main.bal\n+++ b/main.bal\n@@ -1 +1 @@\n-int x = 1;\n+int root_name = 1;\n";
+    fs::write(dir.path().join("pr.diff"), root_diff).expect("write");
+
+    let output = run_cli_with_stdin(&["--diff", "pr.diff", "."], dir.path(), "");
+    let out = stdout(&output);
+    assert!(out.contains("\"root_name\""), "{out}");
+    assert!(!out.contains("\"module_name\""), "{out}");
+
+    // From inside the module, its paths are still taken relative to the
+    // repository root.
+    let module = dir.path().join("modules/foo");
+    let output = run_cli_with_stdin(&["--diff", "../../pr.diff", "main.bal"], &module, "");
+    assert!(!stdout(&output).contains("_name"), "{}", stdout(&output));
+    let module_diff = root_diff
+        .replace("a/main.bal", "a/modules/foo/main.bal")
+        .replace("b/main.bal", "b/modules/foo/main.bal")
+        .replace("root_name", "module_name");
+    let output = run_cli_with_stdin(&["--diff", "-", "main.bal"], &module, &module_diff);
+    assert!(
+        stdout(&output).contains("\"module_name\""),
+        "{}",
+        stdout(&output)
+    );
+
+    // An explicit root overrides the repository.
+    let output = run_cli_with_stdin(
+        &["--diff", "../../pr.diff", "--diff-root", ".", "main.bal"],
+        &module,
+        "",
+    );
+    assert!(
+        stdout(&output).contains("\"module_name\""),
+        "{}",
+        stdout(&output)
+    );
+}
@@ -0,0 +1,205 @@
+mod common;
+
+use blazelint::errors::{Applicability, Diagnostic, DiagnosticKind, Fix, TextEdit};
+use blazelint::fix::{apply_fixes, unified_diff};
+use blazelint::{lint_source, LintOptions};
+use common::{run_cli, stdout};
+use std::fs;
+
+fn edit_fix(span: std::ops::Range<usize>, replacement: &str) -> Diagnostic {
+    Diagnostic::new(DiagnosticKind::Linter, "test", span.clone()).with_fix(Fix {
+        message: "test".to_string(),
+        applicability: Applicability::MachineApplicable,
+        edits: vec![TextEdit {
+            span,
+            replacement: replacement.to_string(),
+        }],
+    })
+}
+
+#[test]
+fn camel_case_fix_renames_every_plain_use() {
+    let source = "int bad_name = 1;\nmap<int> m = {bad_name: bad_name};\nint total = bad_name + m.bad_name;\n";
+    let report = lint_source(source, "a.bal", &LintOptions::default());
+    let fix = report.diagnostics[0]
+        .fix
+        .as_deref()
+        .expect("rename offered");
+
+    assert_eq!(fix.message, "Rename `bad_name` to `badName`");
+    assert_eq!(fix.applicability, Applicability::MachineApplicable);
+    let renamed: Vec<_> = fix.edits.iter().map(|edit| edit.span.start).collect();
+    assert_eq!(renamed, [4, 42, 65]);
+
+    let fixed = apply_fixes(source, &report.diagnostics);
+    assert_eq!(fixed.applied, 1);
+    assert_eq!(
+        fixed.source,
+        "int badName = 1;\nmap<int> m = {bad_name: badName};\nint total = badName + m.bad_name;\n"
+    );
+}
+
+#[test]
+fn camel_case_fix_leaves_fields_with_the_same_name() {
+    let source = "type User record {\n\
+                  \x20   string user_name;\n\
+                  };\n\
+                  \n\
+                  class Account {\n\
+                  \x20   int user_name = 0;\n\
+                  }\n\
+                  \n\
+                  function main() {\n\
+                  \x20   string user_name = \"a\";\n\
+                  \x20   User user = {user_name: user_name};\n\
+                  \x20   string copy = user.user_name;\n\
+                  \x20   string other = user_name;\n\
+                  }\n";
+    let report = lint_source(source, "a.bal", &LintOptions::default());
+    let fixed = apply_fixes(source, &report.diagnostics);
+    assert_eq!(fixed.applied, 1);
+    assert_eq!(
+        fixed.source,
+        source
+            .replace("string user_name = ", "string userName = ")
+            .replace(": user_name}", ": userName}")
+            .replace("other = user_name;", "other = userName;")
+    );
+    let relinted = lint_source(&fixed.source, "a.bal", &LintOptions::default());
+    assert!(relinted.is_clean(), "{:?}", relinted.diagnostics);
+}
+
+#[test]
+fn rename_onto_an_existing_name_needs_review() {
+    let source = "const maxSize = 1;\nint MAX_SIZE = 2;\n";
+    let report = lint_source(source, "a.bal", &LintOptions::default());
+    let constant = report
+        .diagnostics
+        .iter()
+        .find(|d| d.rule.as_deref() == Some("constant_case"))
+        .expect("constant_case finding");
+    let fix = constant.fix.as_deref().expect("rename offered");
+    assert_eq!(fix.applicability, Applicability::MaybeIncorrect);
+    assert_eq!(apply_fixes(source, &report.diagnostics).applied, 0);
+}
+
+#[test]
+fn renames_only_collide_with_declarations_in_nested_scopes() {
+    let source = "type Item record {\n\
+                  \x20   int itemCount;\n\
+                  };\n\
+                  \n\
+                  function first() {\n\
+                  \x20   int item_count = 1;\n\
+                  \x20   string label = \"itemCount\";\n\
+                  }\n\
+                  \n\
+                  function second() {\n\
+                  \x20   int itemCount = 2;\n\
+                  }\n\
+                  \n\
+                  function third() {\n\
+                  \x20   int item_count = 3;\n\
+                  \x20   if (item_count > 0) {\n\
+                  \x20       int itemCount = 4;\n\
+                  \x20   }\n\
+                  }\n";
+    let report = lint_source(source, "a.bal", &LintOptions::default());
+    let applicability: Vec<_> = report
+        .diagnostics
+        .iter()
+        .filter_map(|d| d.fix.as_deref())
+        .map(|fix| fix.applicability)
+        .collect();
+    assert_eq!(
+        applicability,
+        [
+            Applicability::MachineApplicable,
+            Applicability::MaybeIncorrect
+        ]
+    );
+}
+
+#[test]
+fn overlapping_fixes_are_skipped() {
+    let source = "abcdef";
+    let diagnostics = [
+        edit_fix(3..5, "X"),
+        edit_fix(0..2, "Y"),
+        edit_fix(1..4, "Z"),
+    ];
+    let fixed = apply_fixes(source, &diagnostics);
+    assert_eq!(fixed.applied, 2);
+    assert_eq!(fixed.source, "YcXf");
+}
+
+#[test]
+fn unified_diff_groups_changes_into_hunks() {
+    let old: String = (1..=12).map(|n| format!("line {n}\n")).collect();
+    let new = old
+        .replace("line 2\n", "line two\n")
+        .replace("line 11\n", "");
+    let diff = unified_diff("a.bal", &old, &new);
+    assert_eq!(
+        diff,
+        "--- a/a.bal\n+++ b/a.bal\n\
+         @@ -1,5 +1,5 @@\n line 1\n-line 2\n+line two\n line 3\n line 4\n line 5\n\
+         @@ -8,5 +8,4 @@\n line 8\n line 9\n line 10\n-line 11\n line 12\n"
+    );
+    assert_eq!(unified_diff("a.bal", &old, &old), "");
+}
+
+#[test]
+fn fix_rewrites_files_and_reports_what_remains() {
+    let dir = tempfile::tempdir().expect("temp dir");
+    fs::write(
+        dir.path().join("a.bal"),
+        "int bad_name = 1;\nint next = bad_name;\nint x = \"text\";\n",
+    )
+    .unwrap();
+
+    let output = run_cli(&["--fix", "a.bal"], dir.path());
+    let out = stdout(&output);
+    assert!(out.starts_with("Fixed 1 problem(s) in a.bal\n"), "{out}");
+    assert!(!out.contains("camelCase"), "{out}");
+    assert!(out.contains("semantic error"), "{out}");
+    assert!(!output.status.success());
+    assert_eq!(
+        fs::read_to_string(dir.path().join("a.bal")).unwrap(),
+        "int badName = 1;\nint next = badName;\nint x = \"text\";\n"
+    );
+}
+
+#[test]
+fn fix_dry_run_prints_a_diff_without_writing() {
+    let dir = tempfile::tempdir().expect("temp dir");
+    let source = "const limit = 1;\n";
+    fs::write(dir.path().join("a.bal"), source).unwrap();
+
+    let output = run_cli(&["--fix-dry-run", "a.bal"], dir.path());
+    let out = stdout(&output);
+    assert!(
+        out.starts_with(
+            "--- a/a.bal\n+++ b/a.bal\n@@ -1,1 +1,1 @@\n-const limit = 1;\n+const LIMIT = 1;\n"
+        ),
+        "{out}"
+    );
+    assert!(output.status.success());
+    assert_eq!(
+        fs::read_to_string(dir.path().join("a.bal")).unwrap(),
+        source
+    );
+}
+
+#[test]
+fn json_output_describes_fixes() {
+    let dir = tempfile::tempdir().expect("temp dir");
+    fs::write(dir.path().join("a.bal"), "int bad_name = 1;\n").unwrap();
+
+    let out = stdout(&run_cli(&["--format", "json", "a.bal"], dir.path()));
+    assert!(
+        out.contains("\"applicability\": \"machine-applicable\""),
+        "{out}"
+    );
+    assert!(out.contains("\"replacement\": \"badName\""), "{out}");
+}
@@ -0,0 +1,148 @@
+mod common;
+
+use blazelint::config::Config;
+use blazelint::format::{format_source, FormatOptions};
+use common::{run_cli, stdout};
+use std::fs;
+
+fn format(source: &str) -> String {
+    let formatted = format_source(source, &FormatOptions::default()).expect("formats");
+    let again = format_source(&formatted, &FormatOptions::default()).expect("formats again");
+    assert_eq!(again, formatted, "formatting is not idempotent");
+    formatted
+}
+
+#[test]
+fn normalizes_spacing_and_indentation() {
+    let source = "import ballerina/io;\n\
+                  function   add( int a,int b )returns int{return a+b;}\n\
+                  function main(){\n\
+                  map<string> config={name:\"app\"};\n\
+                  int[] numbers=[1,2,3];\n\
+                  if(numbers.length()>2){io:println(numbers[0]);}else{return;}\n\
+                  float f = <float>numbers[1];\n\
+                  int sign = -f > 0.0 ? -1 : +1;\n\
+                  string? name = config[\"name\"];\n\
+                  foreach int i in numbers { io:println(i); }\n\
+                  }";
+    assert_eq!(
+        format(source),
+        "import ballerina/io;\n\
+         function add(int a, int b) returns int {\n\
+         \x20   return a + b;\n\
+         }\n\
+         function main() {\n\
+         \x20   map<string> config = {name: \"app\"};\n\
+         \x20   int[] numbers = [1, 2, 3];\n\
+         \x20   if (numbers.length() > 2) {\n\
+         \x20       io:println(numbers[0]);\n\
+         \x20   } else {\n\
+         \x20       return;\n\
+         \x20   }\n\
+         \x20   float f = <float>numbers[1];\n\
+         \x20   int sign = -f > 0.0 ? -1 : +1;\n\
+         \x20   string? name = config[\"name\"];\n\
+         \x20   foreach int i in numbers {\n\
+         \x20       io:println(i);\n\
+         \x20   }\n\
+         }\n"
+    );
+}
+
+#[test]
+fn preserves_comments_and_single_blank_lines() {
+    let source = "# Entry point.\n\
+                  function main() {\n\n\
+                  \tint a = 1;   // first\n\n\n\
+                  \t/* block */\n\
+                  \tint b = 2;\n\n\
+                  }\n\n\n";
+    assert_eq!(
+        format(source),
+        "# Entry point.\n\
+         function main() {\n\
+         \x20   int a = 1; // first\n\
+         \n\
+         \x20   /* block */\n\
+         \x20   int b = 2;\n\
+         }\n"
+    );
+}
+
+#[test]
+fn breaks_long_lists_one_element_per_line() {
+    let source = "function main() { io:println(add(1, 2), add(3, 4), [5, 6]); }";
+    let options = FormatOptions { max_width: 24 };
+    let formatted = format_source(source, &options).expect("formats");
+    assert_eq!(
+        formatted,
+        "function main() {\n\
+         \x20   io:println(\n\
+         \x20       add(1, 2),\n\
+         \x20       add(3, 4),\n\
+         \x20       [5, 6]\n\
+         \x20   );\n\
+         }\n"
+    );
+    assert_eq!(
+        format_source(&formatted, &options).expect("formats"),
+        formatted
+    );
+}
+
+#[test]
+fn width_comes_from_line_length_config() {
+    let (config, _) = Config::parse("[rules.line_length]\nmax_length = 80\n");
+    assert_eq!(FormatOptions::from_config(&config).max_width, 80);
+    assert_eq!(
+        FormatOptions::from_config(&Config::default()).max_width,
+        120
+    );
+}
+
+#[test]
+fn refuses_code_with_syntax_errors() {
+    let errors = format_source("int x = ;", &FormatOptions::default()).expect_err("parse error");
+    assert!(errors[0].message.contains("Unexpected token"), "{errors:?}");
+}
+
+#[test]
+fn fmt_rewrites_files_in_place() {
+    let dir = tempfile::tempdir().expect("temp dir");
+    fs::write(dir.path().join("a.bal"), "int x=1;\n").expect("write");
+    fs::write(dir.path().join("b.bal"), "int y = 2;\n").expect("write");
+
+    let output = run_cli(&["fmt", "."], dir.path());
+    assert!(output.status.success());
+    assert_eq!(
+        stdout(&output),
+        "Formatted ./a.bal\nChecked 2 file(s): 0 error(s), 0 warning(s)\n"
+    );
+    assert_eq!(
+        fs::read_to_string(dir.path().join("a.bal")).expect("read"),
+        "int x = 1;\n"
+    );
+}
+
+#[test]
+fn fmt_check_reports_without_writing() {
+    let dir = tempfile::tempdir().expect("temp dir");
+    fs::write(dir.path().join("a.bal"), "int ok = 1;\nint x=1;\n").expect("write");
+
+    let output = run_cli(&["fmt", "--check", "a.bal"], dir.path());
+    assert_eq!(output.status.code(), Some(1));
+    let out = stdout(&output);
+    assert!(
+        out.contains("format error[BL5004]: File is not formatted\n --> a.bal:2:1-2:9"),
+        "{out}"
+    );
+    assert_eq!(
+        fs::read_to_string(dir.path().join("a.bal")).expect("read"),
+        "int ok = 1;\nint x=1;\n"
+    );
+
+    fs::write(dir.path().join("a.bal"), "int x = 1;\n").expect("write");
+    let output = run_cli(&["fmt", "--check", "a.bal"], dir.path());
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "");
+}
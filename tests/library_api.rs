@@ -0,0 +1,71 @@
+use blazelint::ast::Stmt;
+use blazelint::codes;
+use blazelint::errors::DiagnosticKind;
+use blazelint::lexer::Token;
+use blazelint::{lint_source, parse, tokenize, LintOptions};
+
+#[test]
+fn lint_source_returns_tokens_ast_and_diagnostics() {
+    let report = lint_source("int a_b = 1;", "sample.bal", &LintOptions::default());
+
+    assert_eq!(report.file_name, "sample.bal");
+    assert!(matches!(report.tokens[0].1, Token::Int));
+    assert!(matches!(report.ast[0], Stmt::VarDecl { .. }));
+    assert_eq!(report.diagnostics.len(), 1);
+    assert!(matches!(report.diagnostics[0].kind, DiagnosticKind::Linter));
+    assert_eq!(report.diagnostics[0].span, 4..7);
+}
+
+#[test]
+fn lint_source_accepts_clean_code() {
+    let report = lint_source(
+        "function add(int a, int b) returns int { return a + b; }",
+        "clean.bal",
+        &LintOptions::default(),
+    );
+    assert!(report.is_clean(), "{:?}", report.diagnostics);
+}
+
+#[test]
+fn lint_source_stops_after_lex_errors() {
+    let report = lint_source("int a = 1 @;", "lex.bal", &LintOptions::default());
+    assert!(report.tokens.is_empty());
+    assert!(report.ast.is_empty());
+    assert!(matches!(report.diagnostics[0].kind, DiagnosticKind::Lex));
+}
+
+#[test]
+fn lint_options_can_skip_stages() {
+    let mut options = LintOptions::default();
+    options.lint = false;
+    let report = lint_source("int a_b = 1; a_b = \"oops\";", "skip.bal", &options);
+    assert!(
+        report
+            .diagnostics
+            .iter()
+            .any(|d| matches!(d.kind, DiagnosticKind::Semantic)
+                && d.code == Some(codes::TYPE_MISMATCH)),
+        "{:?}",
+        report.diagnostics
+    );
+    assert!(
+        report
+            .diagnostics
+            .iter()
+            .all(|d| d.rule.as_deref() != Some("camel_case")),
+        "{:?}",
+        report.diagnostics
+    );
+
+    options.semantic = false;
+    let report = lint_source("int a_b = 1; a_b = \"oops\";", "skip.bal", &options);
+    assert!(report.is_clean());
+}
+
+#[test]
+fn tokenize_and_parse_are_usable_directly() {
+    let tokens = tokenize("const MAX = 1;").expect("tokens");
+    let (ast, diagnostics) = parse(&tokens);
+    assert!(diagnostics.is_empty());
+    assert!(matches!(ast[0], Stmt::ConstDecl { .. }));
+}
@@ -0,0 +1,328 @@
+use assert_cmd::Command;
+use blazelint::json::JsonValue;
+use blazelint::lsp::{read_message, serve, write_message, Server};
+use std::fs;
+use std::io::Cursor;
+
+/// Parses a message written with single quotes for readability.
+fn msg(text: &str) -> JsonValue {
+    JsonValue::parse(&text.replace('\'', "\"")).expect("valid test message")
+}
+
+/// A scripted editor session driving a [`Server`] in-process.
+struct Client {
+    server: Server,
+}
+
+impl Client {
+    /// Starts a session that has completed the `initialize` handshake.
+    fn initialized() -> Self {
+        let mut server = Server::new();
+        server.handle(&msg(
+            "{'jsonrpc':'2.0','id':1,'method':'initialize','params':{}}",
+        ));
+        server.handle(&msg("{'jsonrpc':'2.0','method':'initialized','params':{}}"));
+        Self { server }
+    }
+
+    fn notify(&mut self, method: &str, params: JsonValue) -> Vec<JsonValue> {
+        self.server.handle(&JsonValue::object([
+            ("jsonrpc", "2.0".into()),
+            ("method", method.into()),
+            ("params", params),
+        ]))
+    }
+
+    fn open(&mut self, uri: &str, text: &str) -> Vec<JsonValue> {
+        self.notify(
+            "textDocument/didOpen",
+            JsonValue::object([(
+                "textDocument",
+                JsonValue::object([
+                    ("uri", uri.into()),
+                    ("languageId", "ballerina".into()),
+                    ("version", 1usize.into()),
+                    ("text", text.into()),
+                ]),
+            )]),
+        )
+    }
+
+    /// Sends one change replacing `range` (`[line, character]` pairs, or the
+    /// whole text when `None`) with `text`.
+    fn change(
+        &mut self,
+        uri: &str,
+        version: usize,
+        range: Option<([usize; 2], [usize; 2])>,
+        text: &str,
+    ) -> Vec<JsonValue> {
+        let position = |[line, character]: [usize; 2]| {
+            JsonValue::object([("line", line.into()), ("character", character.into())])
+        };
+        let mut change = Vec::new();
+        if let Some((start, end)) = range {
+            change.push((
+                "range",
+                JsonValue::object([("start", position(start)), ("end", position(end))]),
+            ));
+        }
+        change.push(("text", text.into()));
+        self.notify(
+            "textDocument/didChange",
+            JsonValue::object([
+                (
+                    "textDocument",
+                    JsonValue::object([("uri", uri.into()), ("version", version.into())]),
+                ),
+                (
+                    "contentChanges",
+                    JsonValue::Array(vec![JsonValue::object(change)]),
+                ),
+            ]),
+        )
+    }
+}
+
+/// The diagnostics array of the only `publishDiagnostics` in `messages` that
+/// targets `uri`.
+fn published<'a>(messages: &'a [JsonValue], uri: &str) -> &'a [JsonValue] {
+    let matching: Vec<_> = messages
+        .iter()
+        .filter(|message| {
+            message.get("method").and_then(JsonValue::as_str)
+                == Some("textDocument/publishDiagnostics")
+        })
+        .map(|message| &message["params"])
+        .filter(|params| params.get("uri").and_then(JsonValue::as_str) == Some(uri))
+        .collect();
+    assert_eq!(matching.len(), 1, "{messages:?}");
+    matching[0]["diagnostics"].as_array().expect("array")
+}
+
+/// `[start line, start character, end line, end character]` of a diagnostic.
+fn range(diagnostic: &JsonValue) -> [usize; 4] {
+    let range = &diagnostic["range"];
+    let get = |end: &str, key: &str| range[end][key].as_usize().expect("integer");
+    [
+        get("start", "line"),
+        get("start", "character"),
+        get("end", "line"),
+        get("end", "character"),
+    ]
+}
+
+#[test]
+fn open_publishes_diagnostics_with_utf16_columns() {
+    let mut client = Client::initialized();
+    let uri = "untitled:emoji.bal";
+    let messages = client.open(uri, "string s = \"😀\"; int bad_name = 1;\n");
+
+    let diagnostics = published(&messages, uri);
+    assert_eq!(diagnostics.len(), 1, "{diagnostics:?}");
+    let diagnostic = &diagnostics[0];
+    // The emoji is one character but two UTF-16 code units.
+    assert_eq!(range(diagnostic), [0, 21, 0, 29]);
+    assert_eq!(diagnostic["severity"].as_usize(), Some(2));
+    assert_eq!(diagnostic["code"].as_str(), Some("camel_case"));
+    assert_eq!(diagnostic["source"].as_str(), Some("blazelint"));
+    let message = diagnostic["message"].as_str().unwrap();
+    assert!(
+        message.contains("\nhelp: Rename `bad_name` to `badName`"),
+        "{message}"
+    );
+    assert_eq!(messages[0]["params"]["version"].as_usize(), Some(1));
+}
+
+#[test]
+fn incremental_changes_are_applied_in_utf16_units() {
+    let mut client = Client::initialized();
+    let uri = "untitled:edit.bal";
+    let opened = client.open(uri, "string s = \"😀\";\nint value = 1;\n");
+    assert!(published(&opened, uri).is_empty());
+
+    // Rename `value` on the second line.
+    let renamed = client.change(uri, 2, Some(([1, 4], [1, 9])), "bad_value");
+    let diagnostics = published(&renamed, uri);
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(range(&diagnostics[0]), [1, 4, 1, 13]);
+    assert_eq!(renamed[0]["params"]["version"].as_usize(), Some(2));
+
+    // Replace the quoted emoji, UTF-16 columns 11..15, with a number.
+    let retyped = client.change(uri, 3, Some(([0, 11], [0, 15])), "1");
+    let diagnostics = published(&retyped, uri);
+    assert_eq!(diagnostics.len(), 2, "{diagnostics:?}");
+    assert_eq!(diagnostics[0]["severity"].as_usize(), Some(1));
+    assert_eq!(range(&diagnostics[0])[0], 0);
+
+    // A change without a range replaces the whole document.
+    let replaced = client.change(uri, 4, None, "int fine = 1;\n");
+    assert!(published(&replaced, uri).is_empty());
+}
+
+#[test]
+fn closing_a_document_clears_its_diagnostics() {
+    let mut client = Client::initialized();
+    let uri = "untitled:close.bal";
+    client.open(uri, "int bad_name = 1;\n");
+    let closed = client.notify(
+        "textDocument/didClose",
+        msg("{'textDocument':{'uri':'untitled:close.bal'}}"),
+    );
+    assert!(published(&closed, uri).is_empty());
+    assert!(closed[0]["params"].get("version").is_none());
+
+    // Edits to a closed document are ignored with a log message.
+    let ignored = client.change(uri, 2, None, "int x = 1;\n");
+    assert_eq!(ignored.len(), 1);
+    assert_eq!(ignored[0]["method"].as_str(), Some("window/logMessage"));
+}
+
+#[test]
+fn protocol_errors_are_reported_to_the_client() {
+    let mut server = Server::new();
+    let early = server.handle(&msg("{'jsonrpc':'2.0','id':7,'method':'shutdown'}"));
+    assert_eq!(early[0]["error"]["code"].as_f64(), Some(-32002.0));
+    assert_eq!(early[0]["id"].as_usize(), Some(7));
+
+    let reply = server.handle(&msg(
+        "{'jsonrpc':'2.0','id':'a','method':'initialize','params':{}}",
+    ));
+    let capabilities = &reply[0]["result"]["capabilities"];
+    assert_eq!(
+        capabilities["textDocumentSync"]["change"].as_usize(),
+        Some(2)
+    );
+    assert_eq!(capabilities["positionEncoding"].as_str(), Some("utf-16"));
+    assert_eq!(reply[0]["id"].as_str(), Some("a"));
+
+    let unknown = server.handle(&msg(
+        "{'jsonrpc':'2.0','id':2,'method':'textDocument/hover','params':{}}",
+    ));
+    assert_eq!(unknown[0]["error"]["code"].as_f64(), Some(-32601.0));
+    assert!(server
+        .handle(&msg(
+            "{'jsonrpc':'2.0','method':'$/cancelRequest','params':{'id':2}}"
+        ))
+        .is_empty());
+
+    server.handle(&msg("{'jsonrpc':'2.0','id':3,'method':'shutdown'}"));
+    let late = server.handle(&msg("{'jsonrpc':'2.0','id':4,'method':'shutdown'}"));
+    assert_eq!(late[0]["error"]["code"].as_f64(), Some(-32600.0));
+    assert_eq!(server.exit_code(), None);
+    server.handle(&msg("{'jsonrpc':'2.0','method':'exit'}"));
+    assert_eq!(server.exit_code(), Some(0));
+}
+
+#[test]
+fn documents_use_the_nearest_config() {
+    let dir = tempfile::tempdir().expect("temp dir");
+    let root = dir.path().canonicalize().unwrap();
+    fs::write(
+        root.join(".blazerc"),
+        "[rules]\ncamel_case = false\nbogus = 1\n",
+    )
+    .unwrap();
+    let file_uri = format!("file://{}/main.bal", root.display());
+    let config_uri = format!("file://{}/.blazerc", root.display());
+
+    let mut client = Client::initialized();
+    let messages = client.open(&file_uri, "int bad_name = 1;\n");
+    assert!(published(&messages, &file_uri).is_empty());
+
+    let config_diagnostics = published(&messages, &config_uri);
+    assert_eq!(config_diagnostics.len(), 2, "{config_diagnostics:?}");
+    assert_eq!(range(&config_diagnostics[1]), [2, 0, 2, 5]);
+}
+
+/// Frames `messages` as a client would write them to the server's stdin.
+fn script(messages: &[&str]) -> Vec<u8> {
+    let mut input = Vec::new();
+    for message in messages {
+        write_message(&mut input, &msg(message)).unwrap();
+    }
+    input
+}
+
+/// Decodes every framed message the server wrote.
+fn replies(output: &[u8]) -> Vec<JsonValue> {
+    let mut cursor = Cursor::new(output);
+    let mut messages = Vec::new();
+    while let Some(body) = read_message(&mut cursor).expect("well-framed output") {
+        messages.push(JsonValue::parse(&body).expect("valid JSON"));
+    }
+    messages
+}
+
+#[test]
+fn serve_frames_messages_and_exits_cleanly() {
+    let mut input = script(&[
+        "{'jsonrpc':'2.0','id':1,'method':'initialize','params':{}}",
+        "{'jsonrpc':'2.0','method':'initialized','params':{}}",
+    ]);
+    input.extend(
+        b"Content-Length: 9\r\nContent-Type: application/vscode-jsonrpc\r\n\r\n{\"broken\"",
+    );
+    input.extend(script(&[
+        "{'jsonrpc':'2.0','id':2,'method':'shutdown'}",
+        "{'jsonrpc':'2.0','method':'exit'}",
+        "{'jsonrpc':'2.0','id':3,'method':'never-read'}",
+    ]));
+
+    let mut output = Vec::new();
+    let code = serve(Cursor::new(input), &mut output).expect("serve");
+    assert_eq!(code, 0);
+
+    let messages = replies(&output);
+    assert_eq!(messages.len(), 3, "{messages:?}");
+    assert_eq!(messages[1]["error"]["code"].as_f64(), Some(-32700.0));
+    assert_eq!(messages[1]["id"], JsonValue::Null);
+    assert_eq!(messages[2]["id"].as_usize(), Some(2));
+}
+
+#[test]
+fn serve_reports_an_exit_without_shutdown() {
+    let input = script(&["{'jsonrpc':'2.0','method':'exit'}"]);
+    assert_eq!(serve(Cursor::new(input), Vec::new()).unwrap(), 1);
+    assert_eq!(serve(Cursor::new(Vec::new()), Vec::new()).unwrap(), 1);
+
+    let truncated = b"Content-Length: 40\r\n\r\n{}".to_vec();
+    assert!(serve(Cursor::new(truncated), Vec::new()).is_err());
+}
+
+#[test]
+fn lsp_subcommand_serves_stdio() {
+    let input = script(&[
+        "{'jsonrpc':'2.0','id':1,'method':'initialize','params':{}}",
+        "{'jsonrpc':'2.0','method':'initialized','params':{}}",
+        "{'jsonrpc':'2.0','method':'textDocument/didOpen','params':{'textDocument':\
+         {'uri':'untitled:a.bal','languageId':'ballerina','version':1,'text':'int bad_name = 1;'}}}",
+        "{'jsonrpc':'2.0','id':2,'method':'shutdown'}",
+        "{'jsonrpc':'2.0','method':'exit'}",
+    ]);
+    let output = Command::cargo_bin("blazelint")
+        .expect("binary")
+        .arg("lsp")
+        .write_stdin(input)
+        .output()
+        .expect("run blazelint lsp");
+
+    assert!(output.status.success());
+    let messages = replies(&output.stdout);
+    assert_eq!(published(&messages, "untitled:a.bal").len(), 1);
+}
+
+#[test]
+fn json_parser_handles_escapes_and_rejects_garbage() {
+    let value =
+        JsonValue::parse(r#" {"a": [1, -2.5e1, true, null], "s": "q\"\\\n\u00e9\ud83d\ude00"} "#)
+            .expect("valid JSON");
+    assert_eq!(value["a"].as_array().map(<[_]>::len), Some(4));
+    assert_eq!(value["a"].as_array().unwrap()[1].as_f64(), Some(-25.0));
+    assert_eq!(value["s"].as_str(), Some("q\"\\\né😀"));
+    assert_eq!(JsonValue::parse(&value.to_string()), Ok(value));
+
+    for bad in ["", "{", "[1,]", "{'a':1}", "\"\\ud83d\"", "1 2", "tru"] {
+        assert!(JsonValue::parse(bad).is_err(), "{bad}");
+    }
+}
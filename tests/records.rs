@@ -0,0 +1,258 @@
+use blazelint::ast::{Expr, Stmt, TypeDescriptor};
+use blazelint::codes;
+use blazelint::format::{format_source, FormatOptions};
+use blazelint::semantic::{SymbolKind, Type};
+use blazelint::{lint_source, parse, tokenize, LintOptions};
+
+fn parse_program(source: &str) -> Vec<Stmt> {
+    let tokens = tokenize(source).expect("tokens");
+    let (ast, diagnostics) = parse(&tokens);
+    assert!(diagnostics.is_empty(), "{diagnostics:?}");
+    ast
+}
+
+/// `(code, message)` of every diagnostic for `source`.
+fn diagnostics(source: &str) -> Vec<(&'static str, String)> {
+    lint_source(source, "test.bal", &LintOptions::default())
+        .diagnostics
+        .into_iter()
+        .map(|diagnostic| (diagnostic.code.expect("code"), diagnostic.message))
+        .collect()
+}
+
+const PERSON: &str = "type Person record {|\n\
+                      \x20   string name;\n\
+                      \x20   int age?;\n\
+                      \x20   boolean active = true;\n\
+                      |};\n";
+
+#[test]
+fn parses_closed_and_open_record_definitions() {
+    let program = parse_program(&format!(
+        "{PERSON}public type Config record {{\n    string host;\n    string...;\n}};\n"
+    ));
+
+    let Stmt::TypeDefinition {
+        is_public: false,
+        name,
+        type_desc:
+            TypeDescriptor::Record {
+                fields,
+                closed: true,
+                rest: None,
+            },
+        ..
+    } = &program[0]
+    else {
+        panic!("expected a closed record definition: {:?}", program[0]);
+    };
+    assert_eq!(name, "Person");
+    let summary: Vec<(&str, bool, bool)> = fields
+        .iter()
+        .map(|field| (field.name.as_str(), field.optional, field.default.is_some()))
+        .collect();
+    assert_eq!(
+        summary,
+        [
+            ("name", false, false),
+            ("age", true, false),
+            ("active", false, true)
+        ]
+    );
+
+    let Stmt::TypeDefinition {
+        is_public: true,
+        type_desc:
+            TypeDescriptor::Record {
+                closed: false,
+                rest: Some(rest),
+                ..
+            },
+        ..
+    } = &program[1]
+    else {
+        panic!("expected an open record definition: {:?}", program[1]);
+    };
+    assert_eq!(**rest, TypeDescriptor::Basic("string".to_string()));
+}
+
+#[test]
+fn type_definitions_are_module_level_only() {
+    let tokens = tokenize("function main() {\n    type Id int;\n}\n").expect("tokens");
+    let (_, diagnostics) = parse(&tokens);
+    assert_eq!(diagnostics[0].code, Some(codes::UNEXPECTED_TOKEN));
+    assert_eq!(
+        diagnostics[0].message,
+        "Type definitions are only allowed at module level"
+    );
+}
+
+#[test]
+fn record_values_and_field_access_are_checked() {
+    let source = format!(
+        "{PERSON}\n\
+         function main() {{\n\
+         \x20   Person ok = {{name: \"Ada\", age: 36}};\n\
+         \x20   Person[] people = [{{name: \"Bob\"}}, {{nmae: \"Eve\"}}];\n\
+         \x20   Person wrong = {{name: 1}};\n\
+         \x20   string name = ok.name;\n\
+         \x20   int age = ok.age;\n\
+         \x20   ok.email;\n\
+         }}\n"
+    );
+    assert_eq!(
+        diagnostics(&source),
+        [
+            (
+                codes::UNKNOWN_FIELD,
+                "Record type Person has no field 'nmae'".to_string()
+            ),
+            (
+                codes::MISSING_FIELD,
+                "Missing required field 'name' in value of record type Person".to_string()
+            ),
+            (
+                codes::TYPE_MISMATCH,
+                "Type mismatch in field 'name': expected string, found int".to_string()
+            ),
+            (
+                codes::UNKNOWN_FIELD,
+                "Record type Person has no field 'email'".to_string()
+            ),
+        ]
+    );
+}
+
+#[test]
+fn map_values_are_checked_against_their_record_type() {
+    let source = format!(
+        "{PERSON}\n\
+         function main() {{\n\
+         \x20   map<Person> ok = {{ada: {{name: \"Ada\"}}, bob: {{name: \"Bob\", age: 7}}}};\n\
+         \x20   map<Person> wrong = {{eve: {{nmae: \"Eve\"}}, joe: 1}};\n\
+         }}\n"
+    );
+    assert_eq!(
+        diagnostics(&source),
+        [
+            (
+                codes::UNKNOWN_FIELD,
+                "Record type Person has no field 'nmae'".to_string()
+            ),
+            (
+                codes::MISSING_FIELD,
+                "Missing required field 'name' in value of record type Person".to_string()
+            ),
+            (
+                codes::TYPE_MISMATCH,
+                "Type mismatch in map value: expected Person, found int".to_string()
+            ),
+        ]
+    );
+}
+
+#[test]
+fn record_field_problems_point_at_the_key() {
+    let source = "type Name record {|\n    string first_name;\n|};\n\n\
+                  Name a = {first_name: \"A\", bogus: 1};\n\
+                  Name b = {\"first_name\": 2};\n";
+    let report = lint_source(source, "test.bal", &LintOptions::default());
+    let spans: Vec<(&str, &str)> = report
+        .diagnostics
+        .iter()
+        .map(|diagnostic| {
+            (
+                diagnostic.code.expect("code"),
+                &source[diagnostic.span.clone()],
+            )
+        })
+        .collect();
+    assert_eq!(
+        spans,
+        [
+            (codes::UNKNOWN_FIELD, "bogus"),
+            (codes::TYPE_MISMATCH, "\"first_name\""),
+        ]
+    );
+    let value = &report.diagnostics[1].secondary[0];
+    assert_eq!(&source[value.span.clone()], "2");
+}
+
+#[test]
+fn open_records_accept_fields_of_their_rest_type() {
+    let source = "type Config record {\n    string host;\n    int...;\n};\n\n\
+                  Config ok = {host: \"localhost\", port: 8080};\n\
+                  Config wrong = {host: \"localhost\", mode: \"debug\"};\n";
+    assert_eq!(
+        diagnostics(source),
+        [(
+            codes::TYPE_MISMATCH,
+            "Type mismatch in field 'mode': expected int, found string".to_string()
+        )]
+    );
+
+    let source = "type Tags record {\n    string name;\n};\n\n\
+                  Tags tags = {name: \"a\", color: \"red\", size: 3};\n";
+    assert_eq!(diagnostics(source), []);
+}
+
+#[test]
+fn defaults_are_checked_and_types_resolve_before_their_definition() {
+    let source = "Node head = {value: 1};\n\n\
+                  type Node record {\n    int value = \"zero\";\n    Node? next;\n};\n";
+    assert_eq!(
+        diagnostics(source),
+        [
+            (
+                codes::MISSING_FIELD,
+                "Missing required field 'next' in value of record type Node".to_string()
+            ),
+            (
+                codes::TYPE_MISMATCH,
+                "Type mismatch in default of field 'value': expected int, found string".to_string()
+            ),
+        ]
+    );
+}
+
+#[test]
+fn type_definitions_are_recorded_in_the_model() {
+    let source = format!("{PERSON}\nPerson p = {{name: \"Ada\"}};\n");
+    let report = lint_source(&source, "test.bal", &LintOptions::default());
+    assert!(report.is_clean(), "{:?}", report.diagnostics);
+
+    let person = &report.semantic.symbols()[0];
+    assert_eq!(person.kind, SymbolKind::Type);
+    let Type::Record(record) = &person.ty else {
+        panic!("expected a record type: {:?}", person.ty);
+    };
+    assert!(record.closed);
+    assert!(record.field("name").is_some_and(|field| field.required));
+    assert!(record.field("age").is_some_and(|field| !field.required));
+
+    let Stmt::VarDecl {
+        initializer: Some(value @ Expr::MapLiteral { .. }),
+        ..
+    } = &report.ast[1]
+    else {
+        panic!("expected a declaration");
+    };
+    assert_eq!(report.semantic.type_of(value), Some(&person.ty));
+}
+
+#[test]
+fn formats_one_field_per_line() {
+    let source = "type Person record {|string name;int age?;string...;|};\n\
+                  type Config record{int port=8080;};\n";
+    assert_eq!(
+        format_source(source, &FormatOptions::default()).expect("formats"),
+        "type Person record {|\n\
+         \x20   string name;\n\
+         \x20   int age?;\n\
+         \x20   string...;\n\
+         |};\n\
+         type Config record {\n\
+         \x20   int port = 8080;\n\
+         };\n"
+    );
+}
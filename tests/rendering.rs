@@ -0,0 +1,167 @@
+use blazelint::errors::{Diagnostic, DiagnosticKind, Severity};
+use blazelint::linter::default_rules;
+use blazelint::report::{
+    compute_line_starts, json_report, render_diagnostic, sarif_report, FileDiagnostics,
+};
+use blazelint::{lint_source, LintOptions};
+
+fn render(source: &str, diagnostic: &Diagnostic) -> String {
+    render_diagnostic(
+        "a.bal",
+        source,
+        &compute_line_starts(source),
+        diagnostic,
+        false,
+    )
+}
+
+fn render_first(source: &str) -> String {
+    let report = lint_source(source, "a.bal", &LintOptions::default());
+    render(source, &report.diagnostics[0])
+}
+
+#[test]
+fn redeclaration_points_at_the_first_declaration() {
+    let source = "function main() {\n    int count = 1;\n    int count = 2;\n}\n";
+    assert_eq!(
+        render_first(source),
+        "semantic error[BL3001]: Redeclaration of variable 'count'\n\
+         \x20--> a.bal:3:9-3:14\n\
+         \x20 |\n\
+         2 |     int count = 1;\n\
+         \x20 |         ----- 'count' first declared here\n\
+         3 |     int count = 2;\n\
+         \x20 |         ^^^^^ redeclared here\n"
+    );
+}
+
+#[test]
+fn multi_line_spans_are_connected_in_the_margin() {
+    let source = "int a = 1;\nfunction f() {\n    int b = 2;\n}\n";
+    let start = source.find("function").expect("function");
+    let diagnostic = Diagnostic::new(
+        DiagnosticKind::Linter,
+        "Whole function",
+        start..source.len(),
+    )
+    .with_severity(Severity::Warning)
+    .with_label("this function");
+    assert_eq!(
+        render(source, &diagnostic),
+        "linter warning: Whole function\n\
+         \x20--> a.bal:2:1-5:1\n\
+         \x20 |\n\
+         2 |   function f() {\n\
+         \x20 |  _^\n\
+         3 | |     int b = 2;\n\
+         4 | | }\n\
+         \x20 | |_^ this function\n"
+    );
+}
+
+#[test]
+fn tabs_are_expanded_before_underlining() {
+    let source = "function main() {\n\tint bad_name = 1;\n}\n";
+    let rendered = render_first(source);
+    assert!(
+        rendered.contains(
+            "2 |     int bad_name = 1;\n\
+             \x20 |         ^^^^^^^^\n"
+        ),
+        "{rendered}"
+    );
+    assert!(
+        rendered.contains("  = help: Rename `bad_name` to `badName`\n"),
+        "{rendered}"
+    );
+}
+
+#[test]
+fn hanging_labels_and_distant_lines() {
+    let source = "int a = 1;\n\n\n\n\nint b = a + c;\n";
+    let start = source.find("a +").expect("operand");
+    let c = source.find("c;").expect("operand");
+    let first = source.find("a =").expect("declaration");
+    let diagnostic = Diagnostic::new(DiagnosticKind::Semantic, "Bad sum", start..c + 1)
+        .with_secondary(start..start + 1, "left")
+        .with_secondary(c..c + 1, "right")
+        .with_secondary(first..first + 1, "declared")
+        .with_help("check the operands");
+    assert_eq!(
+        render(source, &diagnostic),
+        "semantic error: Bad sum\n\
+         \x20--> a.bal:6:9-6:14\n\
+         \x20 |\n\
+         1 | int a = 1;\n\
+         \x20 |     - declared\n\
+         ...\n\
+         6 | int b = a + c;\n\
+         \x20 |         ^^^^^ right\n\
+         \x20 |         |\n\
+         \x20 |         left\n\
+         \x20 = help: check the operands\n"
+    );
+}
+
+#[test]
+fn undeclared_names_suggest_similar_variables() {
+    let source = "function main() {\n    int count = 1;\n    int next = cuont + 1;\n}\n";
+    let report = lint_source(source, "a.bal", &LintOptions::default());
+    let diagnostic = report
+        .diagnostics
+        .iter()
+        .find(|d| d.message.contains("undeclared"))
+        .expect("undeclared variable");
+    assert_eq!(
+        diagnostic.help,
+        ["a variable with a similar name exists: 'count'"]
+    );
+}
+
+#[test]
+fn colors_are_ansi_escapes() {
+    let source = "int bad_name = 1;\n";
+    let report = lint_source(source, "a.bal", &LintOptions::default());
+    let rendered = render_diagnostic(
+        "a.bal",
+        source,
+        &compute_line_starts(source),
+        &report.diagnostics[0],
+        true,
+    );
+    assert!(
+        rendered.starts_with("\x1b[1;33mlinter warning[BL4001]\x1b[0m"),
+        "{rendered:?}"
+    );
+    assert!(
+        rendered.contains("\x1b[1;33m^^^^^^^^\x1b[0m"),
+        "{rendered:?}"
+    );
+}
+
+#[test]
+fn machine_formats_carry_related_spans_and_help() {
+    let source = "function main() {\n    final int limit = 1;\n    limit = 2;\n}\n";
+    let report = lint_source(source, "a.bal", &LintOptions::default());
+    let files = [FileDiagnostics {
+        file_name: "a.bal",
+        source,
+        diagnostics: &report.diagnostics,
+    }];
+
+    let json = json_report(&files, 1);
+    let diagnostic = &json["diagnostics"].as_array().expect("diagnostics")[0];
+    let related = &diagnostic["related"].as_array().expect("related")[0];
+    assert_eq!(related["message"].as_str(), Some("declared final here"));
+    assert_eq!(related["range"]["start"]["line"].as_usize(), Some(2));
+    assert_eq!(
+        diagnostic["help"].as_array().expect("help")[0].as_str(),
+        Some("remove `final` from the declaration of 'limit' to allow assignment")
+    );
+
+    let sarif = sarif_report(&files, &default_rules()).to_string();
+    assert!(
+        sarif.contains("\"relatedLocations\":[{\"id\":0,\"physicalLocation\""),
+        "{sarif}"
+    );
+}
@@ -0,0 +1,127 @@
+use blazelint::ast::{Expr, Stmt};
+use blazelint::semantic::{ScopeKind, SymbolKind, Type};
+use blazelint::{lint_source, LintOptions};
+
+/// Span of the `nth` occurrence (0-based) of `needle` in `source`.
+fn nth(source: &str, needle: &str, nth: usize) -> std::ops::Range<usize> {
+    let start = source
+        .match_indices(needle)
+        .nth(nth)
+        .unwrap_or_else(|| panic!("occurrence {nth} of {needle:?}"))
+        .0;
+    start..start + needle.len()
+}
+
+#[test]
+fn references_resolve_to_the_innermost_declaration() {
+    let source = "int count = 1;\n\
+                  function f() {\n\
+                  int count = 2;\n\
+                  count = count + 1;\n\
+                  }\n\
+                  function g() returns int {\n\
+                  f();\n\
+                  return count;\n\
+                  }";
+    let report = lint_source(source, "test.bal", &LintOptions::default());
+    let model = &report.semantic;
+
+    let outer = model
+        .resolve(&nth(source, "count", 4))
+        .expect("outer count");
+    assert_eq!(outer.kind, SymbolKind::Variable);
+    assert_eq!(outer.scope_depth, 0);
+
+    let inner = model.resolve(&nth(source, "count", 2)).expect("assignment");
+    assert_eq!(inner.scope_depth, 1);
+    assert_eq!(inner.name_span, Some(nth(source, "count", 1)));
+    assert_eq!(
+        model.references(inner.id),
+        [nth(source, "count", 2), nth(source, "count", 3)]
+    );
+    assert_eq!(model.references(outer.id), [nth(source, "count", 4)]);
+
+    let call = nth(source, "f();", 0);
+    let f = model
+        .resolve(&(call.start..call.start + 1))
+        .expect("call to f");
+    assert_eq!(f.kind, SymbolKind::Function);
+    assert_eq!(
+        f.name_span,
+        Some(nth(source, "f()", 0).start..nth(source, "f()", 0).start + 1)
+    );
+}
+
+#[test]
+fn expression_types_are_recorded() {
+    let source = "function f(int n) returns float {\n\
+                  float half = 0.5;\n\
+                  return half * 2.0;\n\
+                  }";
+    let report = lint_source(source, "test.bal", &LintOptions::default());
+    assert!(report.is_clean(), "{:?}", report.diagnostics);
+
+    let Stmt::Function { body, .. } = &report.ast[0] else {
+        panic!("expected a function");
+    };
+    let Stmt::Return {
+        value: Some(value), ..
+    } = &body[1]
+    else {
+        panic!("expected a return");
+    };
+    assert_eq!(report.semantic.type_of(value), Some(&Type::Float));
+    let Expr::Binary { left, .. } = value else {
+        panic!("expected a binary expression");
+    };
+    assert_eq!(report.semantic.type_of(left), Some(&Type::Float));
+}
+
+#[test]
+fn scopes_form_a_tree() {
+    let source = "function f(int n) {\n\
+                  while (n > 0) {\n\
+                  int step = 1;\n\
+                  }\n\
+                  }";
+    let report = lint_source(source, "test.bal", &LintOptions::default());
+    let model = &report.semantic;
+
+    let kinds: Vec<ScopeKind> = model.scopes().iter().map(|scope| scope.kind).collect();
+    assert_eq!(
+        kinds,
+        [ScopeKind::Module, ScopeKind::Function, ScopeKind::Loop]
+    );
+
+    let step = model
+        .symbols()
+        .iter()
+        .find(|symbol| symbol.name == "step")
+        .expect("step declared");
+    let loop_scope = model.scope(step.scope);
+    assert_eq!(loop_scope.kind, ScopeKind::Loop);
+    assert_eq!(loop_scope.symbols, [step.id]);
+
+    let function_scope = model.scope(loop_scope.parent.expect("parent"));
+    assert_eq!(function_scope.kind, ScopeKind::Function);
+    let param = model.symbol(function_scope.symbols[0]);
+    assert_eq!(
+        (param.name.as_str(), param.kind),
+        ("n", SymbolKind::Parameter)
+    );
+    assert_eq!(function_scope.parent.map(|id| id.index()), Some(0));
+}
+
+#[test]
+fn model_is_built_without_semantic_diagnostics() {
+    let mut options = LintOptions::default();
+    options.semantic = false;
+    let report = lint_source("int a = \"text\";\nint b = a;", "test.bal", &options);
+
+    assert!(report.diagnostics.is_empty(), "{:?}", report.diagnostics);
+    let a = report
+        .semantic
+        .resolve(&(24..25))
+        .expect("reference to a resolves");
+    assert_eq!(a.name, "a");
+}
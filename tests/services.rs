@@ -0,0 +1,344 @@
+use blazelint::ast::{
+    walk, ArrayDimension, AttachPoint, ClassMember, Expr, PathSegment, Stmt, TypeDescriptor,
+    VisitContext, Visitor,
+};
+use blazelint::codes;
+use blazelint::format::{format_source, FormatOptions};
+use blazelint::semantic::{SymbolKind, Type};
+use blazelint::{lint_source, parse, syntax_tree, tokenize, LintOptions};
+
+fn parse_program(source: &str) -> Vec<Stmt> {
+    let tokens = tokenize(source).expect("tokens");
+    let (ast, diagnostics) = parse(&tokens);
+    assert!(diagnostics.is_empty(), "{diagnostics:?}");
+    ast
+}
+
+/// `(code, message)` of every diagnostic for `source`.
+fn diagnostics(source: &str) -> Vec<(&'static str, String)> {
+    lint_source(source, "test.bal", &LintOptions::default())
+        .diagnostics
+        .into_iter()
+        .map(|diagnostic| (diagnostic.code.expect("code"), diagnostic.message))
+        .collect()
+}
+
+const USERS: &str = "import ballerina/http;\n\
+                     \n\
+                     type User record {\n\
+                     \x20   string name;\n\
+                     };\n\
+                     \n\
+                     listener http:Listener ep = new (9090);\n\
+                     \n\
+                     isolated service /api/v1 on ep {\n\
+                     \x20   private int hits = 0;\n\
+                     \n\
+                     \x20   resource function get users/[string id]() returns User {\n\
+                     \x20       self.hits += 1;\n\
+                     \x20       return {name: id};\n\
+                     \x20   }\n\
+                     \n\
+                     \x20   isolated resource function post users(User user) returns User {\n\
+                     \x20       return user;\n\
+                     \x20   }\n\
+                     \n\
+                     \x20   resource function get files/[string... parts]() returns string {\n\
+                     \x20       return parts[0];\n\
+                     \x20   }\n\
+                     \n\
+                     \x20   resource function get .() returns string {\n\
+                     \x20       return \"root\";\n\
+                     \x20   }\n\
+                     }\n";
+
+#[test]
+fn parses_listener_declarations() {
+    let program = parse_program(
+        "listener http:Listener ep = new (9090);\n\
+         public listener secure = new http:Listener(9443);\n",
+    );
+
+    let Stmt::Listener {
+        is_public: false,
+        name,
+        type_annotation: Some(TypeDescriptor::Basic(type_name)),
+        initializer:
+            Expr::New {
+                class_name: None,
+                arguments,
+                ..
+            },
+        ..
+    } = &program[0]
+    else {
+        panic!("expected a typed listener: {:?}", program[0]);
+    };
+    assert_eq!(name, "ep");
+    assert_eq!(type_name, "http:Listener");
+    assert_eq!(arguments.len(), 1);
+
+    let Stmt::Listener {
+        is_public: true,
+        name,
+        type_annotation: None,
+        initializer: Expr::New { class_name, .. },
+        ..
+    } = &program[1]
+    else {
+        panic!("expected an untyped listener: {:?}", program[1]);
+    };
+    assert_eq!(name, "secure");
+    assert_eq!(class_name.as_deref(), Some("http:Listener"));
+}
+
+#[test]
+fn parses_service_attach_points_and_listeners() {
+    let program = parse_program(
+        "service / on ep {\n}\n\
+         service \"greeter\" on ep, new http:Listener(8080) {\n}\n\
+         service http:Service on ep {\n}\n",
+    );
+    let services: Vec<(Option<&AttachPoint>, usize, bool)> = program
+        .iter()
+        .map(|stmt| match stmt {
+            Stmt::Service {
+                type_annotation,
+                attach_point,
+                listeners,
+                ..
+            } => (
+                attach_point.as_ref(),
+                listeners.len(),
+                type_annotation.is_some(),
+            ),
+            other => panic!("expected a service: {other:?}"),
+        })
+        .collect();
+    assert!(matches!(
+        services[0],
+        (Some(AttachPoint::Path { segments, .. }), 1, false) if segments.is_empty()
+    ));
+    assert!(matches!(
+        services[1],
+        (Some(AttachPoint::Name { name, .. }), 2, false) if name == "greeter"
+    ));
+    assert!(matches!(services[2], (None, 1, true)));
+
+    let Stmt::Service {
+        qualifiers,
+        attach_point: Some(AttachPoint::Path { segments, .. }),
+        ..
+    } = &parse_program(USERS)[3]
+    else {
+        panic!("expected a service with a path");
+    };
+    assert_eq!(qualifiers.len(), 1);
+    assert_eq!(segments, &["api", "v1"]);
+}
+
+#[test]
+fn parses_resource_accessors_and_paths() {
+    let program = parse_program(USERS);
+    let Stmt::Service { members, .. } = &program[3] else {
+        panic!("expected a service: {:?}", program[3]);
+    };
+    assert!(matches!(&members[0], ClassMember::Field(field) if field.name == "hits"));
+
+    let resources: Vec<(&str, Vec<String>)> = members[1..]
+        .iter()
+        .map(|member| match member {
+            ClassMember::Method(Stmt::ResourceFunction { accessor, path, .. }) => {
+                let path = path
+                    .iter()
+                    .map(|segment| match segment {
+                        PathSegment::Name { name, .. } => name.clone(),
+                        PathSegment::Param { name, .. } => format!("[{name}]"),
+                        PathSegment::Rest { name, .. } => format!("[{name}...]"),
+                    })
+                    .collect();
+                (accessor.as_str(), path)
+            }
+            other => panic!("expected a resource function: {other:?}"),
+        })
+        .collect();
+    assert_eq!(
+        resources,
+        [
+            ("get", vec!["users".to_string(), "[id]".to_string()]),
+            ("post", vec!["users".to_string()]),
+            ("get", vec!["files".to_string(), "[parts...]".to_string()]),
+            ("get", vec![]),
+        ]
+    );
+}
+
+#[test]
+fn listeners_and_services_are_module_level_only() {
+    for (source, message) in [
+        (
+            "function main() {\n    listener http:Listener ep = new (9090);\n}\n",
+            "Listener declarations are only allowed at module level",
+        ),
+        (
+            "function main() {\n    service / on ep {\n    }\n}\n",
+            "Service declarations are only allowed at module level",
+        ),
+    ] {
+        let tokens = tokenize(source).expect("tokens");
+        let (_, diagnostics) = parse(&tokens);
+        assert_eq!(diagnostics[0].code, Some(codes::UNEXPECTED_TOKEN));
+        assert_eq!(diagnostics[0].message, message);
+    }
+}
+
+#[test]
+fn resource_parameters_and_service_fields_resolve() {
+    let report = lint_source(USERS, "test.bal", &LintOptions::default());
+    assert!(report.is_clean(), "{:?}", report.diagnostics);
+
+    let symbols = report.semantic.symbols();
+    let ep = symbols
+        .iter()
+        .find(|symbol| symbol.name == "ep")
+        .expect("listener symbol");
+    assert_eq!(ep.kind, SymbolKind::Listener);
+    assert_eq!(ep.ty, Type::Unknown("http:Listener".to_string()));
+
+    let parameter = |name: &str| {
+        symbols
+            .iter()
+            .find(|symbol| symbol.name == name && symbol.kind == SymbolKind::Parameter)
+            .map(|symbol| symbol.ty.clone())
+    };
+    assert_eq!(parameter("id"), Some(Type::String));
+    assert_eq!(
+        parameter("parts"),
+        Some(Type::Array(Box::new(Type::String)))
+    );
+    assert!(matches!(parameter("user"), Some(Type::Record(_))));
+
+    let source = "listener ep = new (9090);\n\
+                  listener int port = \"9090\";\n\
+                  \n\
+                  service / on ep, missing {\n\
+                  \x20   int count = 0;\n\
+                  \n\
+                  \x20   resource function get [int id]() returns int {\n\
+                  \x20       self.cuont += id;\n\
+                  \x20       return self.count;\n\
+                  \x20   }\n\
+                  }\n";
+    assert_eq!(
+        diagnostics(source),
+        [
+            (
+                codes::TYPE_MISMATCH,
+                "Type mismatch in initializer: expected int, found string".to_string()
+            ),
+            (
+                codes::UNDECLARED_VARIABLE,
+                "Use of undeclared variable 'missing'".to_string()
+            ),
+            (
+                codes::UNKNOWN_FIELD,
+                "Service has no field 'cuont'".to_string()
+            ),
+        ]
+    );
+}
+
+/// Collects the accessor, path length, and declared return type of every
+/// resource function, as a lint rule on resource functions would see them.
+#[derive(Default)]
+struct Resources {
+    found: Vec<(String, usize, Option<TypeDescriptor>, bool)>,
+}
+
+impl<'a> Visitor<'a> for Resources {
+    fn enter_stmt(&mut self, stmt: &'a Stmt, _cx: &VisitContext<'a>) {
+        if let Stmt::ResourceFunction {
+            accessor,
+            path,
+            return_type,
+            ..
+        } = stmt
+        {
+            self.found
+                .push((accessor.clone(), path.len(), return_type.clone(), false));
+        }
+    }
+
+    fn enter_expr(&mut self, _expr: &'a Expr, cx: &VisitContext<'a>) {
+        if let (Some(last), Some(Stmt::ResourceFunction { .. })) =
+            (self.found.last_mut(), cx.enclosing_function())
+        {
+            last.3 = true;
+        }
+    }
+}
+
+#[test]
+fn walk_visits_resource_functions() {
+    let program = parse_program(
+        "service /api on ep {\n\
+         \x20   resource function get users() returns User[]|error {\n\
+         \x20       return [];\n\
+         \x20   }\n\
+         \n\
+         \x20   resource function delete users/[string id]() {\n\
+         \x20   }\n\
+         }\n",
+    );
+    let mut resources = Resources::default();
+    walk(&program, &mut resources);
+
+    let user = TypeDescriptor::Basic("User".to_string());
+    assert_eq!(
+        resources.found,
+        [
+            (
+                "get".to_string(),
+                1,
+                Some(TypeDescriptor::Union(vec![
+                    TypeDescriptor::Array {
+                        element_type: Box::new(user),
+                        dimension: Some(ArrayDimension::Open),
+                    },
+                    TypeDescriptor::Basic("error".to_string()),
+                ])),
+                true,
+            ),
+            ("delete".to_string(), 2, None, false),
+        ]
+    );
+}
+
+#[test]
+fn formats_resource_paths_and_attach_points() {
+    let source = "listener http:Listener ep=new (9090);\n\
+                  service /api/v1 on ep{resource function get users/[string id]() returns string{return id;}\
+                  resource function get [string... rest]() returns int{return 0;}\
+                  resource function get .() returns int{return 1;}}\n\
+                  service / on ep{}\n";
+    let formatted = format_source(source, &FormatOptions::default()).expect("formats");
+    assert_eq!(
+        formatted,
+        "listener http:Listener ep = new (9090);\n\
+         service /api/v1 on ep {\n\
+         \x20   resource function get users/[string id]() returns string {\n\
+         \x20       return id;\n\
+         \x20   }\n\
+         \x20   resource function get [string... rest]() returns int {\n\
+         \x20       return 0;\n\
+         \x20   }\n\
+         \x20   resource function get .() returns int {\n\
+         \x20       return 1;\n\
+         \x20   }\n\
+         }\n\
+         service / on ep {}\n"
+    );
+
+    let (tree, _) = syntax_tree(&formatted).expect("lexes");
+    assert_eq!(tree.text(), formatted);
+}
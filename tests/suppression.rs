@@ -0,0 +1,115 @@
+use blazelint::errors::{DiagnosticKind, Severity};
+use blazelint::report::{sarif_report, FileDiagnostics};
+use blazelint::{lint_source, LintOptions, LintReport};
+
+fn lint(source: &str) -> LintReport {
+    lint_source(source, "test.bal", &LintOptions::default())
+}
+
+fn messages(report: &LintReport) -> Vec<&str> {
+    report
+        .diagnostics
+        .iter()
+        .map(|d| d.message.as_str())
+        .collect()
+}
+
+#[test]
+fn disable_next_line_silences_the_named_rule() {
+    let report = lint(
+        "// blazelint-disable-next-line camel_case -- generated code\nint bad_name = 1;\nint other_name = 2;\n",
+    );
+    assert_eq!(
+        messages(&report),
+        ["Variable \"other_name\" is not in camelCase."]
+    );
+    assert_eq!(report.suppressed.len(), 1);
+    let suppression = report.suppressed[0].suppression.as_ref().expect("marked");
+    assert_eq!(suppression.justification.as_deref(), Some("generated code"));
+}
+
+#[test]
+fn disable_line_applies_to_its_own_line() {
+    let report = lint("int bad_name = 1; // blazelint-disable-line\nint goodName = 2;\n");
+    assert!(report.is_clean(), "{:?}", report.diagnostics);
+    assert_eq!(report.suppressed.len(), 1);
+}
+
+#[test]
+fn disable_and_enable_bound_a_region() {
+    let source = "/* blazelint-disable camel_case, constant_case */\nint bad_one = 1;\nconst lowerConst = 1;\n\
+                  /* blazelint-enable camel_case */\nint bad_two = 2;\nconst otherConst = 2;\n";
+    let report = lint(source);
+    assert_eq!(
+        messages(&report),
+        ["Variable \"bad_two\" is not in camelCase."]
+    );
+    assert_eq!(report.suppressed.len(), 3);
+}
+
+#[test]
+fn unused_and_unknown_suppressions_are_reported() {
+    let source = "// blazelint-disable-next-line camel_case, camelcase\nint goodName = 1;\n";
+    let report = lint(source);
+    let warnings: Vec<_> = report
+        .diagnostics
+        .iter()
+        .filter(|d| matches!(d.kind, DiagnosticKind::Suppression))
+        .collect();
+    assert_eq!(warnings.len(), 2, "{:?}", report.diagnostics);
+    assert!(warnings.iter().all(|d| d.severity == Severity::Warning));
+
+    assert_eq!(
+        warnings[0].message,
+        "Unknown rule 'camelcase' in suppression comment"
+    );
+    assert_eq!(&source[warnings[0].span.clone()], "camelcase");
+    assert_eq!(
+        warnings[1].message,
+        "Unused suppression: 'camel_case' is not reported here"
+    );
+    assert_eq!(&source[warnings[1].span.clone()], "camel_case");
+}
+
+#[test]
+fn misspelled_directives_and_stray_enables_are_reported() {
+    let report = lint("// blazelint-disable-nextline\n// blazelint-enable\nint goodName = 1;\n");
+    assert_eq!(
+        messages(&report),
+        [
+            "Unknown suppression directive 'blazelint-disable-nextline'",
+            "'blazelint-enable' does not match an earlier 'blazelint-disable'",
+        ]
+    );
+}
+
+#[test]
+fn suppressions_do_not_hide_compiler_errors() {
+    let report = lint("// blazelint-disable-next-line\nint x = \"text\";\n");
+    assert!(report.has_errors());
+    assert!(report
+        .diagnostics
+        .iter()
+        .any(|d| d.message.starts_with("Unused suppression")));
+}
+
+#[test]
+fn sarif_marks_suppressed_results() {
+    let source = "int bad_name = 1; // blazelint-disable-line camel_case -- legacy\n";
+    let report = lint(source);
+    let mut diagnostics = report.diagnostics.clone();
+    diagnostics.extend(report.suppressed.iter().cloned());
+    let sarif = sarif_report(
+        &[FileDiagnostics {
+            file_name: "test.bal",
+            source,
+            diagnostics: &diagnostics,
+        }],
+        &blazelint::linter::default_rules(),
+    )
+    .to_string();
+    assert!(
+        sarif.contains("\"suppressions\":[{\"kind\":\"inSource\",\"justification\":\"legacy\"}]"),
+        "{sarif}"
+    );
+}
@@ -0,0 +1,59 @@
+use blazelint::lexer::{merge_trivia, CommentKind, Element, TriviaKind};
+use blazelint::{lint_source, LintOptions};
+
+#[test]
+fn tokens_and_trivia_cover_the_source() {
+    let source =
+        "# Adds one.\r\nfunction f(int n) returns int {\n\treturn n + 1; // done\n  /* end */\n}\n";
+    let report = lint_source(source, "test.bal", &LintOptions::default());
+    assert!(report.is_clean(), "{:?}", report.diagnostics);
+
+    let elements = merge_trivia(&report.tokens, &report.trivia);
+    let mut rebuilt = String::new();
+    let mut cursor = 0;
+    for element in &elements {
+        let span = element.span();
+        assert_eq!(span.start, cursor, "gap or overlap before {element:?}");
+        rebuilt.push_str(&source[span.clone()]);
+        cursor = span.end;
+    }
+    assert_eq!(rebuilt, source);
+
+    let kinds: Vec<TriviaKind> = report.trivia.iter().map(|trivia| trivia.kind).collect();
+    assert_eq!(
+        &kinds[..3],
+        [
+            TriviaKind::Comment(CommentKind::Doc),
+            TriviaKind::Newline,
+            TriviaKind::Whitespace,
+        ]
+    );
+    assert_eq!(&source[report.trivia[1].span.clone()], "\r\n");
+    assert!(matches!(elements[2], Element::Token(_)));
+
+    let comments: Vec<&str> = report
+        .comments
+        .iter()
+        .map(|comment| comment.text(source))
+        .collect();
+    assert_eq!(comments, [" Adds one.", " done", " end "]);
+}
+
+#[test]
+fn line_length_checks_lines_outside_statements() {
+    let long_comment = format!("// {}", "x".repeat(130));
+    let source = format!("int a = 1;\n{long_comment}\nint b = 2;\n");
+    let report = lint_source(&source, "test.bal", &LintOptions::default());
+
+    assert_eq!(report.diagnostics.len(), 1, "{:?}", report.diagnostics);
+    let diagnostic = &report.diagnostics[0];
+    assert_eq!(diagnostic.rule.as_deref(), Some("line_length"));
+    assert_eq!(diagnostic.span, 11..11 + long_comment.len());
+}
+
+#[test]
+fn line_length_counts_characters_not_bytes() {
+    let source = format!("string s = \"{}\";\n", "é".repeat(100));
+    let report = lint_source(&source, "test.bal", &LintOptions::default());
+    assert!(report.is_clean(), "{:?}", report.diagnostics);
+}
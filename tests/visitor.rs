@@ -0,0 +1,136 @@
+use blazelint::ast::{walk, Expr, Node, NodeKind, Stmt, VisitContext, Visitor};
+use blazelint::{lint_source, parse, tokenize, LintOptions};
+
+fn parse_program(source: &str) -> Vec<Stmt> {
+    let tokens = tokenize(source).expect("tokens");
+    let (ast, diagnostics) = parse(&tokens);
+    assert!(diagnostics.is_empty(), "{diagnostics:?}");
+    ast
+}
+
+/// Records each hook as `enter`/`exit`, the node kind, and the scope depth.
+#[derive(Default)]
+struct Recorder {
+    events: Vec<String>,
+}
+
+impl<'a> Visitor<'a> for Recorder {
+    fn enter_stmt(&mut self, stmt: &'a Stmt, cx: &VisitContext<'a>) {
+        self.events
+            .push(format!("enter {:?} @{}", stmt.kind(), cx.scope_depth()));
+    }
+
+    fn exit_stmt(&mut self, stmt: &'a Stmt, _cx: &VisitContext<'a>) {
+        self.events.push(format!("exit {:?}", stmt.kind()));
+    }
+
+    fn enter_expr(&mut self, expr: &'a Expr, cx: &VisitContext<'a>) {
+        self.events
+            .push(format!("enter {:?} @{}", expr.kind(), cx.scope_depth()));
+    }
+}
+
+#[test]
+fn walk_visits_nested_nodes_in_source_order() {
+    let program = parse_program(
+        "function f(int n) returns int {\n\
+         if (n > 0) { int x = n; } else { return 0; }\n\
+         return n;\n\
+         }",
+    );
+    let mut recorder = Recorder::default();
+    walk(&program, &mut recorder);
+
+    assert_eq!(
+        recorder.events,
+        [
+            "enter Function @0",
+            "enter If @1",
+            "enter Binary @1",
+            "enter Variable @1",
+            "enter Literal @1",
+            "enter VarDecl @2",
+            "enter Variable @2",
+            "exit VarDecl",
+            "enter Return @2",
+            "enter Literal @2",
+            "exit Return",
+            "exit If",
+            "enter Return @1",
+            "enter Variable @1",
+            "exit Return",
+            "exit Function",
+        ]
+    );
+}
+
+/// Collects, for every variable reference, its parent kind and the name of
+/// the enclosing function.
+#[derive(Default)]
+struct Parents {
+    found: Vec<(String, NodeKind, Option<String>, bool)>,
+}
+
+impl<'a> Visitor<'a> for Parents {
+    fn enter_expr(&mut self, expr: &'a Expr, cx: &VisitContext<'a>) {
+        if let Expr::Variable { name, .. } = expr {
+            let function = cx.enclosing_function().map(|stmt| match stmt {
+                Stmt::Function { name, .. } => name.clone(),
+                _ => unreachable!(),
+            });
+            let in_while = matches!(cx.scope(), Some(Stmt::While { .. }));
+            let parent = cx.parent().map(|node| node.kind()).expect("has a parent");
+            self.found.push((name.clone(), parent, function, in_while));
+        }
+    }
+}
+
+#[test]
+fn visit_context_reports_parents_and_scopes() {
+    let program = parse_program(
+        "int top = 1;\n\
+         int copy = top;\n\
+         function g() { while (top > 0) { print(top); } }",
+    );
+    let mut parents = Parents::default();
+    walk(&program, &mut parents);
+
+    let g = Some("g".to_string());
+    assert_eq!(
+        parents.found,
+        [
+            ("top".to_string(), NodeKind::VarDecl, None, false),
+            ("top".to_string(), NodeKind::Binary, g.clone(), false),
+            ("print".to_string(), NodeKind::Call, g.clone(), true),
+            ("top".to_string(), NodeKind::Call, g, true),
+        ]
+    );
+    assert!(matches!(Node::Stmt(&program[0]).kind(), NodeKind::VarDecl));
+    assert!(NodeKind::STATEMENTS.contains(&NodeKind::ExpressionStmt));
+    assert!(NodeKind::EXPRESSIONS.contains(&NodeKind::Cast));
+}
+
+#[test]
+fn rules_see_declarations_in_nested_blocks() {
+    let source = "function f() {\n\
+                  int outer_value = 1;\n\
+                  if (outer_value > 0) {\n\
+                  while (true) { int inner_value = 2; }\n\
+                  }\n\
+                  foreach int i in [1, 2] { int loop_value = i; }\n\
+                  }\n";
+    let report = lint_source(source, "nested.bal", &LintOptions::default());
+    let messages: Vec<_> = report
+        .diagnostics
+        .iter()
+        .map(|diagnostic| diagnostic.message.as_str())
+        .collect();
+    assert_eq!(
+        messages,
+        [
+            "Variable \"outer_value\" is not in camelCase.",
+            "Variable \"inner_value\" is not in camelCase.",
+            "Variable \"loop_value\" is not in camelCase.",
+        ]
+    );
+}